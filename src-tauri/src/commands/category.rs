@@ -1,9 +1,25 @@
-use crate::db::models::{Category, CategoryNode, CreateCategoryRequest, DeleteStrategy, UpdateCategoryRequest};
+use crate::db::models::{
+    Category, CategoryNode, CategoryStats, CreateCategoryRequest, DeleteStrategy, UpdateCategoryRequest,
+};
 use crate::error::Result;
-use crate::services::CategoryService;
+use crate::services::stats::DEFAULT_TOP_N;
+use crate::services::{CategoryService, PasswordService, StatsService};
 use crate::AppState;
 use tauri::State;
 
+/// 访问分类内容前检查路径上所有受密码保护的分类是否已解锁
+pub fn ensure_category_unlocked(state: &AppState, category_id: i64, token: Option<&str>) -> Result<()> {
+    let service = CategoryService::new(state.db.clone());
+    let protected = service.protected_category_chain(category_id)?;
+    state.sessions.require_categories(token, &protected)
+}
+
+/// 获取令牌无权访问的分类 ID，跨分类查询时用于排除受保护的内容
+pub fn locked_category_ids(state: &AppState, token: Option<&str>) -> Result<Vec<i64>> {
+    let service = CategoryService::new(state.db.clone());
+    service.locked_category_ids(&state.sessions.unlocked_categories(token))
+}
+
 #[tauri::command]
 pub fn create_category(
     state: State<AppState>,
@@ -18,18 +34,30 @@ pub fn update_category(
     state: State<AppState>,
     id: i64,
     req: UpdateCategoryRequest,
+    token: Option<String>,
 ) -> Result<Category> {
+    ensure_category_unlocked(&state, id, token.as_deref())?;
     let service = CategoryService::new(state.db.clone());
     service.update_category(id, req)
 }
 
+/// 删除分类，被删除的分类（按 DeleteAll 策略时包括所有子分类）及其上级中受保护的分类都需已解锁
 #[tauri::command]
 pub fn delete_category(
     state: State<AppState>,
     id: i64,
     strategy: DeleteStrategy,
+    token: Option<String>,
 ) -> Result<()> {
     let service = CategoryService::new(state.db.clone());
+    for category_id in service.categories_removed_by_delete(id, &strategy)? {
+        ensure_category_unlocked(&state, category_id, token.as_deref())?;
+    }
+
+    // 绑定会随分类级联删除，先停止其目录监听，避免监听线程继续处理已不存在的绑定
+    for binding_id in service.bindings_removed_by_delete(id, &strategy)? {
+        state.watchers.unwatch(binding_id);
+    }
     service.delete_category(id, strategy)
 }
 
@@ -39,11 +67,77 @@ pub fn get_category_tree(state: State<AppState>) -> Result<Vec<CategoryNode>> {
     service.get_category_tree()
 }
 
+/// 获取分类（未指定时为全部分类）的磁盘占用统计，未解锁的分类不计入
+#[tauri::command]
+pub fn get_category_stats(
+    state: State<AppState>,
+    category_id: Option<i64>,
+    top_n: Option<usize>,
+    token: Option<String>,
+) -> Result<CategoryStats> {
+    if let Some(category_id) = category_id {
+        ensure_category_unlocked(&state, category_id, token.as_deref())?;
+    }
+    let excluded = locked_category_ids(&state, token.as_deref())?;
+
+    let service = StatsService::new(state.db.clone());
+    service.get_category_stats(category_id, top_n.unwrap_or(DEFAULT_TOP_N), &excluded)
+}
+
 #[tauri::command]
 pub fn reorder_categories(
     state: State<AppState>,
     orders: Vec<(i64, i32)>,
+    token: Option<String>,
 ) -> Result<()> {
+    for (id, _) in &orders {
+        ensure_category_unlocked(&state, *id, token.as_deref())?;
+    }
     let service = CategoryService::new(state.db.clone());
     service.reorder_categories(orders)
 }
+
+#[tauri::command]
+pub fn set_category_password(
+    state: State<AppState>,
+    id: i64,
+    password: String,
+    current_password: Option<String>,
+) -> Result<()> {
+    let service = PasswordService::new(state.db.clone());
+    service.set_category_password(id, &password, current_password.as_deref())?;
+
+    // 已有会话是用旧密码解锁的，需要用新密码重新解锁
+    state.sessions.lock_category(id);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn verify_category_password(
+    state: State<AppState>,
+    id: i64,
+    password: String,
+    token: Option<String>,
+) -> Result<Option<String>> {
+    let service = PasswordService::new(state.db.clone());
+
+    // 校验成功时返回解锁令牌，失败时返回 None
+    if service.verify_category_password(id, &password)? {
+        Ok(Some(state.sessions.unlock_category(token.as_deref(), id)))
+    } else {
+        Ok(None)
+    }
+}
+
+#[tauri::command]
+pub fn clear_category_password(
+    state: State<AppState>,
+    id: i64,
+    password: String,
+) -> Result<()> {
+    let service = PasswordService::new(state.db.clone());
+    service.clear_category_password(id, &password)?;
+
+    state.sessions.lock_category(id);
+    Ok(())
+}
@@ -1,8 +1,10 @@
-use crate::db::models::{DirectoryBinding, ResourceItem};
+use crate::commands::category::ensure_category_unlocked;
+use crate::db::models::{DirectoryBinding, ResourceItem, ScanSummary, UpdateBindingRequest};
 use crate::error::Result;
+use crate::services::scan_job::ScanJobStatus;
 use crate::services::DirectoryService;
 use crate::AppState;
-use tauri::State;
+use tauri::{AppHandle, State};
 use std::process::Command;
 use rfd::AsyncFileDialog;
 use serde::{Deserialize, Serialize};
@@ -24,19 +26,67 @@ pub fn bind_directory(
     state: State<AppState>,
     category_id: i64,
     path: String,
+    token: Option<String>,
 ) -> Result<DirectoryBinding> {
+    ensure_category_unlocked(&state, category_id, token.as_deref())?;
     let service = DirectoryService::new(state.db.clone());
     service.bind_directory(category_id, path)
 }
 
 #[tauri::command]
-pub fn unbind_directory(state: State<AppState>, binding_id: i64) -> Result<()> {
+pub fn unbind_directory(state: State<AppState>, binding_id: i64, token: Option<String>) -> Result<()> {
     let service = DirectoryService::new(state.db.clone());
+    let binding = service.get_binding(binding_id)?;
+    ensure_category_unlocked(&state, binding.category_id, token.as_deref())?;
+
+    state.watchers.unwatch(binding_id);
     service.unbind_directory(binding_id)
 }
 
+/// 更新绑定目录的扫描设置（隐藏文件、忽略规则、符号链接、最大深度），下次扫描时生效
+#[tauri::command]
+pub fn update_binding(
+    state: State<AppState>,
+    binding_id: i64,
+    req: UpdateBindingRequest,
+    token: Option<String>,
+) -> Result<DirectoryBinding> {
+    let service = DirectoryService::new(state.db.clone());
+    let binding = service.get_binding(binding_id)?;
+    ensure_category_unlocked(&state, binding.category_id, token.as_deref())?;
+
+    service.update_binding(binding_id, req)
+}
+
+/// 开启或关闭绑定目录的实时监听，变化会通过 resources://changed 事件推送
+#[tauri::command]
+pub fn set_binding_watch(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    binding_id: i64,
+    enabled: bool,
+    token: Option<String>,
+) -> Result<DirectoryBinding> {
+    let service = DirectoryService::new(state.db.clone());
+    let binding = service.get_binding(binding_id)?;
+    ensure_category_unlocked(&state, binding.category_id, token.as_deref())?;
+
+    if enabled {
+        state.watchers.watch(app_handle, state.db.clone(), &binding)?;
+    } else {
+        state.watchers.unwatch(binding_id);
+    }
+
+    service.set_binding_watch(binding_id, enabled)
+}
+
 #[tauri::command]
-pub fn get_bindings(state: State<AppState>, category_id: i64) -> Result<Vec<DirectoryBinding>> {
+pub fn get_bindings(
+    state: State<AppState>,
+    category_id: i64,
+    token: Option<String>,
+) -> Result<Vec<DirectoryBinding>> {
+    ensure_category_unlocked(&state, category_id, token.as_deref())?;
     let service = DirectoryService::new(state.db.clone());
     service.get_bindings(category_id)
 }
@@ -47,29 +97,71 @@ pub struct ScanProgress {
     pub scanned_files: i64,
     pub current_batch: Vec<ResourceItem>,
     pub is_complete: bool,
+    pub summary: Option<ScanSummary>,
 }
 
 #[tauri::command]
 pub fn scan_directory(
     state: State<AppState>,
     category_id: i64,
-    show_hidden: bool,
-    ignore_directories: Option<String>,
+    token: Option<String>,
 ) -> Result<Vec<ResourceItem>> {
+    ensure_category_unlocked(&state, category_id, token.as_deref())?;
     let service = DirectoryService::new(state.db.clone());
-    service.scan_directory(category_id, show_hidden, ignore_directories)
+    let (resources, _) = service.scan_directory(category_id)?;
+    Ok(resources)
+}
+
+/// 重新扫描分类绑定的目录，只返回增量统计
+#[tauri::command]
+pub fn rescan_directory(
+    state: State<AppState>,
+    category_id: i64,
+    token: Option<String>,
+) -> Result<ScanSummary> {
+    ensure_category_unlocked(&state, category_id, token.as_deref())?;
+    let service = DirectoryService::new(state.db.clone());
+    let (_, summary) = service.scan_directory(category_id)?;
+    Ok(summary)
 }
 
 #[tauri::command]
 pub fn scan_directory_batch(
     state: State<AppState>,
     category_id: i64,
-    show_hidden: bool,
-    ignore_directories: Option<String>,
     batch_size: Option<i32>,
+    token: Option<String>,
 ) -> Result<ScanProgress> {
+    ensure_category_unlocked(&state, category_id, token.as_deref())?;
     let service = DirectoryService::new(state.db.clone());
-    service.scan_directory_batch(category_id, show_hidden, ignore_directories, batch_size.unwrap_or(1000))
+    service.scan_directory_batch(category_id, batch_size.unwrap_or(1000))
+}
+
+/// 启动后台扫描任务，进度通过 scan://progress 事件推送
+#[tauri::command]
+pub fn start_scan_job(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    category_id: i64,
+    token: Option<String>,
+) -> Result<String> {
+    ensure_category_unlocked(&state, category_id, token.as_deref())?;
+    state.scan_jobs.start(app_handle, state.db.clone(), category_id)
+}
+
+/// 获取扫描任务状态，分类受密码保护时需要令牌仍已解锁该分类
+#[tauri::command]
+pub fn get_scan_job(state: State<AppState>, job_id: String, token: Option<String>) -> Result<ScanJobStatus> {
+    let status = state.scan_jobs.get(&job_id)?;
+    ensure_category_unlocked(&state, status.category_id, token.as_deref())?;
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn cancel_scan_job(state: State<AppState>, job_id: String, token: Option<String>) -> Result<()> {
+    let status = state.scan_jobs.get(&job_id)?;
+    ensure_category_unlocked(&state, status.category_id, token.as_deref())?;
+    state.scan_jobs.cancel(&job_id)
 }
 
 #[tauri::command]
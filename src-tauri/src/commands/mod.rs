@@ -14,6 +14,10 @@ pub mod system;
 pub mod env_var;
 pub mod shortcuts;
 pub mod cards;
+pub mod session;
+pub mod resource;
+pub mod slideshow;
+pub mod thumbnail;
 
 pub use category::*;
 pub use directory::*;
@@ -22,3 +26,7 @@ pub use wallpaper::*;
 pub use system::*;
 pub use env_var::*;
 pub use shortcuts::*;
+pub use session::*;
+pub use resource::*;
+pub use slideshow::*;
+pub use thumbnail::*;
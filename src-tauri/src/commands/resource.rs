@@ -0,0 +1,188 @@
+use crate::commands::category::{ensure_category_unlocked, locked_category_ids};
+use crate::db::models::{
+    FileKind, ResolveDuplicatesRequest, ResolveDuplicatesResult, ResourceMetadata, ResourcePage,
+    ResourceSort, SearchResourcesRequest, SearchResourcesResult,
+};
+use crate::error::{AppError, Result};
+use crate::services::duplicate::DuplicateJobStatus;
+use crate::services::metadata::MetadataJobStatus;
+use crate::services::thumbnail::ThumbnailOutput;
+use crate::services::{DuplicateService, MetadataService, ResourceService};
+use crate::AppState;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// 搜索已扫描的资源，未解锁的受保护分类不会出现在结果中
+#[tauri::command]
+pub fn search_resources(
+    state: State<AppState>,
+    request: SearchResourcesRequest,
+    token: Option<String>,
+) -> Result<SearchResourcesResult> {
+    if let Some(category_id) = request.category_id {
+        ensure_category_unlocked(&state, category_id, token.as_deref())?;
+    }
+    let excluded = locked_category_ids(&state, token.as_deref())?;
+
+    let service = ResourceService::new(state.db.clone());
+    service.search_resources(&request, &excluded)
+}
+
+/// 从数据库分页读取分类下的资源，不访问磁盘；page 为上一页返回的 next_page
+#[tauri::command]
+pub fn list_resources(
+    state: State<AppState>,
+    category_id: i64,
+    include_descendants: bool,
+    sort: Option<ResourceSort>,
+    page: Option<String>,
+    page_size: Option<u32>,
+    token: Option<String>,
+) -> Result<ResourcePage> {
+    ensure_category_unlocked(&state, category_id, token.as_deref())?;
+    let excluded = if include_descendants {
+        locked_category_ids(&state, token.as_deref())?
+    } else {
+        Vec::new()
+    };
+
+    let service = ResourceService::new(state.db.clone());
+    service.list_resources(
+        category_id,
+        include_descendants,
+        &excluded,
+        sort.unwrap_or_default(),
+        page.as_deref(),
+        page_size,
+    )
+}
+
+/// 启动后台重复文件检测任务，进度通过 duplicates://progress 事件推送，未解锁的分类不参与检测
+#[tauri::command]
+pub fn start_duplicate_job(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    token: Option<String>,
+) -> Result<String> {
+    let excluded = locked_category_ids(&state, token.as_deref())?;
+    state.duplicate_jobs.start(app_handle, state.db.clone(), excluded)
+}
+
+/// 获取重复文件检测任务状态，任务检测过当前令牌未解锁的分类时返回 Locked
+#[tauri::command]
+pub fn get_duplicate_job(
+    state: State<AppState>,
+    job_id: String,
+    token: Option<String>,
+) -> Result<DuplicateJobStatus> {
+    let locked = locked_category_ids(&state, token.as_deref())?;
+    state.duplicate_jobs.get(&job_id, &locked)
+}
+
+#[tauri::command]
+pub fn cancel_duplicate_job(state: State<AppState>, job_id: String, token: Option<String>) -> Result<()> {
+    let locked = locked_category_ids(&state, token.as_deref())?;
+    state.duplicate_jobs.cancel(&job_id, &locked)
+}
+
+/// 删除、移入回收站或硬链接替换重复文件
+#[tauri::command]
+pub fn resolve_duplicates(
+    state: State<AppState>,
+    request: ResolveDuplicatesRequest,
+    token: Option<String>,
+) -> Result<ResolveDuplicatesResult> {
+    let service = DuplicateService::new(state.db.clone());
+
+    let resource_ids: Vec<i64> = request
+        .groups
+        .iter()
+        .flat_map(|group| std::iter::once(group.keep_id).chain(group.duplicate_ids.iter().copied()))
+        .collect();
+    for category_id in service.resource_category_ids(&resource_ids)? {
+        ensure_category_unlocked(&state, category_id, token.as_deref())?;
+    }
+
+    service.resolve_duplicates(&request)
+}
+
+/// 启动后台媒体元数据提取任务，只处理尚未提取的图片、音频、视频资源；
+/// 未指定分类时处理所有已解锁的分类，进度通过 metadata://progress 事件推送
+#[tauri::command]
+pub fn start_metadata_job(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    category_id: Option<i64>,
+    token: Option<String>,
+) -> Result<String> {
+    if let Some(category_id) = category_id {
+        ensure_category_unlocked(&state, category_id, token.as_deref())?;
+    }
+    let excluded = locked_category_ids(&state, token.as_deref())?;
+    state
+        .metadata_jobs
+        .start(app_handle, state.db.clone(), category_id, excluded)
+}
+
+/// 获取媒体元数据提取任务状态，任务处理过当前令牌未解锁的分类时返回 Locked
+#[tauri::command]
+pub fn get_metadata_job(
+    state: State<AppState>,
+    job_id: String,
+    token: Option<String>,
+) -> Result<MetadataJobStatus> {
+    let locked = locked_category_ids(&state, token.as_deref())?;
+    state.metadata_jobs.get(&job_id, &locked)
+}
+
+#[tauri::command]
+pub fn cancel_metadata_job(state: State<AppState>, job_id: String, token: Option<String>) -> Result<()> {
+    let locked = locked_category_ids(&state, token.as_deref())?;
+    state.metadata_jobs.cancel(&job_id, &locked)
+}
+
+/// 获取资源的媒体元数据，尚未提取时返回 null
+#[tauri::command]
+pub fn get_resource_metadata(
+    state: State<AppState>,
+    resource_id: i64,
+    token: Option<String>,
+) -> Result<Option<ResourceMetadata>> {
+    let service = MetadataService::new(state.db.clone());
+    let category_id = service.resource_category_id(resource_id)?;
+    ensure_category_unlocked(&state, category_id, token.as_deref())?;
+
+    service.get_metadata(resource_id)
+}
+
+/// 获取图片资源的缩略图，缩放到 width × height 以内；output 默认为 data URL
+#[tauri::command]
+pub async fn get_resource_thumbnail(
+    state: State<'_, AppState>,
+    resource_id: i64,
+    width: u32,
+    height: u32,
+    output: Option<ThumbnailOutput>,
+    token: Option<String>,
+) -> Result<String> {
+    let resource = ResourceService::new(state.db.clone()).get_resource(resource_id)?;
+    ensure_category_unlocked(&state, resource.category_id, token.as_deref())?;
+    if resource.kind != FileKind::Image {
+        return Err(AppError::InvalidInput(format!(
+            "Resource is not an image: {}",
+            resource.file_name
+        )));
+    }
+
+    let thumbnails = state.thumbnails.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        thumbnails.get(
+            Path::new(&resource.file_path),
+            width,
+            height,
+            output.unwrap_or_default(),
+        )
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+}
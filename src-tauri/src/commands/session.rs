@@ -0,0 +1,27 @@
+use crate::error::Result;
+use crate::AppState;
+use tauri::State;
+
+/// 主动锁定会话，未传令牌时锁定所有会话
+#[tauri::command]
+pub fn lock_session(state: State<AppState>, token: Option<String>) -> Result<()> {
+    match token {
+        Some(token) => state.sessions.lock(&token),
+        None => state.sessions.lock_all(),
+    }
+    Ok(())
+}
+
+/// 获取空闲自动锁定时间（分钟），0 表示不自动锁定
+#[tauri::command]
+pub fn get_auto_lock_timeout(state: State<AppState>) -> Result<u64> {
+    Ok(state.sessions.idle_timeout_minutes())
+}
+
+/// 设置空闲自动锁定时间（分钟），0 表示不自动锁定
+#[tauri::command]
+pub fn set_auto_lock_timeout(state: State<AppState>, minutes: u64) -> Result<()> {
+    state.db.set_auto_lock_minutes(minutes)?;
+    state.sessions.set_idle_timeout_minutes(minutes);
+    Ok(())
+}
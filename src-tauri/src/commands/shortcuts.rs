@@ -1,9 +1,11 @@
 use tauri::{AppHandle, Manager};
 use uuid::Uuid;
+use rusqlite::OptionalExtension;
 use std::time::{SystemTime, UNIX_EPOCH};
 use crate::models::shortcuts::{QuickAccessItem, CreateQuickAccessDto, UpdateQuickAccessDto};
+use crate::services::vault::VaultKey;
+use crate::services::PasswordService;
 use crate::AppState;
-use rusqlite::OptionalExtension;
 use reqwest::header::USER_AGENT;
 use scraper::{Html, Selector};
 
@@ -83,8 +85,16 @@ pub async fn fetch_website_metadata(url: String) -> Result<WebsiteMetadata, Stri
 pub async fn get_shortcuts(
     app_handle: AppHandle,
     include_hidden: bool,
+    token: Option<String>,
 ) -> Result<Vec<QuickAccessItem>, String> {
     let state = app_handle.state::<AppState>();
+
+    // 隐藏项需要已解锁的会话；未解锁时加密项的内容会被脱敏，解锁后解密返回
+    if include_hidden {
+        state.sessions.require_global(token.as_deref()).map_err(|e| e.to_string())?;
+    }
+    let key = state.sessions.global_key(token.as_deref()).ok();
+
     let db = &state.db;
     let conn = db.get_connection();
     let conn = conn.lock().unwrap();
@@ -114,7 +124,17 @@ pub async fn get_shortcuts(
 
     let mut items = Vec::new();
     for row in rows {
-        items.push(row.map_err(|e| e.to_string())?);
+        let mut item = row.map_err(|e| e.to_string())?;
+        if item.encrypted {
+            match &key {
+                Some(key) => open_item(key, &mut item)?,
+                None => {
+                    item.target = String::new();
+                    item.description = None;
+                }
+            }
+        }
+        items.push(item);
     }
 
     Ok(items)
@@ -124,8 +144,18 @@ pub async fn get_shortcuts(
 pub async fn create_shortcut(
     app_handle: AppHandle,
     dto: CreateQuickAccessDto,
+    token: Option<String>,
 ) -> Result<QuickAccessItem, String> {
     let state = app_handle.state::<AppState>();
+
+    // 加密项以密文落库，需要已解锁的会话提供密钥
+    let (target, description) = if dto.encrypted {
+        let key = state.sessions.global_key(token.as_deref()).map_err(|e| e.to_string())?;
+        seal_fields(&key, &dto.target, &dto.description)?
+    } else {
+        (dto.target.clone(), dto.description.clone())
+    };
+
     let db = &state.db;
     let conn = db.get_connection();
     let conn = conn.lock().unwrap();
@@ -141,14 +171,14 @@ pub async fn create_shortcut(
         (
             &id,
             &dto.name,
-            &dto.target,
+            &target,
             &dto.kind,
             &dto.icon,
             dto.encrypted,
             dto.hidden,
             now,
             now,
-            &dto.description,
+            &description,
         ),
     ).map_err(|e| e.to_string())?;
 
@@ -171,6 +201,7 @@ pub async fn update_shortcut(
     app_handle: AppHandle,
     id: String,
     dto: UpdateQuickAccessDto,
+    token: Option<String>,
 ) -> Result<QuickAccessItem, String> {
     let state = app_handle.state::<AppState>();
     let db = &state.db;
@@ -205,6 +236,23 @@ pub async fn update_shortcut(
         })
     }).map_err(|e| e.to_string())?;
 
+    // 隐藏或加密项需要已解锁的会话，否则会泄露其内容
+    if item.hidden || item.encrypted {
+        state.sessions.require_global(token.as_deref()).map_err(|e| e.to_string())?;
+    }
+
+    // 涉及加密项（加密前或加密后）时需要密钥：先解密旧值，保存时重新加密
+    let key = if item.encrypted || dto.encrypted == Some(true) {
+        Some(state.sessions.global_key(token.as_deref()).map_err(|e| e.to_string())?)
+    } else {
+        None
+    };
+    if item.encrypted {
+        if let Some(key) = &key {
+            open_item(key, &mut item)?;
+        }
+    }
+
     // Apply updates
     if let Some(v) = dto.name { item.name = v; }
     if let Some(v) = dto.target { item.target = v; }
@@ -214,17 +262,22 @@ pub async fn update_shortcut(
     if let Some(v) = dto.hidden { item.hidden = v; }
     item.updated_at = now;
 
+    let (target, description) = match (&key, item.encrypted) {
+        (Some(key), true) => seal_fields(key, &item.target, &item.description)?,
+        _ => (item.target.clone(), item.description.clone()),
+    };
+
     // Save back
     conn.execute(
         "UPDATE shortcuts SET name = ?1, target = ?2, icon = ?3, encrypted = ?4, hidden = ?5, updated_at = ?6, description = ?7 WHERE id = ?8",
         (
             &item.name,
-            &item.target,
+            &target,
             &item.icon,
             item.encrypted,
             item.hidden,
             item.updated_at,
-            &item.description,
+            &description,
             &id,
         ),
     ).map_err(|e| e.to_string())?;
@@ -236,12 +289,26 @@ pub async fn update_shortcut(
 pub async fn delete_shortcut(
     app_handle: AppHandle,
     id: String,
+    token: Option<String>,
 ) -> Result<(), String> {
     let state = app_handle.state::<AppState>();
     let db = &state.db;
     let conn = db.get_connection();
     let conn = conn.lock().unwrap();
 
+    // 隐藏或加密项需要已解锁的会话才能删除
+    let protected: Option<bool> = conn
+        .query_row(
+            "SELECT hidden OR encrypted FROM shortcuts WHERE id = ?1",
+            [&id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    if protected == Some(true) {
+        state.sessions.require_global(token.as_deref()).map_err(|e| e.to_string())?;
+    }
+
     conn.execute("DELETE FROM shortcuts WHERE id = ?1", [&id])
         .map_err(|e| e.to_string())?;
 
@@ -270,17 +337,16 @@ pub async fn open_shortcut(
 pub async fn set_global_password(
     app_handle: AppHandle,
     password: String,
+    current_password: Option<String>,
 ) -> Result<(), String> {
     let state = app_handle.state::<AppState>();
-    let db = &state.db;
-    let conn = db.get_connection();
-    let conn = conn.lock().unwrap();
-
-    conn.execute(
-        "INSERT OR REPLACE INTO app_settings (key, value, updated_at) VALUES ('global_password', ?1, CURRENT_TIMESTAMP)",
-        [&password],
-    ).map_err(|e| e.to_string())?;
+    let service = PasswordService::new(state.db.clone());
+    service
+        .set_global_password(&password, current_password.as_deref())
+        .map_err(|e| e.to_string())?;
 
+    // 快捷方式已用新密钥重新加密，现有会话持有的旧密钥无法再解密或加密，全部锁定后需重新解锁
+    state.sessions.lock_all();
     Ok(())
 }
 
@@ -288,18 +354,15 @@ pub async fn set_global_password(
 pub async fn verify_password(
     app_handle: AppHandle,
     password: String,
-) -> Result<bool, String> {
+    token: Option<String>,
+) -> Result<Option<String>, String> {
     let state = app_handle.state::<AppState>();
-    let db = &state.db;
-    let conn = db.get_connection();
-    let conn = conn.lock().unwrap();
-
-    let mut stmt = conn.prepare("SELECT value FROM app_settings WHERE key = 'global_password'").map_err(|e| e.to_string())?;
-    let stored_password: Option<String> = stmt.query_row([], |row| row.get(0)).optional().map_err(|e| e.to_string())?;
+    let service = PasswordService::new(state.db.clone());
 
-    match stored_password {
-        Some(stored) => Ok(stored == password),
-        None => Ok(false), // No password set
+    // 校验成功时派生加密密钥并返回解锁令牌，失败时返回 None
+    match service.unlock_global(&password).map_err(|e| e.to_string())? {
+        Some(key) => Ok(Some(state.sessions.unlock_global(token.as_deref(), key))),
+        None => Ok(None),
     }
 }
 
@@ -308,12 +371,29 @@ pub async fn has_password(
     app_handle: AppHandle,
 ) -> Result<bool, String> {
     let state = app_handle.state::<AppState>();
-    let db = &state.db;
-    let conn = db.get_connection();
-    let conn = conn.lock().unwrap();
+    let service = PasswordService::new(state.db.clone());
+    service.has_global_password().map_err(|e| e.to_string())
+}
 
-    let mut stmt = conn.prepare("SELECT 1 FROM app_settings WHERE key = 'global_password'").map_err(|e| e.to_string())?;
-    let exists = stmt.exists([]).map_err(|e| e.to_string())?;
+/// 解密加密快捷方式的 target 和 description
+fn open_item(key: &VaultKey, item: &mut QuickAccessItem) -> Result<(), String> {
+    item.target = key.open(&item.target).map_err(|e| e.to_string())?;
+    if let Some(description) = &item.description {
+        item.description = Some(key.open(description).map_err(|e| e.to_string())?);
+    }
+    Ok(())
+}
 
-    Ok(exists)
+/// 加密 target 和 description，返回待落库的密文
+fn seal_fields(
+    key: &VaultKey,
+    target: &str,
+    description: &Option<String>,
+) -> Result<(String, Option<String>), String> {
+    let target = key.seal(target).map_err(|e| e.to_string())?;
+    let description = match description {
+        Some(d) => Some(key.seal(d).map_err(|e| e.to_string())?),
+        None => None,
+    };
+    Ok((target, description))
 }
@@ -0,0 +1,60 @@
+use crate::error::{AppError, Result};
+use crate::services::slideshow::{SlideshowConfig, SlideshowStatus};
+use crate::AppState;
+use tauri::{AppHandle, State};
+
+/// 获取壁纸轮播状态
+#[tauri::command]
+pub fn get_slideshow_status(state: State<AppState>) -> Result<SlideshowStatus> {
+    Ok(state.slideshow.status())
+}
+
+/// 按配置开始轮播并立即切换到第一张壁纸
+#[tauri::command]
+pub async fn start_slideshow(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    config: SlideshowConfig,
+) -> Result<SlideshowStatus> {
+    let db = state.db.clone();
+    let slideshow = state.slideshow.clone();
+    tauri::async_runtime::spawn_blocking(move || slideshow.start(&app_handle, &db, config))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/// 停止轮播，配置会保留
+#[tauri::command]
+pub fn stop_slideshow(app_handle: AppHandle, state: State<AppState>) -> Result<SlideshowStatus> {
+    state.slideshow.stop(&app_handle, &state.db)
+}
+
+#[tauri::command]
+pub fn pause_slideshow(app_handle: AppHandle, state: State<AppState>) -> Result<SlideshowStatus> {
+    state.slideshow.pause(&app_handle, &state.db)
+}
+
+#[tauri::command]
+pub fn resume_slideshow(app_handle: AppHandle, state: State<AppState>) -> Result<SlideshowStatus> {
+    state.slideshow.resume(&app_handle, &state.db)
+}
+
+/// 立即切换到下一张壁纸，并从现在起重新计算切换间隔
+#[tauri::command]
+pub async fn slideshow_next(app_handle: AppHandle, state: State<'_, AppState>) -> Result<SlideshowStatus> {
+    let db = state.db.clone();
+    let slideshow = state.slideshow.clone();
+    tauri::async_runtime::spawn_blocking(move || slideshow.next(&app_handle, &db))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/// 切换回上一张壁纸
+#[tauri::command]
+pub async fn slideshow_previous(app_handle: AppHandle, state: State<'_, AppState>) -> Result<SlideshowStatus> {
+    let db = state.db.clone();
+    let slideshow = state.slideshow.clone();
+    tauri::async_runtime::spawn_blocking(move || slideshow.previous(&app_handle, &db))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+}
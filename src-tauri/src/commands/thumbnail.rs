@@ -0,0 +1,16 @@
+use crate::error::Result;
+use crate::services::thumbnail::ThumbnailCacheStats;
+use crate::AppState;
+use tauri::State;
+
+/// 获取缩略图缓存的占用情况
+#[tauri::command]
+pub fn get_thumbnail_cache_stats(state: State<AppState>) -> Result<ThumbnailCacheStats> {
+    Ok(state.thumbnails.stats())
+}
+
+/// 清空缩略图缓存，返回释放的字节数
+#[tauri::command]
+pub fn clear_thumbnail_cache(state: State<AppState>) -> Result<u64> {
+    state.thumbnails.clear()
+}
@@ -1,42 +1,72 @@
 use crate::error::{AppError, Result};
-use crate::services::WallpaperService;
+use crate::services::thumbnail::ThumbnailOutput;
+use crate::services::wallpaper::{LocalWallpaperPage, WallpaperFilter, WallpaperImportResult, WallpaperSort};
+use crate::services::wallpaper_dedup::{WallpaperDedupSettings, WallpaperDuplicateCluster};
+use crate::services::wallpaper_folder::{emit_changes, WallpaperFolder, WallpaperFolderInput, WallpaperFolderSyncResult};
+use crate::services::wallpaper_library::{WallpaperCollection, WallpaperCollectionInput, WallpaperTag};
+use crate::services::wallpaper_source::{WallpaperSourceConfig, WallpaperSourceSettings};
+use crate::services::{WallpaperDedupService, WallpaperFolderService, WallpaperLibraryService, WallpaperService};
 use crate::AppState;
-use tauri::State;
+use tauri::{AppHandle, State};
 
 /**
- * 获取本地壁纸列表
+ * 分页获取本地壁纸列表
  * @param state 应用状态
- * @returns 本地壁纸数组JSON字符串
+ * @param filter 筛选条件（可选），如按分辨率、标签、合集、收藏或评分筛选
+ * @param sort 排序方式（可选），默认按导入时间倒序
+ * @param page 页码（可选），从1开始
+ * @param page_size 每页数量（可选）
+ * @returns 本地壁纸分页响应
  */
 #[tauri::command]
-pub fn get_local_wallpapers(state: State<AppState>) -> Result<String> {
+pub fn get_local_wallpapers(
+    state: State<AppState>,
+    filter: Option<WallpaperFilter>,
+    sort: Option<WallpaperSort>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+) -> Result<LocalWallpaperPage> {
     let service = WallpaperService::new(state.db.clone());
-    service.get_local_wallpapers()
+    service.get_local_wallpapers(&filter.unwrap_or_default(), sort.unwrap_or_default(), page, page_size)
 }
 
 /**
  * 导入本地壁纸
+ * @description 与已有壁纸近似重复的图片按去重设置拒绝或合并
  * @param state 应用状态
  * @param file_paths 壁纸文件路径数组JSON字符串
- * @returns 导入的壁纸数组JSON字符串
+ * @returns 导入结果，包含导入的壁纸与近似重复的图片
  */
 #[tauri::command]
-pub fn import_wallpapers(state: State<AppState>, file_paths: String) -> Result<String> {
+pub async fn import_wallpapers(state: State<'_, AppState>, file_paths: String) -> Result<WallpaperImportResult> {
     let paths: Vec<String> = serde_json::from_str(&file_paths)?;
-    let service = WallpaperService::new(state.db.clone());
-    service.import_wallpapers(paths)
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let service = WallpaperService::new(db);
+        service.import_wallpapers(paths)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
 }
 
 /**
  * 从目录导入壁纸
  * @param state 应用状态
  * @param directory_path 目录路径
- * @returns 导入的壁纸数组JSON字符串
+ * @returns 导入结果，包含导入的壁纸与近似重复的图片
  */
 #[tauri::command]
-pub fn import_wallpapers_from_directory(state: State<AppState>, directory_path: String) -> Result<String> {
-    let service = WallpaperService::new(state.db.clone());
-    service.import_wallpapers_from_directory(directory_path)
+pub async fn import_wallpapers_from_directory(
+    state: State<'_, AppState>,
+    directory_path: String,
+) -> Result<WallpaperImportResult> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let service = WallpaperService::new(db);
+        service.import_wallpapers_from_directory(directory_path)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
 }
 
 /**
@@ -47,7 +77,7 @@ pub fn import_wallpapers_from_directory(state: State<AppState>, directory_path:
 #[tauri::command]
 pub fn delete_local_wallpaper(state: State<AppState>, wallpaper_id: String) -> Result<()> {
     let service = WallpaperService::new(state.db.clone());
-    service.delete_local_wallpaper(wallpaper_id)
+    service.delete_local_wallpaper(&state.thumbnails, wallpaper_id)
 }
 
 /**
@@ -59,26 +89,362 @@ pub fn delete_local_wallpaper(state: State<AppState>, wallpaper_id: String) -> R
 pub fn delete_local_wallpapers(state: State<AppState>, wallpaper_ids: String) -> Result<()> {
     let ids: Vec<String> = serde_json::from_str(&wallpaper_ids)?;
     let service = WallpaperService::new(state.db.clone());
-    service.delete_local_wallpapers(ids)
+    service.delete_local_wallpapers(&state.thumbnails, ids)
+}
+
+/**
+ * 补全已导入壁纸的宽高、文件大小与修改时间
+ * @param state 应用状态
+ * @returns 补全结果JSON字符串
+ */
+#[tauri::command]
+pub async fn backfill_wallpaper_dimensions(state: State<'_, AppState>) -> Result<String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let service = WallpaperService::new(db);
+        service.backfill_wallpaper_dimensions()
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
 }
 
 /**
  * 获取壁纸缩略图
  * @param state 应用状态
  * @param wallpaper_id 壁纸ID
- * @param width 缩略图宽度
- * @param height 缩略图高度
- * @returns 缩略图数据URL
+ * @param width 缩略图最大宽度
+ * @param height 缩略图最大高度
+ * @param output 返回形式（可选），默认为数据URL
+ * @returns 缩略图数据URL或缓存文件路径
  */
 #[tauri::command]
-pub fn get_wallpaper_thumbnail(
-    state: State<AppState>,
+pub async fn get_wallpaper_thumbnail(
+    state: State<'_, AppState>,
     wallpaper_id: String,
     width: u32,
     height: u32,
+    output: Option<ThumbnailOutput>,
 ) -> Result<String> {
-    let service = WallpaperService::new(state.db.clone());
-    service.get_wallpaper_thumbnail(wallpaper_id, width, height)
+    let db = state.db.clone();
+    let thumbnails = state.thumbnails.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let service = WallpaperService::new(db);
+        service.get_wallpaper_thumbnail(&thumbnails, wallpaper_id, width, height, output.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/**
+ * 获取壁纸去重设置
+ * @param state 应用状态
+ * @returns 去重设置
+ */
+#[tauri::command]
+pub fn get_wallpaper_dedup_settings(state: State<AppState>) -> Result<WallpaperDedupSettings> {
+    WallpaperDedupService::new(state.db.clone()).settings()
+}
+
+/**
+ * 保存壁纸去重设置
+ * @param state 应用状态
+ * @param settings 相似度阈值与导入时的处理方式
+ * @returns 保存后的设置
+ */
+#[tauri::command]
+pub fn save_wallpaper_dedup_settings(
+    state: State<AppState>,
+    settings: WallpaperDedupSettings,
+) -> Result<WallpaperDedupSettings> {
+    WallpaperDedupService::new(state.db.clone()).save_settings(&settings)
+}
+
+/**
+ * 查找壁纸库中的近似重复壁纸
+ * @description 尚未计算感知哈希的壁纸会先补算，壁纸较多时耗时较长
+ * @param state 应用状态
+ * @param similarity 相似度阈值（可选），默认使用去重设置中的值
+ * @returns 近似重复的壁纸分组
+ */
+#[tauri::command]
+pub async fn find_wallpaper_duplicates(
+    state: State<'_, AppState>,
+    similarity: Option<f64>,
+) -> Result<Vec<WallpaperDuplicateCluster>> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || WallpaperDedupService::new(db).find_clusters(similarity))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/**
+ * 获取本地壁纸标签列表
+ * @param state 应用状态
+ * @returns 标签数组，包含各标签的壁纸数量
+ */
+#[tauri::command]
+pub fn get_wallpaper_tags(state: State<AppState>) -> Result<Vec<WallpaperTag>> {
+    WallpaperLibraryService::new(state.db.clone()).list_tags()
+}
+
+/**
+ * 替换壁纸的全部标签
+ * @param state 应用状态
+ * @param wallpaper_id 壁纸ID
+ * @param tags 标签名数组，不存在的标签会自动创建
+ */
+#[tauri::command]
+pub fn set_wallpaper_tags(state: State<AppState>, wallpaper_id: String, tags: Vec<String>) -> Result<()> {
+    WallpaperLibraryService::new(state.db.clone()).set_wallpaper_tags(&wallpaper_id, &tags)
+}
+
+/**
+ * 为多张壁纸添加标签
+ * @param state 应用状态
+ * @param wallpaper_ids 壁纸ID数组
+ * @param tags 标签名数组
+ */
+#[tauri::command]
+pub fn add_wallpaper_tags(state: State<AppState>, wallpaper_ids: Vec<String>, tags: Vec<String>) -> Result<()> {
+    WallpaperLibraryService::new(state.db.clone()).add_wallpaper_tags(&wallpaper_ids, &tags)
+}
+
+/**
+ * 从多张壁纸上移除标签
+ * @param state 应用状态
+ * @param wallpaper_ids 壁纸ID数组
+ * @param tag_ids 标签ID数组
+ */
+#[tauri::command]
+pub fn remove_wallpaper_tags(state: State<AppState>, wallpaper_ids: Vec<String>, tag_ids: Vec<i64>) -> Result<()> {
+    WallpaperLibraryService::new(state.db.clone()).remove_wallpaper_tags(&wallpaper_ids, &tag_ids)
+}
+
+/**
+ * 重命名标签，与已有标签重名时合并
+ * @param state 应用状态
+ * @param tag_id 标签ID
+ * @param name 新名称
+ */
+#[tauri::command]
+pub fn rename_wallpaper_tag(state: State<AppState>, tag_id: i64, name: String) -> Result<()> {
+    WallpaperLibraryService::new(state.db.clone()).rename_tag(tag_id, &name)
+}
+
+/**
+ * 删除标签
+ * @param state 应用状态
+ * @param tag_id 标签ID
+ */
+#[tauri::command]
+pub fn delete_wallpaper_tag(state: State<AppState>, tag_id: i64) -> Result<()> {
+    WallpaperLibraryService::new(state.db.clone()).delete_tag(tag_id)
+}
+
+/**
+ * 收藏或取消收藏壁纸
+ * @param state 应用状态
+ * @param wallpaper_ids 壁纸ID数组
+ * @param favorite 是否收藏
+ */
+#[tauri::command]
+pub fn set_wallpaper_favorite(state: State<AppState>, wallpaper_ids: Vec<String>, favorite: bool) -> Result<()> {
+    WallpaperLibraryService::new(state.db.clone()).set_favorite(&wallpaper_ids, favorite)
+}
+
+/**
+ * 设置壁纸评分
+ * @param state 应用状态
+ * @param wallpaper_ids 壁纸ID数组
+ * @param rating 评分 1-5，0 表示清除评分
+ */
+#[tauri::command]
+pub fn set_wallpaper_rating(state: State<AppState>, wallpaper_ids: Vec<String>, rating: i32) -> Result<()> {
+    WallpaperLibraryService::new(state.db.clone()).set_rating(&wallpaper_ids, rating)
+}
+
+/**
+ * 获取壁纸合集列表
+ * @param state 应用状态
+ * @returns 合集数组
+ */
+#[tauri::command]
+pub fn get_wallpaper_collections(state: State<AppState>) -> Result<Vec<WallpaperCollection>> {
+    WallpaperLibraryService::new(state.db.clone()).list_collections()
+}
+
+/**
+ * 创建壁纸合集
+ * @param state 应用状态
+ * @param input 合集名称与描述
+ * @returns 创建的合集
+ */
+#[tauri::command]
+pub fn create_wallpaper_collection(state: State<AppState>, input: WallpaperCollectionInput) -> Result<WallpaperCollection> {
+    WallpaperLibraryService::new(state.db.clone()).create_collection(&input)
+}
+
+/**
+ * 修改壁纸合集
+ * @param state 应用状态
+ * @param collection_id 合集ID
+ * @param input 合集名称与描述
+ * @returns 修改后的合集
+ */
+#[tauri::command]
+pub fn update_wallpaper_collection(
+    state: State<AppState>,
+    collection_id: i64,
+    input: WallpaperCollectionInput,
+) -> Result<WallpaperCollection> {
+    WallpaperLibraryService::new(state.db.clone()).update_collection(collection_id, &input)
+}
+
+/**
+ * 删除壁纸合集，不会删除其中的壁纸
+ * @param state 应用状态
+ * @param collection_id 合集ID
+ */
+#[tauri::command]
+pub fn delete_wallpaper_collection(state: State<AppState>, collection_id: i64) -> Result<()> {
+    WallpaperLibraryService::new(state.db.clone()).delete_collection(collection_id)
+}
+
+/**
+ * 将壁纸加入合集
+ * @param state 应用状态
+ * @param collection_id 合集ID
+ * @param wallpaper_ids 壁纸ID数组
+ * @returns 更新后的合集
+ */
+#[tauri::command]
+pub fn add_wallpapers_to_collection(
+    state: State<AppState>,
+    collection_id: i64,
+    wallpaper_ids: Vec<String>,
+) -> Result<WallpaperCollection> {
+    WallpaperLibraryService::new(state.db.clone()).add_to_collection(collection_id, &wallpaper_ids)
+}
+
+/**
+ * 将壁纸移出合集
+ * @param state 应用状态
+ * @param collection_id 合集ID
+ * @param wallpaper_ids 壁纸ID数组
+ * @returns 更新后的合集
+ */
+#[tauri::command]
+pub fn remove_wallpapers_from_collection(
+    state: State<AppState>,
+    collection_id: i64,
+    wallpaper_ids: Vec<String>,
+) -> Result<WallpaperCollection> {
+    WallpaperLibraryService::new(state.db.clone()).remove_from_collection(collection_id, &wallpaper_ids)
+}
+
+/**
+ * 获取壁纸来源文件夹列表
+ * @param state 应用状态
+ * @returns 壁纸文件夹数组
+ */
+#[tauri::command]
+pub fn get_wallpaper_folders(state: State<AppState>) -> Result<Vec<WallpaperFolder>> {
+    WallpaperFolderService::new(state.db.clone()).list_folders()
+}
+
+/**
+ * 添加壁纸来源文件夹并立即同步
+ * @param app_handle 应用句柄
+ * @param state 应用状态
+ * @param input 文件夹路径与同步设置
+ * @returns 添加后的壁纸文件夹
+ */
+#[tauri::command]
+pub async fn add_wallpaper_folder(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    input: WallpaperFolderInput,
+) -> Result<WallpaperFolder> {
+    let service = WallpaperFolderService::new(state.db.clone());
+    let folder = service.add_folder(&input)?;
+    sync_and_watch_folder(app_handle, &state, folder.id).await
+}
+
+/**
+ * 修改壁纸来源文件夹设置并按新设置重新同步
+ * @param app_handle 应用句柄
+ * @param state 应用状态
+ * @param folder_id 文件夹ID
+ * @param input 同步设置，路径不会被修改
+ * @returns 修改后的壁纸文件夹
+ */
+#[tauri::command]
+pub async fn update_wallpaper_folder(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    folder_id: i64,
+    input: WallpaperFolderInput,
+) -> Result<WallpaperFolder> {
+    let service = WallpaperFolderService::new(state.db.clone());
+    service.update_folder(folder_id, &input)?;
+    sync_and_watch_folder(app_handle, &state, folder_id).await
+}
+
+/**
+ * 删除壁纸来源文件夹
+ * @param state 应用状态
+ * @param folder_id 文件夹ID
+ * @param remove_wallpapers 是否同时从壁纸库移除该文件夹导入的壁纸（不会删除文件）
+ */
+#[tauri::command]
+pub fn remove_wallpaper_folder(state: State<AppState>, folder_id: i64, remove_wallpapers: bool) -> Result<()> {
+    state.wallpaper_folders.unwatch(folder_id);
+    WallpaperFolderService::new(state.db.clone()).remove_folder(&state.thumbnails, folder_id, remove_wallpapers)
+}
+
+/**
+ * 手动同步壁纸来源文件夹
+ * @param app_handle 应用句柄
+ * @param state 应用状态
+ * @param folder_id 文件夹ID（可选），为空时同步所有文件夹
+ * @returns 各文件夹的同步结果
+ */
+#[tauri::command]
+pub async fn sync_wallpaper_folders(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    folder_id: Option<i64>,
+) -> Result<Vec<WallpaperFolderSyncResult>> {
+    let db = state.db.clone();
+    let thumbnails = state.thumbnails.clone();
+    let results = tauri::async_runtime::spawn_blocking(move || {
+        let service = WallpaperFolderService::new(db);
+        match folder_id {
+            Some(folder_id) => service.sync_folder(&thumbnails, folder_id).map(|result| vec![result]),
+            None => service.sync_all(&thumbnails),
+        }
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))??;
+    emit_changes(&app_handle, &results);
+    Ok(results)
+}
+
+/// 同步文件夹并按其设置开始或停止监听
+async fn sync_and_watch_folder(app_handle: AppHandle, state: &AppState, folder_id: i64) -> Result<WallpaperFolder> {
+    let db = state.db.clone();
+    let thumbnails = state.thumbnails.clone();
+    let result =
+        tauri::async_runtime::spawn_blocking(move || WallpaperFolderService::new(db).sync_folder(&thumbnails, folder_id))
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))??;
+    emit_changes(&app_handle, &[result]);
+
+    let folder = WallpaperFolderService::new(state.db.clone()).get_folder(folder_id)?;
+    state
+        .wallpaper_folders
+        .apply(app_handle, state.db.clone(), state.thumbnails.clone(), &folder)?;
+    Ok(folder)
 }
 
 /**
@@ -87,9 +453,16 @@ pub fn get_wallpaper_thumbnail(
  * @returns 壁纸分类数组JSON字符串
  */
 #[tauri::command]
-pub fn get_wallpaper_categories(state: State<AppState>) -> Result<String> {
-    let service = WallpaperService::new(state.db.clone());
-    service.get_wallpaper_categories()
+pub async fn get_wallpaper_categories(state: State<'_, AppState>) -> Result<String> {
+    let db = state.db.clone();
+    let sources = state.wallpaper_sources.clone();
+    // 壁纸源会发起网络请求，放到后台线程执行
+    tauri::async_runtime::spawn_blocking(move || {
+        let service = WallpaperService::new(db);
+        service.get_wallpaper_categories(&sources)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
 }
 
 /**
@@ -101,26 +474,41 @@ pub fn get_wallpaper_categories(state: State<AppState>) -> Result<String> {
  * @returns 壁纸列表响应JSON字符串
  */
 #[tauri::command]
-pub fn get_wallpaper_station_wallpapers(
-    state: State<AppState>,
+pub async fn get_wallpaper_station_wallpapers(
+    state: State<'_, AppState>,
     category_id: Option<String>,
     page: u32,
     page_size: u32,
 ) -> Result<String> {
-    let service = WallpaperService::new(state.db.clone());
-    service.get_wallpaper_station_wallpapers(category_id, page, page_size)
+    let db = state.db.clone();
+    let sources = state.wallpaper_sources.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let service = WallpaperService::new(db);
+        service.get_wallpaper_station_wallpapers(&sources, category_id, page, page_size)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
 }
 
 /**
- * 下载壁纸站壁纸到本地
+ * 下载壁纸站壁纸到本地壁纸库并导入
+ * @description 下载进度通过 wallpaper://download 事件推送，中断后再次下载会从断点继续
+ * @param app_handle 应用句柄
  * @param state 应用状态
- * @param wallpaper_id 壁纸ID
- * @returns 下载后的壁纸信息JSON字符串
+ * @param wallpaper_id 壁纸站中的壁纸ID
+ * @returns 导入后的本地壁纸信息JSON字符串
  */
 #[tauri::command]
-pub fn download_wallpaper(state: State<AppState>, wallpaper_id: String) -> Result<String> {
-    let service = WallpaperService::new(state.db.clone());
-    service.download_wallpaper(wallpaper_id)
+pub async fn download_wallpaper(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    wallpaper_id: String,
+) -> Result<String> {
+    let wallpaper = state
+        .wallpaper_downloads
+        .download(&app_handle, state.db.clone(), state.wallpaper_sources.clone(), wallpaper_id)
+        .await?;
+    serde_json::to_string(&wallpaper).map_err(|e| AppError::Internal(e.to_string()))
 }
 
 /**
@@ -132,52 +520,117 @@ pub fn download_wallpaper(state: State<AppState>, wallpaper_id: String) -> Resul
  * @returns 壁纸列表响应JSON字符串
  */
 #[tauri::command]
-pub fn search_wallpapers(
-    state: State<AppState>,
+pub async fn search_wallpapers(
+    state: State<'_, AppState>,
     keyword: String,
     page: u32,
     page_size: u32,
 ) -> Result<String> {
-    let service = WallpaperService::new(state.db.clone());
-    service.search_wallpapers(keyword, page, page_size)
+    let db = state.db.clone();
+    let sources = state.wallpaper_sources.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let service = WallpaperService::new(db);
+        service.search_wallpapers(&sources, keyword, page, page_size)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/**
+ * 获取已配置的壁纸源及当前选中的壁纸源
+ * @param state 应用状态
+ * @returns 壁纸源设置
+ */
+#[tauri::command]
+pub fn get_wallpaper_sources(state: State<AppState>) -> Result<WallpaperSourceSettings> {
+    Ok(state.wallpaper_sources.settings())
+}
+
+/**
+ * 添加或更新壁纸源
+ * @param state 应用状态
+ * @param config 壁纸源配置，id 为空时新增，否则替换同 ID 的壁纸源
+ * @returns 更新后的壁纸源设置
+ */
+#[tauri::command]
+pub fn save_wallpaper_source(state: State<AppState>, config: WallpaperSourceConfig) -> Result<WallpaperSourceSettings> {
+    state.wallpaper_sources.save_source(&state.db, config)
+}
+
+/**
+ * 删除壁纸源
+ * @param state 应用状态
+ * @param source_id 壁纸源ID
+ * @returns 更新后的壁纸源设置
+ */
+#[tauri::command]
+pub fn remove_wallpaper_source(state: State<AppState>, source_id: String) -> Result<WallpaperSourceSettings> {
+    state.wallpaper_sources.remove_source(&state.db, &source_id)
+}
+
+/**
+ * 切换壁纸站使用的壁纸源
+ * @param state 应用状态
+ * @param source_id 壁纸源ID
+ * @returns 更新后的壁纸源设置
+ */
+#[tauri::command]
+pub fn set_active_wallpaper_source(state: State<AppState>, source_id: String) -> Result<WallpaperSourceSettings> {
+    state.wallpaper_sources.set_active(&state.db, &source_id)
 }
 
 /**
  * 设置系统壁纸
+ * @param app_handle 应用句柄
  * @param state 应用状态
  * @param wallpaper_id 壁纸ID
- * @param fit_mode 适配模式
- * @param monitor_id 显示器ID（可选）
+ * @param fit_mode 适配模式：fill、fit、stretch、center、tile、span
+ * @param monitor_id 显示器ID（可选），不传则设置到所有显示器
  * @returns 设置结果JSON字符串
  */
 #[tauri::command]
-pub fn set_wallpaper(
-    state: State<AppState>,
+pub async fn set_wallpaper(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
     wallpaper_id: String,
     fit_mode: String,
     monitor_id: Option<String>,
 ) -> Result<String> {
-    let service = WallpaperService::new(state.db.clone());
-    service.set_wallpaper(wallpaper_id, fit_mode, monitor_id)
+    let db = state.db.clone();
+    // 拼接多显示器壁纸需要解码大图，放到后台线程执行
+    tauri::async_runtime::spawn_blocking(move || {
+        let service = WallpaperService::new(db);
+        service.set_wallpaper(&app_handle, wallpaper_id, fit_mode, monitor_id)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
 }
 
 /**
  * 设置本地文件为壁纸
+ * @param app_handle 应用句柄
  * @param state 应用状态
  * @param file_path 文件路径
- * @param fit_mode 适配模式
- * @param monitor_id 显示器ID（可选）
+ * @param fit_mode 适配模式：fill、fit、stretch、center、tile、span
+ * @param monitor_id 显示器ID（可选），不传则设置到所有显示器
  * @returns 设置结果JSON字符串
  */
 #[tauri::command]
-pub fn set_local_wallpaper(
-    state: State<AppState>,
+pub async fn set_local_wallpaper(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
     file_path: String,
     fit_mode: String,
     monitor_id: Option<String>,
 ) -> Result<String> {
-    let service = WallpaperService::new(state.db.clone());
-    service.set_local_wallpaper(file_path, fit_mode, monitor_id)
+    let db = state.db.clone();
+    // 拼接多显示器壁纸需要解码大图，放到后台线程执行
+    tauri::async_runtime::spawn_blocking(move || {
+        let service = WallpaperService::new(db);
+        service.set_local_wallpaper(&app_handle, file_path, fit_mode, monitor_id)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
 }
 
 /**
@@ -193,13 +646,14 @@ pub fn get_current_wallpaper(state: State<AppState>) -> Result<String> {
 
 /**
  * 获取可用显示器列表
+ * @param app_handle 应用句柄
  * @param state 应用状态
  * @returns 显示器数组JSON字符串
  */
 #[tauri::command]
-pub fn get_monitors(state: State<AppState>) -> Result<String> {
+pub fn get_monitors(app_handle: AppHandle, state: State<AppState>) -> Result<String> {
     let service = WallpaperService::new(state.db.clone());
-    service.get_monitors()
+    service.get_monitors(&app_handle)
 }
 
 /**
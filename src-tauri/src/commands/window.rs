@@ -1,4 +1,5 @@
-use tauri::{Manager, WindowEvent, image::Image};
+use crate::services::slideshow::{SlideshowStatus, SLIDESHOW_CHANGED_EVENT};
+use tauri::{Listener, Manager, WindowEvent, image::Image};
 
 pub fn setup_window_listeners(app: &tauri::App) {
     let app_handle = app.app_handle().clone();
@@ -15,6 +16,8 @@ pub fn setup_window_listeners(app: &tauri::App) {
                 if behavior == "minimize" {
                     api.prevent_close();
                     let _ = handle.hide();
+                    // 隐藏到托盘时锁定所有解锁会话
+                    state.sessions.lock_all();
                 }
             }
         });
@@ -24,6 +27,7 @@ pub fn setup_window_listeners(app: &tauri::App) {
 pub async fn setup_system_tray(app: &tauri::App) {
     use tauri::menu::MenuItem;
     use tauri::menu::Menu;
+    use tauri::menu::PredefinedMenuItem;
     use tauri::tray::TrayIconBuilder;
     
     let app_handle = app.app_handle().clone();
@@ -31,8 +35,12 @@ pub async fn setup_system_tray(app: &tauri::App) {
     let quit_i = MenuItem::with_id(&app_handle, "quit", "退出", true, None::<&str>).unwrap();
     let show_i = MenuItem::with_id(&app_handle, "show", "显示窗口", true, None::<&str>).unwrap();
     // let about_i = MenuItem::with_id(&app_handle, "about", "关于", true, None::<&str>).unwrap();
+    let next_i = MenuItem::with_id(&app_handle, "slideshow_next", "下一张壁纸", true, None::<&str>).unwrap();
+    let previous_i = MenuItem::with_id(&app_handle, "slideshow_previous", "上一张壁纸", true, None::<&str>).unwrap();
+    let pause_i = MenuItem::with_id(&app_handle, "slideshow_pause", pause_label(&app_handle), true, None::<&str>).unwrap();
+    let separator = PredefinedMenuItem::separator(&app_handle).unwrap();
     
-    let menu = Menu::with_items(&app_handle, &[&show_i, &quit_i]).unwrap();
+    let menu = Menu::with_items(&app_handle, &[&show_i, &separator, &next_i, &previous_i, &pause_i, &separator, &quit_i]).unwrap();
 
     let icon = Image::from_bytes(include_bytes!("../../icons/logo.png")).unwrap();
     
@@ -42,6 +50,14 @@ pub async fn setup_system_tray(app: &tauri::App) {
         .tooltip("大胖工具箱")
         .build(&app_handle)
         .unwrap();
+
+    // 轮播状态可能由前端、调度线程或托盘菜单修改，统一在状态变化事件中刷新暂停项的文字
+    let pause_item = pause_i.clone();
+    app_handle.listen(SLIDESHOW_CHANGED_EVENT, move |event| {
+        if let Ok(status) = serde_json::from_str::<SlideshowStatus>(event.payload()) {
+            let _ = pause_item.set_text(pause_text(status.paused));
+        }
+    });
     
     let app_handle_clone = app_handle.clone();
     
@@ -52,6 +68,21 @@ pub async fn setup_system_tray(app: &tauri::App) {
             let _ = app_handle_clone.get_webview_window("main").unwrap().set_focus();
         } else if id.as_ref() == "quit" {
             let _ = app_handle_clone.exit(0);
+        } else if id.as_ref().starts_with("slideshow_") {
+            // 切换壁纸可能需要拼接大图，放到后台线程执行，避免阻塞托盘菜单
+            let app = app_handle_clone.clone();
+            let action = id.as_ref().to_string();
+            tauri::async_runtime::spawn_blocking(move || {
+                let state = app.state::<crate::AppState>();
+                let result = match action.as_str() {
+                    "slideshow_next" => state.slideshow.next(&app, &state.db),
+                    "slideshow_previous" => state.slideshow.previous(&app, &state.db),
+                    _ => state.slideshow.toggle_pause(&app, &state.db),
+                };
+                if let Err(e) = result {
+                    eprintln!("Slideshow tray action {} failed: {}", action, e);
+                }
+            });
         } else if id.as_ref() == "about" {
             let _ = app_handle_clone.get_webview_window("about").unwrap().show();
             let _ = app_handle_clone.get_webview_window("about").unwrap().set_focus();
@@ -65,3 +96,16 @@ pub async fn setup_system_tray(app: &tauri::App) {
     //     }
     // });
 }
+
+/// 托盘菜单中暂停/继续轮播项的文字
+fn pause_label(app_handle: &tauri::AppHandle) -> &'static str {
+    pause_text(app_handle.state::<crate::AppState>().slideshow.status().paused)
+}
+
+fn pause_text(paused: bool) -> &'static str {
+    if paused {
+        "继续轮播"
+    } else {
+        "暂停轮播"
+    }
+}
@@ -1,9 +1,102 @@
-use rusqlite::{Connection, Result};
+use crate::services::password::{hash_password, GLOBAL_PASSWORD_KEY};
+use rusqlite::{params, Connection, OptionalExtension, Result, Transaction};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
 pub mod models;
 
+/// 单个数据库迁移步骤
+struct Migration {
+    /// 迁移完成后的 schema 版本号（记录在 PRAGMA user_version 中）
+    version: i32,
+    description: &'static str,
+    up: fn(&Transaction) -> Result<()>,
+}
+
+/// 按版本号递增排列的迁移列表，新增迁移只能追加到末尾
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "repair shortcuts schema drift",
+        up: migrate_shortcuts_schema,
+    },
+    Migration {
+        version: 2,
+        description: "hash plaintext global password",
+        up: migrate_plaintext_global_password,
+    },
+    Migration {
+        version: 3,
+        description: "add directory_bindings.watch_enabled",
+        up: migrate_binding_watch,
+    },
+    Migration {
+        version: 4,
+        description: "add resources full-text index",
+        up: migrate_resources_fts,
+    },
+    Migration {
+        version: 5,
+        description: "add resources keyset pagination indexes",
+        up: migrate_resources_sort_indexes,
+    },
+    Migration {
+        version: 6,
+        description: "add resources content hashes",
+        up: migrate_resources_content_hash,
+    },
+    Migration {
+        version: 7,
+        description: "add directory_bindings.ignore_rules",
+        up: migrate_binding_ignore_rules,
+    },
+    Migration {
+        version: 8,
+        description: "add directory_bindings scan options",
+        up: migrate_binding_scan_options,
+    },
+    Migration {
+        version: 9,
+        description: "add resources.mime_type and resources.kind",
+        up: migrate_resources_file_kind,
+    },
+    Migration {
+        version: 10,
+        description: "create resource_metadata",
+        up: migrate_resource_metadata,
+    },
+    Migration {
+        version: 11,
+        description: "create wallpaper_assignments",
+        up: migrate_wallpaper_assignments,
+    },
+    Migration {
+        version: 12,
+        description: "create wallpaper_folders and wallpapers sync columns",
+        up: migrate_wallpaper_folders,
+    },
+    Migration {
+        version: 13,
+        description: "create wallpaper tags and collections, add favourites and ratings",
+        up: migrate_wallpaper_library,
+    },
+    Migration {
+        version: 14,
+        description: "add wallpapers.perceptual_hash",
+        up: migrate_wallpaper_perceptual_hash,
+    },
+    Migration {
+        version: 15,
+        description: "make wallpapers.file_path unique",
+        up: migrate_wallpaper_unique_path,
+    },
+    Migration {
+        version: 16,
+        description: "add wallpapers.hash_failed",
+        up: migrate_wallpaper_hash_failed,
+    },
+];
+
 pub struct Database {
     conn: Arc<Mutex<Connection>>,
 }
@@ -19,6 +112,7 @@ impl Database {
             conn: Arc::new(Mutex::new(conn)),
         };
         db.init_tables()?;
+        db.run_migrations()?;
         Ok(db)
     }
 
@@ -98,16 +192,15 @@ impl Database {
         conn.execute(
             "CREATE TABLE IF NOT EXISTS shortcuts (
                 id TEXT PRIMARY KEY,
-                title TEXT NOT NULL,
+                name TEXT NOT NULL,
                 target TEXT NOT NULL,
+                kind TEXT NOT NULL DEFAULT 'website',
                 icon TEXT,
                 description TEXT,
-                category_id TEXT,
-                is_pinned BOOLEAN DEFAULT 0,
-                launch_count INTEGER DEFAULT 0,
-                sort_order INTEGER DEFAULT 0,
-                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+                encrypted BOOLEAN NOT NULL DEFAULT 0,
+                hidden BOOLEAN NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
             )",
             [],
         )?;
@@ -131,8 +224,7 @@ impl Database {
             [],
         )?;
 
-        // 创建全局配置表（用于存储密码等敏感信息，如果不想用 app_settings）
-        // 这里直接复用 app_settings 存储 'global_password_hash'
+        // 全局密码以 Argon2id 哈希形式存放在 app_settings 的 'global_password_hash' 中
 
         // 创建壁纸表
         conn.execute(
@@ -192,6 +284,28 @@ impl Database {
         Ok(())
     }
 
+    /// 执行所有未应用的迁移，每个迁移在独立事务中完成并更新 user_version
+    fn run_migrations(&self) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+
+        let current: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+        for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+            log::info!(
+                "Applying database migration {}: {}",
+                migration.version,
+                migration.description
+            );
+
+            let tx = conn.transaction()?;
+            (migration.up)(&tx)?;
+            tx.pragma_update(None, "user_version", migration.version)?;
+            tx.commit()?;
+        }
+
+        Ok(())
+    }
+
     pub fn get_connection(&self) -> Arc<Mutex<Connection>> {
         Arc::clone(&self.conn)
     }
@@ -211,4 +325,372 @@ impl Database {
         )?;
         Ok(())
     }
+
+    pub fn get_auto_lock_minutes(&self) -> Result<Option<u64>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT value FROM app_settings WHERE key = 'auto_lock_minutes'")?;
+        let minutes: Option<String> = stmt.query_row([], |row| row.get(0)).ok();
+        Ok(minutes.and_then(|m| m.parse().ok()))
+    }
+
+    pub fn set_auto_lock_minutes(&self, minutes: u64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO app_settings (key, value, updated_at) VALUES ('auto_lock_minutes', ?, CURRENT_TIMESTAMP)",
+            [minutes.to_string()],
+        )?;
+        Ok(())
+    }
+}
+
+/// 检查表中是否存在指定列
+fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(columns.iter().any(|c| c == column))
+}
+
+/// v1: 旧版 shortcuts 表使用 title/is_pinned 等列，而命令层读写的是 name/kind/encrypted/hidden，
+/// 且时间戳以秒级整数存储。这里按命令层的结构重建该表并迁移已有数据。
+fn migrate_shortcuts_schema(tx: &Transaction) -> Result<()> {
+    let has_title = column_exists(tx, "shortcuts", "title")?;
+    let has_name = column_exists(tx, "shortcuts", "name")?;
+
+    // 已经是新结构，只需补齐可能缺失的列
+    if has_name && !has_title {
+        if !column_exists(tx, "shortcuts", "kind")? {
+            tx.execute("ALTER TABLE shortcuts ADD COLUMN kind TEXT NOT NULL DEFAULT 'website'", [])?;
+        }
+        if !column_exists(tx, "shortcuts", "encrypted")? {
+            tx.execute("ALTER TABLE shortcuts ADD COLUMN encrypted BOOLEAN NOT NULL DEFAULT 0", [])?;
+        }
+        if !column_exists(tx, "shortcuts", "hidden")? {
+            tx.execute("ALTER TABLE shortcuts ADD COLUMN hidden BOOLEAN NOT NULL DEFAULT 0", [])?;
+        }
+        if !column_exists(tx, "shortcuts", "description")? {
+            tx.execute("ALTER TABLE shortcuts ADD COLUMN description TEXT", [])?;
+        }
+        return Ok(());
+    }
+
+    tx.execute(
+        "CREATE TABLE shortcuts_new (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            target TEXT NOT NULL,
+            kind TEXT NOT NULL DEFAULT 'website',
+            icon TEXT,
+            description TEXT,
+            encrypted BOOLEAN NOT NULL DEFAULT 0,
+            hidden BOOLEAN NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    // 旧数据的时间戳是 CURRENT_TIMESTAMP 文本，统一转换为秒级时间戳
+    tx.execute(
+        "INSERT INTO shortcuts_new (id, name, target, kind, icon, description, encrypted, hidden, created_at, updated_at)
+         SELECT id, title, target, 'website', icon, description, 0, 0,
+                CASE WHEN typeof(created_at) = 'integer' THEN created_at
+                     ELSE COALESCE(CAST(strftime('%s', created_at) AS INTEGER), 0) END,
+                CASE WHEN typeof(updated_at) = 'integer' THEN updated_at
+                     ELSE COALESCE(CAST(strftime('%s', updated_at) AS INTEGER), 0) END
+         FROM shortcuts",
+        [],
+    )?;
+
+    tx.execute("DROP TABLE shortcuts", [])?;
+    tx.execute("ALTER TABLE shortcuts_new RENAME TO shortcuts", [])?;
+
+    Ok(())
+}
+
+/// v2: 早期版本把全局密码明文存放在 app_settings.global_password 中，这里改为 Argon2id 哈希
+fn migrate_plaintext_global_password(tx: &Transaction) -> Result<()> {
+    let plaintext: Option<String> = tx
+        .query_row(
+            "SELECT value FROM app_settings WHERE key = 'global_password'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    if let Some(password) = plaintext {
+        let hash = hash_password(&password)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        tx.execute(
+            "INSERT OR REPLACE INTO app_settings (key, value, updated_at) VALUES (?, ?, CURRENT_TIMESTAMP)",
+            params![GLOBAL_PASSWORD_KEY, hash],
+        )?;
+        tx.execute("DELETE FROM app_settings WHERE key = 'global_password'", [])?;
+    }
+
+    Ok(())
+}
+
+/// v3: 目录绑定增加实时监听开关
+fn migrate_binding_watch(tx: &Transaction) -> Result<()> {
+    tx.execute(
+        "ALTER TABLE directory_bindings ADD COLUMN watch_enabled BOOLEAN NOT NULL DEFAULT 0",
+        [],
+    )?;
+    Ok(())
+}
+
+/// v4: 为资源文件名和路径建立 FTS5 全文索引（trigram 分词以支持子串匹配），
+/// 通过触发器与 resources 表保持同步
+fn migrate_resources_fts(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE VIRTUAL TABLE resources_fts USING fts5(
+             file_name,
+             file_path,
+             content = 'resources',
+             content_rowid = 'id',
+             tokenize = 'trigram'
+         );
+
+         CREATE TRIGGER resources_fts_ai AFTER INSERT ON resources BEGIN
+             INSERT INTO resources_fts (rowid, file_name, file_path)
+             VALUES (new.id, new.file_name, new.file_path);
+         END;
+
+         CREATE TRIGGER resources_fts_ad AFTER DELETE ON resources BEGIN
+             INSERT INTO resources_fts (resources_fts, rowid, file_name, file_path)
+             VALUES ('delete', old.id, old.file_name, old.file_path);
+         END;
+
+         CREATE TRIGGER resources_fts_au AFTER UPDATE OF file_name, file_path ON resources BEGIN
+             INSERT INTO resources_fts (resources_fts, rowid, file_name, file_path)
+             VALUES ('delete', old.id, old.file_name, old.file_path);
+             INSERT INTO resources_fts (rowid, file_name, file_path)
+             VALUES (new.id, new.file_name, new.file_path);
+         END;
+
+         INSERT INTO resources_fts (resources_fts) VALUES ('rebuild');",
+    )?;
+    Ok(())
+}
+
+/// v5: 资源列表按 (分类, 排序列, id) 做键集分页，为每种排序方式建立复合索引
+fn migrate_resources_sort_indexes(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_resources_category_name
+             ON resources(category_id, file_name COLLATE NOCASE, id);
+         CREATE INDEX IF NOT EXISTS idx_resources_category_size
+             ON resources(category_id, file_size, id);
+         CREATE INDEX IF NOT EXISTS idx_resources_category_type
+             ON resources(category_id, file_type COLLATE NOCASE, id);
+         CREATE INDEX IF NOT EXISTS idx_resources_category_modified
+             ON resources(category_id, modified_at, id);",
+    )?;
+    Ok(())
+}
+
+/// v6: 为重复文件检测保存部分内容哈希与完整内容哈希，
+/// 文件大小或修改时间变化后由触发器清空，下次检测时重新计算
+fn migrate_resources_content_hash(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "ALTER TABLE resources ADD COLUMN partial_hash TEXT;
+         ALTER TABLE resources ADD COLUMN content_hash TEXT;
+
+         CREATE INDEX IF NOT EXISTS idx_resources_file_size ON resources(file_size);
+
+         CREATE TRIGGER resources_hash_invalidate AFTER UPDATE OF file_size, modified_at ON resources
+         WHEN old.file_size IS NOT new.file_size OR old.modified_at IS NOT new.modified_at
+         BEGIN
+             UPDATE resources SET partial_hash = NULL, content_hash = NULL WHERE id = new.id;
+         END;",
+    )?;
+    Ok(())
+}
+
+/// v7: 绑定目录保存 gitignore 语法的忽略规则
+fn migrate_binding_ignore_rules(tx: &Transaction) -> Result<()> {
+    tx.execute(
+        "ALTER TABLE directory_bindings ADD COLUMN ignore_rules TEXT NOT NULL DEFAULT ''",
+        [],
+    )?;
+    Ok(())
+}
+
+/// v8: 绑定目录保存各自的扫描选项；原先由前端传入的默认忽略目录写入尚未设置规则的绑定，保持升级前的扫描结果
+fn migrate_binding_scan_options(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "ALTER TABLE directory_bindings ADD COLUMN show_hidden BOOLEAN NOT NULL DEFAULT 0;
+         ALTER TABLE directory_bindings ADD COLUMN follow_symlinks BOOLEAN NOT NULL DEFAULT 0;
+         ALTER TABLE directory_bindings ADD COLUMN max_depth INTEGER;
+
+         UPDATE directory_bindings SET ignore_rules = 'node_modules/
+.git/
+dist/
+target/' WHERE ignore_rules = '';",
+    )?;
+    Ok(())
+}
+
+/// v9: 保存按文件头识别的 MIME 类型与粗粒度分类，已有记录为 NULL，下次扫描时补全
+fn migrate_resources_file_kind(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "ALTER TABLE resources ADD COLUMN mime_type TEXT;
+         ALTER TABLE resources ADD COLUMN kind TEXT;
+
+         CREATE INDEX IF NOT EXISTS idx_resources_kind ON resources(kind);",
+    )?;
+    Ok(())
+}
+
+/// v10: 媒体元数据由后台任务按需提取，文件大小或修改时间变化后由触发器删除，下次提取时重新生成
+fn migrate_resource_metadata(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE resource_metadata (
+             resource_id INTEGER PRIMARY KEY REFERENCES resources(id) ON DELETE CASCADE,
+             width INTEGER,
+             height INTEGER,
+             duration_ms INTEGER,
+             captured_at TEXT,
+             camera_make TEXT,
+             camera_model TEXT,
+             title TEXT,
+             artist TEXT,
+             album TEXT,
+             genre TEXT,
+             extracted_at TEXT NOT NULL
+         );
+
+         CREATE INDEX idx_resource_metadata_size ON resource_metadata(width, height);
+         CREATE INDEX idx_resource_metadata_captured_at ON resource_metadata(captured_at);
+
+         CREATE TRIGGER resource_metadata_invalidate AFTER UPDATE OF file_size, modified_at ON resources
+         WHEN old.file_size IS NOT new.file_size OR old.modified_at IS NOT new.modified_at
+         BEGIN
+             DELETE FROM resource_metadata WHERE resource_id = new.id;
+         END;",
+    )?;
+    Ok(())
+}
+
+/// v11: 记录设置到系统的壁纸及适配模式，monitor_id 为 '*' 表示所有显示器
+fn migrate_wallpaper_assignments(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE wallpaper_assignments (
+             monitor_id TEXT PRIMARY KEY,
+             file_path TEXT NOT NULL,
+             fit_mode TEXT NOT NULL,
+             updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+         );",
+    )?;
+    Ok(())
+}
+
+/// v12: 壁纸来源文件夹；由文件夹同步导入的壁纸记录所属文件夹，文件丢失时标记 missing 而不是留下失效记录
+fn migrate_wallpaper_folders(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE wallpaper_folders (
+             id INTEGER PRIMARY KEY AUTOINCREMENT,
+             path TEXT NOT NULL UNIQUE,
+             recursive BOOLEAN NOT NULL DEFAULT 0,
+             min_width INTEGER,
+             min_height INTEGER,
+             watch_enabled BOOLEAN NOT NULL DEFAULT 0,
+             missing_policy TEXT NOT NULL DEFAULT 'flag',
+             last_synced_at DATETIME,
+             created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+         );
+
+         ALTER TABLE wallpapers ADD COLUMN folder_id INTEGER REFERENCES wallpaper_folders(id) ON DELETE SET NULL;
+         ALTER TABLE wallpapers ADD COLUMN missing BOOLEAN NOT NULL DEFAULT 0;
+
+         CREATE INDEX idx_wallpapers_folder_id ON wallpapers(folder_id);
+         CREATE INDEX idx_wallpapers_file_path ON wallpapers(file_path);",
+    )?;
+    Ok(())
+}
+
+/// v13: 本地壁纸的标签、收藏、评分与合集；标签名与合集名不区分大小写，删除壁纸时一并删除关联
+fn migrate_wallpaper_library(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "ALTER TABLE wallpapers ADD COLUMN favorite BOOLEAN NOT NULL DEFAULT 0;
+         ALTER TABLE wallpapers ADD COLUMN rating INTEGER NOT NULL DEFAULT 0;
+
+         CREATE TABLE wallpaper_tags (
+             id INTEGER PRIMARY KEY AUTOINCREMENT,
+             name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+             created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+         );
+
+         CREATE TABLE wallpaper_tag_links (
+             wallpaper_id TEXT NOT NULL REFERENCES wallpapers(id) ON DELETE CASCADE,
+             tag_id INTEGER NOT NULL REFERENCES wallpaper_tags(id) ON DELETE CASCADE,
+             PRIMARY KEY (wallpaper_id, tag_id)
+         );
+
+         CREATE TABLE wallpaper_collections (
+             id INTEGER PRIMARY KEY AUTOINCREMENT,
+             name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+             description TEXT,
+             created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+             updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+         );
+
+         CREATE TABLE wallpaper_collection_items (
+             collection_id INTEGER NOT NULL REFERENCES wallpaper_collections(id) ON DELETE CASCADE,
+             wallpaper_id TEXT NOT NULL REFERENCES wallpapers(id) ON DELETE CASCADE,
+             added_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+             PRIMARY KEY (collection_id, wallpaper_id)
+         );
+
+         CREATE INDEX idx_wallpaper_tag_links_tag_id ON wallpaper_tag_links(tag_id);
+         CREATE INDEX idx_wallpaper_collection_items_wallpaper_id ON wallpaper_collection_items(wallpaper_id);
+         CREATE INDEX idx_wallpapers_favorite ON wallpapers(favorite);
+         CREATE INDEX idx_wallpapers_rating ON wallpapers(rating);",
+    )?;
+    Ok(())
+}
+
+/// v14: 保存壁纸的 dHash 感知哈希（64 位，按 i64 存放），已有记录为 NULL，查找重复或导入时补算
+fn migrate_wallpaper_perceptual_hash(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE wallpapers ADD COLUMN perceptual_hash INTEGER", [])?;
+    Ok(())
+}
+
+/// v15: 壁纸路径唯一，避免并发同步重复导入同一文件；已有的重复记录优先保留文件夹同步的、其次最早导入的一条，
+/// 其余记录的收藏、评分、标签与合集合并到保留的记录上
+fn migrate_wallpaper_unique_path(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TEMP TABLE wallpaper_path_dupes AS
+         SELECT w.id AS dup_id,
+                (SELECT k.id FROM wallpapers k WHERE k.file_path = w.file_path
+                 ORDER BY k.folder_id IS NULL, k.created_at, k.rowid LIMIT 1) AS keep_id
+         FROM wallpapers w;
+         DELETE FROM wallpaper_path_dupes WHERE dup_id = keep_id;
+
+         UPDATE wallpapers SET
+             favorite = MAX(favorite, (SELECT MAX(w.favorite) FROM wallpaper_path_dupes d
+                                       JOIN wallpapers w ON w.id = d.dup_id WHERE d.keep_id = wallpapers.id)),
+             rating = MAX(rating, (SELECT MAX(w.rating) FROM wallpaper_path_dupes d
+                                   JOIN wallpapers w ON w.id = d.dup_id WHERE d.keep_id = wallpapers.id))
+         WHERE id IN (SELECT keep_id FROM wallpaper_path_dupes);
+         INSERT OR IGNORE INTO wallpaper_tag_links (wallpaper_id, tag_id)
+             SELECT d.keep_id, l.tag_id FROM wallpaper_tag_links l
+             JOIN wallpaper_path_dupes d ON d.dup_id = l.wallpaper_id;
+         INSERT OR IGNORE INTO wallpaper_collection_items (collection_id, wallpaper_id, added_at)
+             SELECT i.collection_id, d.keep_id, i.added_at FROM wallpaper_collection_items i
+             JOIN wallpaper_path_dupes d ON d.dup_id = i.wallpaper_id;
+         DELETE FROM wallpapers WHERE id IN (SELECT dup_id FROM wallpaper_path_dupes);
+         DROP TABLE wallpaper_path_dupes;
+
+         DROP INDEX idx_wallpapers_file_path;
+         CREATE UNIQUE INDEX idx_wallpapers_file_path ON wallpapers(file_path);",
+    )?;
+    Ok(())
+}
+
+/// v16: 记录感知哈希计算失败（无法解码）的壁纸，文件内容变化前不再重复尝试
+fn migrate_wallpaper_hash_failed(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE wallpapers ADD COLUMN hash_failed INTEGER NOT NULL DEFAULT 0", [])?;
+    Ok(())
 }
@@ -38,6 +38,24 @@ pub struct DirectoryBinding {
     pub category_id: i64,
     pub directory_path: String,
     pub created_at: String,
+    /// 是否实时监听该目录的文件变化
+    pub watch_enabled: bool,
+    /// gitignore 语法的忽略规则，每行一条
+    pub ignore_rules: String,
+    pub show_hidden: bool,
+    pub follow_symlinks: bool,
+    /// 最大扫描深度，None 表示不限制
+    pub max_depth: Option<u32>,
+}
+
+/// 更新绑定目录的扫描设置，未提供的字段保持不变
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UpdateBindingRequest {
+    pub show_hidden: Option<bool>,
+    pub ignore_rules: Option<String>,
+    pub follow_symlinks: Option<bool>,
+    /// 0 表示不限制深度
+    pub max_depth: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,11 +65,288 @@ pub struct ResourceItem {
     pub file_name: String,
     pub file_path: String,
     pub file_size: i64,
+    /// 文件格式对应的小写扩展名，能通过文件头识别时以实际内容为准
     pub file_type: String,
+    pub mime_type: String,
+    pub kind: FileKind,
     pub modified_at: String,
     pub scanned_at: String,
 }
 
+/// 文件的粗粒度分类
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileKind {
+    Image,
+    Video,
+    Audio,
+    Archive,
+    Document,
+    Code,
+    Executable,
+    #[default]
+    Other,
+}
+
+impl FileKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileKind::Image => "image",
+            FileKind::Video => "video",
+            FileKind::Audio => "audio",
+            FileKind::Archive => "archive",
+            FileKind::Document => "document",
+            FileKind::Code => "code",
+            FileKind::Executable => "executable",
+            FileKind::Other => "other",
+        }
+    }
+
+    /// 解析数据库中保存的分类，未知值视为 Other
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "image" => FileKind::Image,
+            "video" => FileKind::Video,
+            "audio" => FileKind::Audio,
+            "archive" => FileKind::Archive,
+            "document" => FileKind::Document,
+            "code" => FileKind::Code,
+            "executable" => FileKind::Executable,
+            _ => FileKind::Other,
+        }
+    }
+}
+
+/// 增量扫描结果统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanSummary {
+    pub added: i64,
+    pub changed: i64,
+    pub removed: i64,
+    pub unchanged: i64,
+}
+
+/// 容量树节点类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageNodeKind {
+    Category,
+    Binding,
+    Folder,
+}
+
+/// 容量树节点，可直接作为矩形树图的数据源
+/// 分类节点的 total_bytes 包含所有子分类，子节点为子分类和该分类绑定的目录；
+/// 绑定目录和文件夹节点的子节点为其下的文件夹，文件夹节点的 id 为所属绑定目录的 ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageNode {
+    pub id: i64,
+    pub name: String,
+    pub kind: UsageNodeKind,
+    /// 绑定目录和文件夹的完整路径，分类节点为空
+    pub path: Option<String>,
+    pub total_bytes: i64,
+    pub file_count: i64,
+    pub children: Vec<UsageNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BindingUsage {
+    pub binding_id: i64,
+    pub category_id: i64,
+    pub directory_path: String,
+    pub total_bytes: i64,
+    pub file_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileTypeUsage {
+    pub file_type: String,
+    pub total_bytes: i64,
+    pub file_count: i64,
+}
+
+/// 按文件分类统计的容量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KindUsage {
+    pub kind: FileKind,
+    pub total_bytes: i64,
+    pub file_count: i64,
+}
+
+/// 绑定目录下的子文件夹容量（含其所有子孙文件）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderUsage {
+    pub path: String,
+    pub category_id: i64,
+    pub total_bytes: i64,
+    pub file_count: i64,
+}
+
+/// 分类容量统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryStats {
+    pub total_bytes: i64,
+    pub file_count: i64,
+    pub tree: Vec<UsageNode>,
+    pub bindings: Vec<BindingUsage>,
+    /// 按占用空间从大到小排列
+    pub file_types: Vec<FileTypeUsage>,
+    /// 按占用空间从大到小排列
+    pub kinds: Vec<KindUsage>,
+    pub largest_files: Vec<ResourceItem>,
+    pub largest_folders: Vec<FolderUsage>,
+}
+
+/// 内容完全相同的一组资源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub content_hash: String,
+    pub file_size: i64,
+    /// 除保留一份外其余副本占用的字节数（已经是硬链接的副本不计入）
+    pub wasted_bytes: i64,
+    pub resources: Vec<ResourceItem>,
+}
+
+/// 重复文件的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateAction {
+    /// 直接删除副本
+    Delete,
+    /// 移动副本到回收站
+    Trash,
+    /// 用指向保留文件的硬链接替换副本
+    Hardlink,
+}
+
+/// 一组重复文件的处理方案：保留 keep_id，处理 duplicate_ids
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateResolution {
+    pub keep_id: i64,
+    pub duplicate_ids: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolveDuplicatesRequest {
+    pub action: DuplicateAction,
+    pub groups: Vec<DuplicateResolution>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResolveDuplicatesResult {
+    /// 处理成功的资源 ID
+    pub success: Vec<i64>,
+    /// 处理失败的文件及原因
+    pub failed: Vec<String>,
+    pub reclaimed_bytes: i64,
+}
+
+/// 资源列表的排序字段
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceSortField {
+    #[default]
+    Name,
+    Size,
+    Type,
+    Modified,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceSort {
+    pub field: ResourceSortField,
+    pub direction: SortDirection,
+}
+
+/// 资源列表的一页，next_page 为下一页的游标，没有更多数据时为 None
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourcePage {
+    pub items: Vec<ResourceItem>,
+    pub total: i64,
+    pub next_page: Option<String>,
+}
+
+/// 资源搜索的匹配方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    /// 文件名以关键字开头
+    Prefix,
+    /// 文件名或路径中包含关键字
+    #[default]
+    Substring,
+}
+
+/// 资源搜索条件，未提供的过滤项不参与筛选
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchResourcesRequest {
+    pub query: String,
+    pub mode: SearchMode,
+    /// 限定在该分类及其子分类下
+    pub category_id: Option<i64>,
+    pub file_types: Vec<String>,
+    pub kinds: Vec<FileKind>,
+    pub min_size: Option<i64>,
+    pub max_size: Option<i64>,
+    /// RFC 3339 格式的修改时间范围（含边界）
+    pub modified_after: Option<String>,
+    pub modified_before: Option<String>,
+    /// 以下条件基于媒体元数据，尚未提取元数据的资源不会匹配
+    pub min_width: Option<u32>,
+    pub max_width: Option<u32>,
+    pub min_height: Option<u32>,
+    pub max_height: Option<u32>,
+    /// 拍摄时间范围（含边界），本地时间 YYYY-MM-DD 或 YYYY-MM-DDTHH:MM:SS
+    pub captured_after: Option<String>,
+    pub captured_before: Option<String>,
+    pub min_duration_ms: Option<i64>,
+    pub max_duration_ms: Option<i64>,
+    /// 匹配相机厂商、型号以及标题、艺术家、专辑、流派
+    pub metadata_query: Option<String>,
+    /// 页码，从 1 开始
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
+
+/// 图片、音频、视频资源的媒体元数据，未能提取的字段为 None
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceMetadata {
+    pub resource_id: i64,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub duration_ms: Option<i64>,
+    /// 拍摄时间，本地时间 YYYY-MM-DDTHH:MM:SS
+    pub captured_at: Option<String>,
+    pub camera_make: Option<String>,
+    pub camera_model: Option<String>,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub genre: Option<String>,
+    pub extracted_at: String,
+}
+
+/// 分页的资源搜索结果，按相关度排序
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResourcesResult {
+    pub items: Vec<ResourceItem>,
+    pub total: i64,
+    pub page: u32,
+    pub page_size: u32,
+    pub has_more: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct OperationLog {
@@ -29,6 +29,12 @@ pub enum AppError {
     #[error("Invalid password")]
     InvalidPassword,
 
+    #[error("Locked: please unlock with the password first")]
+    Locked,
+
+    #[error("Operation cancelled")]
+    Cancelled,
+
     #[error("Path not found: {0}")]
     PathNotFound(String),
 
@@ -41,6 +47,9 @@ pub enum AppError {
     #[error("Internal error: {0}")]
     Internal(String),
 
+    #[error("Network error: {0}")]
+    Network(String),
+
     #[error("GPU error: {0}")]
     Gpu(String),
 
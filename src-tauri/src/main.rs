@@ -18,6 +18,16 @@ mod services;
 
 use db::Database;
 use models::shortcuts::ShortcutState;
+use services::duplicate::DuplicateJobManager;
+use services::metadata::MetadataJobManager;
+use services::scan_job::ScanJobManager;
+use services::session::{SessionManager, DEFAULT_AUTO_LOCK_MINUTES};
+use services::slideshow::SlideshowManager;
+use services::thumbnail::ThumbnailCache;
+use services::wallpaper_download::WallpaperDownloadManager;
+use services::wallpaper_folder::WallpaperFolderManager;
+use services::wallpaper_source::WallpaperSourceRegistry;
+use services::watcher::WatcherManager;
 use std::sync::Arc;
 use tauri::Manager;
 use commands::{setup_window_listeners, setup_system_tray};
@@ -25,6 +35,16 @@ use commands::{setup_window_listeners, setup_system_tray};
 #[derive(Clone)]
 pub struct AppState {
     pub db: Arc<Database>,
+    pub sessions: Arc<SessionManager>,
+    pub scan_jobs: Arc<ScanJobManager>,
+    pub watchers: Arc<WatcherManager>,
+    pub duplicate_jobs: Arc<DuplicateJobManager>,
+    pub metadata_jobs: Arc<MetadataJobManager>,
+    pub thumbnails: Arc<ThumbnailCache>,
+    pub slideshow: Arc<SlideshowManager>,
+    pub wallpaper_sources: Arc<WallpaperSourceRegistry>,
+    pub wallpaper_downloads: Arc<WallpaperDownloadManager>,
+    pub wallpaper_folders: Arc<WallpaperFolderManager>,
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -48,8 +68,56 @@ fn main() {
             let db_path = app_dir.join("local_resource_manager.db");
             let db = Database::new(db_path).expect("Failed to initialize database");
 
+            // 初始化解锁会话管理器
+            let auto_lock_minutes = db
+                .get_auto_lock_minutes()
+                .unwrap_or(None)
+                .unwrap_or(DEFAULT_AUTO_LOCK_MINUTES);
+            let sessions = SessionManager::new(auto_lock_minutes);
+
+            // 恢复已开启的目录实时监听
+            let db = Arc::new(db);
+            let watchers = WatcherManager::new();
+            if let Err(e) = watchers.restore(app.handle(), db.clone()) {
+                eprintln!("Failed to restore directory watchers: {}", e);
+            }
+
+            // 恢复壁纸轮播，调度线程在窗口隐藏到托盘后仍会运行
+            let slideshow = Arc::new(SlideshowManager::new());
+            if let Err(e) = slideshow.restore(app.handle().clone(), db.clone()) {
+                eprintln!("Failed to restore wallpaper slideshow: {}", e);
+            }
+
+            let thumbnails = Arc::new(ThumbnailCache::new(app_dir.join("thumbnails")));
+
+            // 后台同步壁纸文件夹，并恢复已开启的文件夹监听
+            let wallpaper_folders = WallpaperFolderManager::new();
+            if let Err(e) = wallpaper_folders.restore(app.handle(), db.clone(), thumbnails.clone()) {
+                eprintln!("Failed to restore wallpaper folders: {}", e);
+            }
+
+            // 壁纸源设置无法读取时不影响启动，壁纸站功能在重新配置前不可用
+            let wallpaper_sources = Arc::new(WallpaperSourceRegistry::load(&db).unwrap_or_else(|e| {
+                eprintln!("Failed to load wallpaper sources: {}", e);
+                WallpaperSourceRegistry::default()
+            }));
+
             // 设置应用状态
-            app.manage(AppState { db: Arc::new(db) });
+            app.manage(AppState {
+                db,
+                sessions: Arc::new(sessions),
+                scan_jobs: Arc::new(ScanJobManager::new()),
+                watchers: Arc::new(watchers),
+                duplicate_jobs: Arc::new(DuplicateJobManager::new()),
+                metadata_jobs: Arc::new(MetadataJobManager::new()),
+                thumbnails,
+                slideshow,
+                wallpaper_sources,
+                wallpaper_downloads: Arc::new(
+                    WallpaperDownloadManager::new().expect("Failed to create wallpaper downloader"),
+                ),
+                wallpaper_folders: Arc::new(wallpaper_folders),
+            });
             
             // 初始化 ShortcutState 并加载数据
             let shortcut_state = ShortcutState::new();
@@ -71,29 +139,86 @@ fn main() {
             commands::delete_category,
             commands::get_category_tree,
             commands::reorder_categories,
+            commands::get_category_stats,
+            commands::set_category_password,
+            commands::verify_category_password,
+            commands::clear_category_password,
             commands::bind_directory,
             commands::unbind_directory,
             commands::get_bindings,
+            commands::set_binding_watch,
+            commands::update_binding,
             commands::scan_directory,
+            commands::rescan_directory,
             commands::scan_directory_batch,
+            commands::start_scan_job,
+            commands::get_scan_job,
+            commands::cancel_scan_job,
             commands::select_directory,
             commands::open_in_explorer,
             commands::open_file_location,
             commands::batch_rename_files,
+            commands::search_resources,
+            commands::list_resources,
+            commands::start_duplicate_job,
+            commands::get_duplicate_job,
+            commands::cancel_duplicate_job,
+            commands::resolve_duplicates,
+            commands::start_metadata_job,
+            commands::get_metadata_job,
+            commands::cancel_metadata_job,
+            commands::get_resource_metadata,
+            commands::get_resource_thumbnail,
             commands::get_local_wallpapers,
             commands::import_wallpapers,
             commands::import_wallpapers_from_directory,
             commands::delete_local_wallpaper,
             commands::delete_local_wallpapers,
+            commands::backfill_wallpaper_dimensions,
             commands::get_wallpaper_thumbnail,
+            commands::get_wallpaper_dedup_settings,
+            commands::save_wallpaper_dedup_settings,
+            commands::find_wallpaper_duplicates,
+            commands::get_wallpaper_tags,
+            commands::set_wallpaper_tags,
+            commands::add_wallpaper_tags,
+            commands::remove_wallpaper_tags,
+            commands::rename_wallpaper_tag,
+            commands::delete_wallpaper_tag,
+            commands::set_wallpaper_favorite,
+            commands::set_wallpaper_rating,
+            commands::get_wallpaper_collections,
+            commands::create_wallpaper_collection,
+            commands::update_wallpaper_collection,
+            commands::delete_wallpaper_collection,
+            commands::add_wallpapers_to_collection,
+            commands::remove_wallpapers_from_collection,
+            commands::get_wallpaper_folders,
+            commands::add_wallpaper_folder,
+            commands::update_wallpaper_folder,
+            commands::remove_wallpaper_folder,
+            commands::sync_wallpaper_folders,
+            commands::get_thumbnail_cache_stats,
+            commands::clear_thumbnail_cache,
             commands::get_wallpaper_categories,
             commands::get_wallpaper_station_wallpapers,
             commands::download_wallpaper,
             commands::search_wallpapers,
+            commands::get_wallpaper_sources,
+            commands::save_wallpaper_source,
+            commands::remove_wallpaper_source,
+            commands::set_active_wallpaper_source,
             commands::set_wallpaper,
             commands::set_local_wallpaper,
             commands::get_current_wallpaper,
             commands::get_monitors,
+            commands::get_slideshow_status,
+            commands::start_slideshow,
+            commands::stop_slideshow,
+            commands::pause_slideshow,
+            commands::resume_slideshow,
+            commands::slideshow_next,
+            commands::slideshow_previous,
             commands::open_file_dialog,
             commands::get_system_info,
             commands::get_env_vars,
@@ -112,6 +237,9 @@ fn main() {
             commands::set_global_password,
             commands::verify_password,
             commands::has_password,
+            commands::lock_session,
+            commands::get_auto_lock_timeout,
+            commands::set_auto_lock_timeout,
             commands::fetch_website_metadata,
             commands::cards::get_desktop_cards,
             commands::cards::create_desktop_card,
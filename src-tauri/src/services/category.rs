@@ -1,7 +1,9 @@
 use crate::db::models::{Category, CategoryNode, CreateCategoryRequest, DeleteStrategy, UpdateCategoryRequest};
 use crate::db::Database;
 use crate::error::{AppError, Result};
+use crate::services::resource::SUBTREE_CTE;
 use rusqlite::params;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 pub struct CategoryService {
@@ -185,6 +187,41 @@ impl CategoryService {
         Ok(())
     }
 
+    /// 获取删除分类时会被删除的分类 ID：DeleteAll 包括所有子分类，PromoteChildren 只包括自身
+    pub fn categories_removed_by_delete(&self, id: i64, strategy: &DeleteStrategy) -> Result<Vec<i64>> {
+        match strategy {
+            DeleteStrategy::DeleteAll => {
+                let conn = self.db.get_connection();
+                let conn = conn.lock().unwrap();
+                let mut stmt = conn.prepare(&format!("{}SELECT id FROM subtree", SUBTREE_CTE))?;
+                let ids = stmt
+                    .query_map(params![id], |row| row.get(0))?
+                    .collect::<std::result::Result<Vec<i64>, _>>()?;
+                Ok(ids)
+            }
+            DeleteStrategy::PromoteChildren => Ok(vec![id]),
+        }
+    }
+
+    /// 获取删除分类时会被级联删除的目录绑定 ID：DeleteAll 包括所有子分类的绑定，PromoteChildren 只包括自身的绑定
+    pub fn bindings_removed_by_delete(&self, id: i64, strategy: &DeleteStrategy) -> Result<Vec<i64>> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+
+        let sql = match strategy {
+            DeleteStrategy::DeleteAll => format!(
+                "{}SELECT id FROM directory_bindings WHERE category_id IN (SELECT id FROM subtree)",
+                SUBTREE_CTE
+            ),
+            DeleteStrategy::PromoteChildren => "SELECT id FROM directory_bindings WHERE category_id = ?".to_string(),
+        };
+        let mut stmt = conn.prepare(&sql)?;
+        let ids = stmt
+            .query_map(params![id], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<i64>, _>>()?;
+        Ok(ids)
+    }
+
     /// 获取分类树
     pub fn get_category_tree(&self) -> Result<Vec<CategoryNode>> {
         log::info!("Getting category tree");
@@ -281,6 +318,68 @@ impl CategoryService {
         nodes
     }
 
+    /// 获取从根到该分类路径上所有设置了密码的分类 ID（包括自身）
+    pub fn protected_category_chain(&self, id: i64) -> Result<Vec<i64>> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "WITH RECURSIVE chain(id, parent_id, password_hash) AS (
+                 SELECT id, parent_id, password_hash FROM categories WHERE id = ?
+                 UNION ALL
+                 SELECT c.id, c.parent_id, c.password_hash
+                 FROM categories c JOIN chain ON c.id = chain.parent_id
+             )
+             SELECT id FROM chain WHERE password_hash IS NOT NULL",
+        )?;
+
+        let ids = stmt
+            .query_map(params![id], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<i64>, _>>()?;
+
+        Ok(ids)
+    }
+
+    /// 获取当前仍处于锁定状态的分类 ID：路径上存在未解锁的受保护分类即视为锁定
+    pub fn locked_category_ids(&self, unlocked: &HashSet<i64>) -> Result<Vec<i64>> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT id, parent_id, password_hash IS NOT NULL FROM categories",
+        )?;
+        let categories: HashMap<i64, (Option<i64>, bool)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?
+            .collect::<std::result::Result<HashMap<_, _>, _>>()?;
+
+        let locked = categories
+            .keys()
+            .copied()
+            .filter(|&id| {
+                // 沿父链向上查找，限制步数防止异常数据形成环
+                let mut current = Some(id);
+                let mut steps = 0;
+                while let Some(cid) = current {
+                    let (parent_id, protected) = match categories.get(&cid) {
+                        Some(entry) => *entry,
+                        None => break,
+                    };
+                    if protected && !unlocked.contains(&cid) {
+                        return true;
+                    }
+                    steps += 1;
+                    if steps > categories.len() {
+                        break;
+                    }
+                    current = parent_id;
+                }
+                false
+            })
+            .collect();
+
+        Ok(locked)
+    }
+
     /// 调整分类顺序
     pub fn reorder_categories(&self, orders: Vec<(i64, i32)>) -> Result<()> {
         let conn = self.db.get_connection();
@@ -1,22 +1,23 @@
-use crate::db::models::{DirectoryBinding, ResourceItem};
+use crate::db::models::{DirectoryBinding, FileKind, ResourceItem, ScanSummary, UpdateBindingRequest};
 use crate::db::Database;
 use crate::error::{AppError, Result};
 use crate::commands::directory::ScanProgress;
-use rusqlite::params;
+use crate::services::file_type;
+use crate::services::ignore_rules::IgnoreRules;
+use once_cell::sync::Lazy;
+use rusqlite::{params, OptionalExtension};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
-use walkdir::WalkDir;
-use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
 
-thread_local! {
-    static SCAN_STATE: RefCell<ScanState> = RefCell::new(ScanState::new());
-}
+/// 分批扫描的进度，按分类 ID 保存，Tauri 可能在不同线程上分发命令，因此不能使用线程局部存储
+static SCAN_STATES: Lazy<Mutex<HashMap<i64, ScanState>>> = Lazy::new(|| Mutex::new(HashMap::new()));
 
 struct ScanState {
     all_resources: Vec<ResourceItem>,
     current_index: usize,
     is_complete: bool,
-    category_id: i64,
+    summary: Option<ScanSummary>,
 }
 
 impl ScanState {
@@ -25,17 +26,10 @@ impl ScanState {
             all_resources: Vec::new(),
             current_index: 0,
             is_complete: false,
-            category_id: 0,
+            summary: None,
         }
     }
 
-    fn reset(&mut self, category_id: i64) {
-        self.all_resources.clear();
-        self.current_index = 0;
-        self.is_complete = false;
-        self.category_id = category_id;
-    }
-
     fn add_resources(&mut self, resources: Vec<ResourceItem>) {
         self.all_resources.extend(resources);
     }
@@ -107,17 +101,9 @@ impl DirectoryService {
 
         // 查询并返回
         let binding = conn.query_row(
-            "SELECT id, category_id, directory_path, created_at FROM directory_bindings WHERE id = ?",
+            &format!("SELECT {} FROM directory_bindings WHERE id = ?", BINDING_COLUMNS),
             params![id],
-
-            |row| {
-                Ok(DirectoryBinding {
-                    id: row.get(0)?,
-                    category_id: row.get(1)?,
-                    directory_path: row.get(2)?,
-                    created_at: row.get(3)?,
-                })
-            },
+            map_binding,
         )?;
 
         Ok(binding)
@@ -141,156 +127,323 @@ impl DirectoryService {
         let conn = self.db.get_connection();
         let conn = conn.lock().unwrap();
 
-        let mut stmt = conn.prepare(
-            "SELECT id, category_id, directory_path, created_at 
-             FROM directory_bindings 
-             WHERE category_id = ?",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM directory_bindings WHERE category_id = ?",
+            BINDING_COLUMNS
+        ))?;
 
         let bindings = stmt
-            .query_map(params![category_id], |row| {
-                Ok(DirectoryBinding {
-                    id: row.get(0)?,
-                    category_id: row.get(1)?,
-                    directory_path: row.get(2)?,
-                    created_at: row.get(3)?,
-                })
-            })?
+            .query_map(params![category_id], map_binding)?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok(bindings)
     }
 
-    /// 扫描目录
-    pub fn scan_directory(&self, category_id: i64, show_hidden: bool, ignore_directories: Option<String>) -> Result<Vec<ResourceItem>> {
+    /// 获取单个绑定
+    pub fn get_binding(&self, binding_id: i64) -> Result<DirectoryBinding> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+
+        conn.query_row(
+            &format!("SELECT {} FROM directory_bindings WHERE id = ?", BINDING_COLUMNS),
+            params![binding_id],
+            map_binding,
+        )
+        .optional()?
+        .ok_or_else(|| AppError::InvalidInput(format!("Binding not found: {}", binding_id)))
+    }
+
+    /// 获取所有开启了实时监听的绑定
+    pub fn get_watched_bindings(&self) -> Result<Vec<DirectoryBinding>> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM directory_bindings WHERE watch_enabled = 1",
+            BINDING_COLUMNS
+        ))?;
+
+        let bindings = stmt
+            .query_map([], map_binding)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(bindings)
+    }
+
+    /// 开启或关闭绑定的实时监听
+    pub fn set_binding_watch(&self, binding_id: i64, enabled: bool) -> Result<DirectoryBinding> {
+        {
+            let conn = self.db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.execute(
+                "UPDATE directory_bindings SET watch_enabled = ? WHERE id = ?",
+                params![enabled, binding_id],
+            )?;
+        }
+        self.get_binding(binding_id)
+    }
+
+    /// 更新绑定目录的扫描设置，未提供的字段保持不变，下次扫描时生效
+    pub fn update_binding(&self, binding_id: i64, req: UpdateBindingRequest) -> Result<DirectoryBinding> {
+        if let Some(rules) = &req.ignore_rules {
+            IgnoreRules::validate(rules)?;
+        }
+        self.get_binding(binding_id)?;
+
+        let mut updates = Vec::new();
+        let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(show_hidden) = req.show_hidden {
+            updates.push("show_hidden = ?");
+            values.push(Box::new(show_hidden));
+        }
+        if let Some(ignore_rules) = req.ignore_rules {
+            updates.push("ignore_rules = ?");
+            values.push(Box::new(ignore_rules));
+        }
+        if let Some(follow_symlinks) = req.follow_symlinks {
+            updates.push("follow_symlinks = ?");
+            values.push(Box::new(follow_symlinks));
+        }
+        if let Some(max_depth) = req.max_depth {
+            // 0 表示不限制深度
+            updates.push("max_depth = ?");
+            values.push(Box::new(if max_depth == 0 { None } else { Some(max_depth) }));
+        }
+
+        if !updates.is_empty() {
+            let sql = format!("UPDATE directory_bindings SET {} WHERE id = ?", updates.join(", "));
+            values.push(Box::new(binding_id));
+
+            let conn = self.db.get_connection();
+            let conn = conn.lock().unwrap();
+            let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+            conn.execute(&sql, params.as_slice())?;
+        }
+
+        self.get_binding(binding_id)
+    }
+
+    /// 扫描目录，每个绑定目录使用各自保存的扫描设置
+    pub fn scan_directory(&self, category_id: i64) -> Result<(Vec<ResourceItem>, ScanSummary)> {
+        self.scan_directory_with_progress(category_id, |_| true)
+    }
+
+    /// 扫描目录，每发现一个文件调用一次 on_file，返回 false 时取消扫描并返回 Cancelled
+    pub fn scan_directory_with_progress<F>(
+        &self,
+        category_id: i64,
+        mut on_file: F,
+    ) -> Result<(Vec<ResourceItem>, ScanSummary)>
+    where
+        F: FnMut(&Path) -> bool,
+    {
         let bindings = self.get_bindings(category_id)?;
         let mut all_resources = Vec::new();
 
-        // 解析忽略的目录列表
-        let ignore_dirs: Vec<String> = ignore_directories
-            .map(|s| {
-                s.split(',')
-                    .map(|d| d.trim().to_lowercase())
-                    .filter(|d| !d.is_empty())
-                    .collect()
-            })
-            .unwrap_or_default();
-
         for binding in bindings {
-            let resources = self.scan_path(&binding.directory_path, show_hidden, &ignore_dirs)?;
+            let rules = IgnoreRules::for_binding(&binding);
+            let resources = self.scan_path(&rules, Path::new(&binding.directory_path), &mut on_file)?;
             all_resources.extend(resources);
         }
 
         // 保存到数据库
-        self.save_resources(category_id, &all_resources)?;
+        let summary = self.save_resources(category_id, &mut all_resources)?;
 
-        Ok(all_resources)
+        Ok((all_resources, summary))
     }
 
     /// 分批扫描目录
-    pub fn scan_directory_batch(&self, category_id: i64, show_hidden: bool, ignore_directories: Option<String>, batch_size: i32) -> Result<ScanProgress> {
-        SCAN_STATE.with(|state| {
-            let mut scan_state = state.borrow_mut();
-
-            // 如果是第一次扫描或分类ID不同，重新开始扫描
-            if scan_state.category_id != category_id || scan_state.is_complete {
-                scan_state.reset(category_id);
-
-                let bindings = self.get_bindings(category_id)?;
-
-                // 解析忽略的目录列表
-                let ignore_dirs: Vec<String> = ignore_directories
-                    .map(|s| {
-                        s.split(',')
-                            .map(|d| d.trim().to_lowercase())
-                            .filter(|d| !d.is_empty())
-                            .collect()
-                    })
-                    .unwrap_or_default();
+    pub fn scan_directory_batch(&self, category_id: i64, batch_size: i32) -> Result<ScanProgress> {
+        // 如果是第一次扫描或上次已完成，重新开始扫描（扫描期间不持有锁，不阻塞其他分类）
+        let needs_scan = !SCAN_STATES.lock().unwrap().contains_key(&category_id);
+        if needs_scan {
+            let (resources, summary) = self.scan_directory(category_id)?;
+
+            let mut scan_state = ScanState::new();
+            scan_state.add_resources(resources);
+            scan_state.summary = Some(summary);
+            SCAN_STATES.lock().unwrap().insert(category_id, scan_state);
+        }
 
-                for binding in bindings {
-                    let resources = self.scan_path(&binding.directory_path, show_hidden, &ignore_dirs)?;
-                    scan_state.add_resources(resources);
+        let mut states = SCAN_STATES.lock().unwrap();
+        let scan_state = match states.get_mut(&category_id) {
+            Some(scan_state) => scan_state,
+            None => return Err(AppError::Internal("Scan state was released concurrently".to_string())),
+        };
+
+        // 获取下一批数据
+        let current_batch = scan_state.get_next_batch(batch_size as usize);
+
+        let progress = ScanProgress {
+            total_files: scan_state.get_total_count(),
+            scanned_files: scan_state.get_scanned_count(),
+            current_batch,
+            is_complete: scan_state.is_complete,
+            summary: scan_state.summary.clone(),
+        };
+
+        // 全部取完后释放缓存的扫描结果
+        if progress.is_complete {
+            states.remove(&category_id);
+        }
+
+        Ok(progress)
+    }
+
+    /// 将绑定目录下的文件系统变化增量应用到资源表
+    /// 路径存在时插入或更新（目录会递归展开），不存在或被忽略时删除该路径及其下的所有记录
+    pub fn apply_changes(&self, binding_id: i64, paths: &[PathBuf]) -> Result<ScanSummary> {
+        let binding = self.get_binding(binding_id)?;
+        let category_id = binding.category_id;
+        let rules = IgnoreRules::for_binding(&binding);
+
+        // 先在数据库锁之外收集文件信息，不跟随符号链接时符号链接本身不计入资源
+        let mut upserts = Vec::new();
+        let mut removals = Vec::new();
+        for path in paths {
+            let metadata = if rules.options().follow_symlinks {
+                std::fs::metadata(path)
+            } else {
+                std::fs::symlink_metadata(path)
+            };
+            match metadata {
+                Ok(metadata) if rules.is_path_ignored(path, metadata.is_dir()) => {
+                    removals.push(path.to_string_lossy().to_string());
                 }
+                Ok(metadata) if metadata.is_file() => {
+                    upserts.push(build_resource(path, &metadata));
+                }
+                Ok(metadata) if metadata.is_dir() => {
+                    upserts.extend(self.scan_path(&rules, path, &mut |_| true)?);
+                }
+                Ok(_) => {}
+                Err(_) => removals.push(path.to_string_lossy().to_string()),
+            }
+        }
+        upserts.iter_mut().for_each(detect_file_type);
+
+        let conn = self.db.get_connection();
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut summary = ScanSummary::default();
 
-                // 保存到数据库
-                self.save_resources(category_id, &scan_state.all_resources)?;
+        {
+            let mut select_stmt = tx.prepare(
+                "SELECT id, file_size, modified_at, mime_type IS NULL FROM resources WHERE file_path = ?",
+            )?;
+            let mut insert_stmt = tx.prepare(
+                "INSERT INTO resources
+                 (category_id, file_name, file_path, file_size, file_type, mime_type, kind, modified_at, scanned_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )?;
+            let mut update_stmt = tx.prepare(
+                "UPDATE resources
+                 SET category_id = ?, file_name = ?, file_size = ?, file_type = ?, mime_type = ?, kind = ?,
+                     modified_at = ?, scanned_at = ?
+                 WHERE id = ?",
+            )?;
+            let mut type_stmt = tx.prepare(
+                "UPDATE resources SET file_type = ?, mime_type = ?, kind = ? WHERE id = ?",
+            )?;
+
+            for resource in &upserts {
+                let existing: Option<(i64, i64, String, bool)> = select_stmt
+                    .query_row(params![resource.file_path], |row| {
+                        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+                    })
+                    .optional()?;
+
+                match existing {
+                    Some((id, file_size, modified_at, untyped))
+                        if file_size == resource.file_size && modified_at == resource.modified_at =>
+                    {
+                        // 升级前扫描的记录还没有识别格式，顺便补全
+                        if untyped {
+                            type_stmt.execute(params![
+                                resource.file_type,
+                                resource.mime_type,
+                                resource.kind.as_str(),
+                                id,
+                            ])?;
+                        }
+                        summary.unchanged += 1;
+                    }
+                    Some((id, _, _, _)) => {
+                        update_stmt.execute(params![
+                            category_id,
+                            resource.file_name,
+                            resource.file_size,
+                            resource.file_type,
+                            resource.mime_type,
+                            resource.kind.as_str(),
+                            resource.modified_at,
+                            resource.scanned_at,
+                            id,
+                        ])?;
+                        summary.changed += 1;
+                    }
+                    None => {
+                        insert_stmt.execute(params![
+                            category_id,
+                            resource.file_name,
+                            resource.file_path,
+                            resource.file_size,
+                            resource.file_type,
+                            resource.mime_type,
+                            resource.kind.as_str(),
+                            resource.modified_at,
+                            resource.scanned_at,
+                        ])?;
+                        summary.added += 1;
+                    }
+                }
             }
 
-            // 获取下一批数据
-            let current_batch = scan_state.get_next_batch(batch_size as usize);
+            // 删除的可能是文件，也可能是整个目录
+            let mut delete_stmt = tx.prepare(
+                "DELETE FROM resources
+                 WHERE category_id = ? AND (file_path = ? OR substr(file_path, 1, ?) = ?)",
+            )?;
+            for path in &removals {
+                let prefix = format!("{}{}", path, std::path::MAIN_SEPARATOR);
+                let removed = delete_stmt.execute(params![
+                    category_id,
+                    path,
+                    prefix.chars().count() as i64,
+                    prefix,
+                ])?;
+                summary.removed += removed as i64;
+            }
+        }
 
-            Ok(ScanProgress {
-                total_files: scan_state.get_total_count(),
-                scanned_files: scan_state.get_scanned_count(),
-                current_batch,
-                is_complete: scan_state.is_complete,
-            })
-        })
+        tx.commit()?;
+        Ok(summary)
     }
 
-    /// 扫描单个路径
-    fn scan_path(&self, path: &str, show_hidden: bool, ignore_directories: &[String]) -> Result<Vec<ResourceItem>> {
+    /// 按忽略规则遍历路径，被忽略的目录不会进入
+    fn scan_path(
+        &self,
+        rules: &IgnoreRules,
+        path: &Path,
+        on_file: &mut dyn FnMut(&Path) -> bool,
+    ) -> Result<Vec<ResourceItem>> {
         let mut resources = Vec::new();
 
-        for entry in WalkDir::new(path).follow_links(false) {
+        for entry in rules.walk(path) {
             match entry {
                 Ok(entry) => {
-                    // 检查是否应该跳过该目录
-                    if entry.file_type().is_dir() {
-                        if let Some(dir_name) = entry.file_name().to_str() {
-                            if ignore_directories.contains(&dir_name.to_lowercase()) {
-                                // 跳过该目录及其子目录
-                                continue;
-                            }
-                        }
+                    if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+                        continue;
                     }
 
-                    if entry.file_type().is_file() {
-                        let file_path = entry.path();
-                        
-                        // 检查是否为隐藏文件
-                        if !show_hidden && is_hidden(file_path) {
-                            continue;
-                        }
-
-                        // 检查文件路径中是否包含忽略的目录
-                        let should_skip = ignore_directories.iter().any(|ignore_dir| {
-                            file_path.components()
-                                .any(|component| {
-                                    component.as_os_str()
-                                        .to_str()
-                                        .map(|s| s.eq_ignore_ascii_case(ignore_dir))
-                                        .unwrap_or(false)
-                                })
-                        });
-
-                        if should_skip {
-                            continue;
-                        }
+                    let file_path = entry.path();
+                    if !on_file(file_path) {
+                        return Err(AppError::Cancelled);
+                    }
 
-                        if let Ok(metadata) = entry.metadata() {
-                            let file_name = entry.file_name().to_string_lossy().to_string();
-                            let file_path_str = file_path.to_string_lossy().to_string();
-                            let file_size = metadata.len() as i64;
-                            let file_type = get_file_type(file_path);
-                            let modified_at = metadata
-                                .modified()
-                                .ok()
-                                .and_then(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339().parse().ok())
-                                .unwrap_or_else(|| chrono::Utc::now().to_rfc3339());
-
-                            resources.push(ResourceItem {
-                                id: 0, // 临时 ID
-                                category_id: 0, // 临时 category_id
-                                file_name,
-                                file_path: file_path_str,
-                                file_size,
-                                file_type,
-                                modified_at,
-                                scanned_at: chrono::Utc::now().to_rfc3339(),
-                            });
-                        }
+                    if let Ok(metadata) = entry.metadata() {
+                        resources.push(build_resource(file_path, &metadata));
                     }
                 }
                 Err(e) => {
@@ -302,50 +455,219 @@ impl DirectoryService {
         Ok(resources)
     }
 
-    /// 保存资源到数据库
-    fn save_resources(&self, category_id: i64, resources: &[ResourceItem]) -> Result<()> {
-        let conn = self.db.get_connection();
-        let conn = conn.lock().unwrap();
+    /// 补全资源的实际格式：大小和修改时间未变的文件沿用数据库中已识别的结果，
+    /// 其余文件读取文件头识别；文件读取在数据库锁之外进行
+    fn resolve_file_types(&self, category_id: i64, resources: &mut [ResourceItem]) -> Result<()> {
+        let known: HashMap<String, (i64, String, String, String, String)> = {
+            let conn = self.db.get_connection();
+            let conn = conn.lock().unwrap();
+            let mut stmt = conn.prepare(
+                "SELECT file_path, file_size, modified_at, file_type, mime_type, kind
+                 FROM resources WHERE category_id = ? AND mime_type IS NOT NULL",
+            )?;
+            let rows = stmt.query_map(params![category_id], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    (
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                        row.get(4)?,
+                        row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+                    ),
+                ))
+            })?;
+            rows.collect::<std::result::Result<HashMap<_, _>, _>>()?
+        };
+
+        for resource in resources.iter_mut() {
+            match known.get(&resource.file_path) {
+                Some((file_size, modified_at, file_type, mime_type, kind))
+                    if *file_size == resource.file_size && *modified_at == resource.modified_at =>
+                {
+                    resource.file_type = file_type.clone();
+                    resource.mime_type = mime_type.clone();
+                    resource.kind = FileKind::parse(kind);
+                }
+                _ => detect_file_type(resource),
+            }
+        }
 
-        // 先删除该分类的旧资源
-        conn.execute(
-            "DELETE FROM resources WHERE category_id = ?",
-            params![category_id],
-        )?;
+        Ok(())
+    }
 
-        // 批量插入新资源
-        for resource in resources {
-            conn.execute(
-                "INSERT OR REPLACE INTO resources 
-                 (category_id, file_name, file_path, file_size, file_type, modified_at) 
-                 VALUES (?, ?, ?, ?, ?, ?)",
-                params![
-                    category_id,
-                    resource.file_name,
-                    resource.file_path,
-                    resource.file_size,
-                    resource.file_type,
-                    resource.modified_at,
-                ],
+    /// 增量保存资源到数据库
+    /// 按路径与已有记录比对大小和修改时间，只插入、更新或删除有变化的记录，
+    /// 并回填资源的 ID 和分类 ID
+    fn save_resources(&self, category_id: i64, resources: &mut [ResourceItem]) -> Result<ScanSummary> {
+        self.resolve_file_types(category_id, resources)?;
+
+        let conn = self.db.get_connection();
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut summary = ScanSummary::default();
+
+        // 读取该分类已有的资源
+        let mut existing: HashMap<String, (i64, i64, String, bool)> = {
+            let mut stmt = tx.prepare(
+                "SELECT id, file_path, file_size, modified_at, mime_type IS NULL FROM resources WHERE category_id = ?",
+            )?;
+            let rows = stmt.query_map(params![category_id], |row| {
+                Ok((row.get::<_, String>(1)?, (row.get(0)?, row.get(2)?, row.get(3)?, row.get(4)?)))
+            })?;
+            rows.collect::<std::result::Result<HashMap<_, _>, _>>()?
+        };
+
+        {
+            // file_path 全局唯一，路径已被其他分类占用时先移除旧记录；
+            // 不使用 INSERT OR REPLACE，因为其隐式删除不会触发全文索引的同步触发器
+            let mut steal_stmt = tx.prepare("DELETE FROM resources WHERE file_path = ?")?;
+            let mut insert_stmt = tx.prepare(
+                "INSERT INTO resources
+                 (category_id, file_name, file_path, file_size, file_type, mime_type, kind, modified_at, scanned_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )?;
+            let mut update_stmt = tx.prepare(
+                "UPDATE resources
+                 SET file_name = ?, file_size = ?, file_type = ?, mime_type = ?, kind = ?, modified_at = ?, scanned_at = ?
+                 WHERE id = ?",
             )?;
+            let mut type_stmt = tx.prepare(
+                "UPDATE resources SET file_type = ?, mime_type = ?, kind = ? WHERE id = ?",
+            )?;
+
+            // 多个绑定目录存在重叠时同一路径可能出现多次，只处理第一次
+            let mut processed: HashMap<String, i64> = HashMap::new();
+
+            for resource in resources.iter_mut() {
+                resource.category_id = category_id;
+
+                if let Some(id) = processed.get(&resource.file_path) {
+                    resource.id = *id;
+                    continue;
+                }
+
+                match existing.remove(&resource.file_path) {
+                    Some((id, file_size, modified_at, untyped)) => {
+                        resource.id = id;
+                        processed.insert(resource.file_path.clone(), id);
+                        if file_size == resource.file_size && modified_at == resource.modified_at {
+                            if untyped {
+                                type_stmt.execute(params![
+                                    resource.file_type,
+                                    resource.mime_type,
+                                    resource.kind.as_str(),
+                                    id,
+                                ])?;
+                            }
+                            summary.unchanged += 1;
+                            continue;
+                        }
+                        update_stmt.execute(params![
+                            resource.file_name,
+                            resource.file_size,
+                            resource.file_type,
+                            resource.mime_type,
+                            resource.kind.as_str(),
+                            resource.modified_at,
+                            resource.scanned_at,
+                            id,
+                        ])?;
+                        summary.changed += 1;
+                    }
+                    None => {
+                        steal_stmt.execute(params![resource.file_path])?;
+                        insert_stmt.execute(params![
+                            category_id,
+                            resource.file_name,
+                            resource.file_path,
+                            resource.file_size,
+                            resource.file_type,
+                            resource.mime_type,
+                            resource.kind.as_str(),
+                            resource.modified_at,
+                            resource.scanned_at,
+                        ])?;
+                        resource.id = tx.last_insert_rowid();
+                        processed.insert(resource.file_path.clone(), resource.id);
+                        summary.added += 1;
+                    }
+                }
+            }
+
+            // 本次扫描未出现的记录即为已删除的文件
+            let mut delete_stmt = tx.prepare("DELETE FROM resources WHERE id = ?")?;
+            for (id, _, _, _) in existing.values() {
+                delete_stmt.execute(params![id])?;
+                summary.removed += 1;
+            }
         }
 
-        Ok(())
+        tx.commit()?;
+
+        log::info!(
+            "Saved resources for category {}: {} added, {} changed, {} removed, {} unchanged",
+            category_id,
+            summary.added,
+            summary.changed,
+            summary.removed,
+            summary.unchanged
+        );
+
+        Ok(summary)
     }
 }
 
-/// 检查文件是否为隐藏文件
-fn is_hidden(path: &Path) -> bool {
-    path.file_name()
-        .and_then(|name| name.to_str())
-        .map(|name| name.starts_with('.'))
-        .unwrap_or(false)
+const BINDING_COLUMNS: &str =
+    "id, category_id, directory_path, created_at, watch_enabled, ignore_rules, show_hidden, follow_symlinks, max_depth";
+
+fn map_binding(row: &rusqlite::Row) -> rusqlite::Result<DirectoryBinding> {
+    Ok(DirectoryBinding {
+        id: row.get(0)?,
+        category_id: row.get(1)?,
+        directory_path: row.get(2)?,
+        created_at: row.get(3)?,
+        watch_enabled: row.get(4)?,
+        ignore_rules: row.get(5)?,
+        show_hidden: row.get(6)?,
+        follow_symlinks: row.get(7)?,
+        max_depth: row.get(8)?,
+    })
+}
+
+/// 根据文件元数据构建资源记录（ID 和分类 ID 在保存时回填）
+pub(crate) fn build_resource(file_path: &Path, metadata: &std::fs::Metadata) -> ResourceItem {
+    let file_name = file_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let modified_at = metadata
+        .modified()
+        .ok()
+        .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339())
+        .unwrap_or_else(|| chrono::Utc::now().to_rfc3339());
+
+    // 先按文件名识别格式，保存前再对新增或变化的文件读取文件头
+    let info = file_type::from_name(file_path);
+
+    ResourceItem {
+        id: 0, // 临时 ID
+        category_id: 0, // 临时 category_id
+        file_name,
+        file_path: file_path.to_string_lossy().to_string(),
+        file_size: metadata.len() as i64,
+        file_type: info.file_type,
+        mime_type: info.mime_type,
+        kind: info.kind,
+        modified_at,
+        scanned_at: chrono::Utc::now().to_rfc3339(),
+    }
 }
 
-/// 获取文件类型
-fn get_file_type(path: &Path) -> String {
-    path.extension()
-        .and_then(|ext| ext.to_str())
-        .unwrap_or("unknown")
-        .to_string()
+/// 读取文件头识别资源的实际格式
+fn detect_file_type(resource: &mut ResourceItem) {
+    let info = file_type::detect(Path::new(&resource.file_path));
+    resource.file_type = info.file_type;
+    resource.mime_type = info.mime_type;
+    resource.kind = info.kind;
 }
@@ -0,0 +1,570 @@
+use crate::db::models::{
+    DuplicateAction, DuplicateGroup, DuplicateResolution, ResolveDuplicatesRequest, ResolveDuplicatesResult,
+    ResourceItem,
+};
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::services::directory::build_resource;
+use crate::services::resource::{map_resource, RESOURCE_COLUMNS, RESOURCE_COLUMN_COUNT};
+use crate::services::job::{Job, JobRegistry, JobState, JobStatus};
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Runtime};
+use uuid::Uuid;
+
+/// 重复文件检测进度事件名
+pub const DUPLICATE_PROGRESS_EVENT: &str = "duplicates://progress";
+
+/// 部分哈希只读取文件开头的字节数，不超过该大小的文件部分哈希即完整哈希
+const PARTIAL_HASH_BYTES: u64 = 64 * 1024;
+
+/// 检测阶段：按大小分组 → 部分哈希 → 完整哈希
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicatePhase {
+    Grouping,
+    PartialHash,
+    FullHash,
+}
+
+/// 重复文件检测任务状态，同时作为 duplicates://progress 事件的负载
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateJobStatus {
+    pub job_id: String,
+    pub state: JobState,
+    pub phase: DuplicatePhase,
+    /// 当前阶段已处理 / 需处理的文件数
+    pub processed: i64,
+    pub total: i64,
+    /// 检测完成后的重复分组，按浪费空间从大到小排列
+    pub groups: Option<Vec<DuplicateGroup>>,
+    pub wasted_bytes: i64,
+    pub error: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+impl JobStatus for DuplicateJobStatus {
+    fn job_id(&self) -> &str {
+        &self.job_id
+    }
+
+    fn state(&self) -> JobState {
+        self.state
+    }
+
+    fn finish(&mut self, state: JobState, error: Option<String>) {
+        self.state = state;
+        self.error = error;
+        self.finished_at = Some(chrono::Utc::now().to_rfc3339());
+    }
+}
+
+/// 候选文件：大小与其他文件相同的资源
+struct Candidate {
+    item: ResourceItem,
+    partial_hash: Option<String>,
+    content_hash: Option<String>,
+}
+
+/// 重复文件服务
+pub struct DuplicateService {
+    db: Arc<Database>,
+}
+
+impl DuplicateService {
+    pub fn new(db: Arc<Database>) -> Self {
+        DuplicateService { db }
+    }
+
+    /// 查找内容相同的资源
+    /// 先按大小分组，再用部分哈希、完整哈希逐步确认，已计算的哈希会写回 resources 表复用；
+    /// on_progress 返回 false 时取消检测
+    pub fn find_duplicates(
+        &self,
+        excluded_category_ids: &[i64],
+        on_progress: &mut dyn FnMut(DuplicatePhase, i64, i64) -> bool,
+    ) -> Result<Vec<DuplicateGroup>> {
+        if !on_progress(DuplicatePhase::Grouping, 0, 0) {
+            return Err(AppError::Cancelled);
+        }
+        let candidates = self.load_candidates(excluded_category_ids)?;
+
+        // 第一轮：大小相同的文件计算部分哈希
+        let candidates = self.hash_candidates(candidates, DuplicatePhase::PartialHash, on_progress)?;
+        let groups = group_by(candidates, |c| c.partial_hash.clone());
+
+        // 第二轮：部分哈希相同的文件计算完整哈希
+        let candidates = groups.into_iter().flatten().collect();
+        let candidates = self.hash_candidates(candidates, DuplicatePhase::FullHash, on_progress)?;
+
+        let mut groups: Vec<DuplicateGroup> = group_by(candidates, |c| c.content_hash.clone())
+            .into_iter()
+            .filter_map(|group| {
+                let content_hash = group[0].content_hash.clone()?;
+                let file_size = group[0].item.file_size;
+                let resources: Vec<ResourceItem> = group.into_iter().map(|c| c.item).collect();
+
+                // 互为硬链接的文件不占用额外空间
+                let copies = distinct_files(&resources);
+                if copies < 2 {
+                    return None;
+                }
+
+                Some(DuplicateGroup {
+                    content_hash,
+                    file_size,
+                    wasted_bytes: file_size * (copies as i64 - 1),
+                    resources,
+                })
+            })
+            .collect();
+        groups.sort_by_key(|g| Reverse(g.wasted_bytes));
+
+        Ok(groups)
+    }
+
+    /// 处理重复文件，每个副本在操作前都会确认其内容哈希与保留文件一致且文件未被修改
+    pub fn resolve_duplicates(&self, req: &ResolveDuplicatesRequest) -> Result<ResolveDuplicatesResult> {
+        let mut result = ResolveDuplicatesResult::default();
+
+        for group in &req.groups {
+            if let Err(e) = self.resolve_group(req.action, group, &mut result) {
+                result.failed.push(format!("group {}: {}", group.keep_id, e));
+            }
+        }
+
+        log::info!(
+            "Resolved duplicates ({:?}): {} succeeded, {} failed, {} bytes reclaimed",
+            req.action,
+            result.success.len(),
+            result.failed.len(),
+            result.reclaimed_bytes
+        );
+
+        Ok(result)
+    }
+
+    /// 获取资源所属的分类 ID（去重），用于在处理前校验分类是否已解锁
+    pub fn resource_category_ids(&self, resource_ids: &[i64]) -> Result<Vec<i64>> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+
+        let mut stmt = conn.prepare("SELECT category_id FROM resources WHERE id = ?")?;
+        let mut categories = HashSet::new();
+        for id in resource_ids {
+            if let Some(category_id) = stmt.query_row(params![id], |row| row.get(0)).optional()? {
+                categories.insert(category_id);
+            }
+        }
+
+        Ok(categories.into_iter().collect())
+    }
+
+    fn load_candidates(&self, excluded_category_ids: &[i64]) -> Result<Vec<Candidate>> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+
+        let exclusion = if excluded_category_ids.is_empty() {
+            String::new()
+        } else {
+            format!(
+                " AND r.category_id NOT IN ({})",
+                vec!["?"; excluded_category_ids.len()].join(", ")
+            )
+        };
+        let sql = format!(
+            "SELECT {columns}, r.partial_hash, r.content_hash
+             FROM resources r
+             WHERE r.file_size > 0{exclusion}
+               AND r.file_size IN (
+                   SELECT r.file_size FROM resources r
+                   WHERE r.file_size > 0{exclusion}
+                   GROUP BY r.file_size HAVING COUNT(*) > 1
+               )
+             ORDER BY r.file_size DESC, r.id",
+            columns = RESOURCE_COLUMNS,
+            exclusion = exclusion
+        );
+
+        // 排除条件在 SQL 中出现两次
+        let params: Vec<&dyn rusqlite::ToSql> = excluded_category_ids
+            .iter()
+            .chain(excluded_category_ids.iter())
+            .map(|id| id as &dyn rusqlite::ToSql)
+            .collect();
+
+        let mut stmt = conn.prepare(&sql)?;
+        let candidates = stmt
+            .query_map(params.as_slice(), |row| {
+                Ok(Candidate {
+                    item: map_resource(row)?,
+                    partial_hash: row.get(RESOURCE_COLUMN_COUNT)?,
+                    content_hash: row.get(RESOURCE_COLUMN_COUNT + 1)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(candidates)
+    }
+
+    /// 为缺少哈希的候选文件计算哈希并写回数据库，无法读取的文件会被剔除
+    fn hash_candidates(
+        &self,
+        candidates: Vec<Candidate>,
+        phase: DuplicatePhase,
+        on_progress: &mut dyn FnMut(DuplicatePhase, i64, i64) -> bool,
+    ) -> Result<Vec<Candidate>> {
+        let total = candidates.len() as i64;
+        let mut hashed = Vec::with_capacity(candidates.len());
+        let mut computed: Vec<(i64, String)> = Vec::new();
+        let mut cancelled = false;
+
+        for (index, mut candidate) in candidates.into_iter().enumerate() {
+            if !on_progress(phase, index as i64, total) {
+                cancelled = true;
+                break;
+            }
+
+            let existing = match phase {
+                DuplicatePhase::PartialHash => candidate.partial_hash.clone(),
+                _ => candidate.content_hash.clone(),
+            };
+            let hash = match existing {
+                Some(hash) => hash,
+                None => {
+                    let small_file = candidate.item.file_size as u64 <= PARTIAL_HASH_BYTES;
+                    let hashed = match (phase, &candidate.partial_hash) {
+                        (DuplicatePhase::PartialHash, _) => {
+                            hash_file(Path::new(&candidate.item.file_path), Some(PARTIAL_HASH_BYTES))
+                        }
+                        (_, Some(partial)) if small_file => Ok(partial.clone()),
+                        _ => hash_file(Path::new(&candidate.item.file_path), None),
+                    };
+                    match hashed {
+                        Ok(hash) => {
+                            computed.push((candidate.item.id, hash.clone()));
+                            hash
+                        }
+                        Err(e) => {
+                            log::debug!("Skipping {}: {}", candidate.item.file_path, e);
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            match phase {
+                DuplicatePhase::PartialHash => candidate.partial_hash = Some(hash),
+                _ => candidate.content_hash = Some(hash),
+            }
+            hashed.push(candidate);
+        }
+
+        // 取消时也保存已经算好的哈希，下次检测可以直接复用
+        self.store_hashes(phase, &computed)?;
+        if cancelled {
+            return Err(AppError::Cancelled);
+        }
+        on_progress(phase, total, total);
+
+        Ok(hashed)
+    }
+
+    fn store_hashes(&self, phase: DuplicatePhase, hashes: &[(i64, String)]) -> Result<()> {
+        if hashes.is_empty() {
+            return Ok(());
+        }
+
+        let column = match phase {
+            DuplicatePhase::PartialHash => "partial_hash",
+            _ => "content_hash",
+        };
+        let conn = self.db.get_connection();
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(&format!("UPDATE resources SET {} = ? WHERE id = ?", column))?;
+            for (id, hash) in hashes {
+                stmt.execute(params![hash, id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn resolve_group(
+        &self,
+        action: DuplicateAction,
+        group: &DuplicateResolution,
+        result: &mut ResolveDuplicatesResult,
+    ) -> Result<()> {
+        let keep = self.load_verified(group.keep_id)?;
+        let keep_path = PathBuf::from(&keep.0.file_path);
+
+        for &id in &group.duplicate_ids {
+            match self.resolve_one(action, &keep, &keep_path, id) {
+                Ok(reclaimed) => {
+                    result.success.push(id);
+                    result.reclaimed_bytes += reclaimed;
+                }
+                Err(e) => result.failed.push(format!("{}: {}", id, e)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 处理单个副本，返回释放的字节数
+    fn resolve_one(
+        &self,
+        action: DuplicateAction,
+        keep: &(ResourceItem, String),
+        keep_path: &Path,
+        duplicate_id: i64,
+    ) -> Result<i64> {
+        if duplicate_id == keep.0.id {
+            return Err(AppError::InvalidInput("Cannot resolve a file against itself".to_string()));
+        }
+        let (duplicate, hash) = self.load_verified(duplicate_id)?;
+        if hash != keep.1 || duplicate.file_size != keep.0.file_size {
+            return Err(AppError::InvalidInput(format!(
+                "{} does not match the kept file",
+                duplicate.file_path
+            )));
+        }
+
+        let duplicate_path = PathBuf::from(&duplicate.file_path);
+        let already_linked = same_file(keep_path, &duplicate_path);
+        let reclaimed = if already_linked { 0 } else { duplicate.file_size };
+
+        match action {
+            DuplicateAction::Delete | DuplicateAction::Trash => {
+                if action == DuplicateAction::Delete {
+                    std::fs::remove_file(&duplicate_path)?;
+                } else {
+                    trash::delete(&duplicate_path)
+                        .map_err(|e| AppError::Internal(format!("Failed to move to trash: {}", e)))?;
+                }
+                let conn = self.db.get_connection();
+                let conn = conn.lock().unwrap();
+                conn.execute("DELETE FROM resources WHERE id = ?", params![duplicate_id])?;
+            }
+            DuplicateAction::Hardlink => {
+                if !already_linked {
+                    replace_with_hard_link(keep_path, &duplicate_path)?;
+                }
+                // 硬链接与保留文件共享元数据，同步修改时间避免下次扫描误判为已修改
+                let conn = self.db.get_connection();
+                let conn = conn.lock().unwrap();
+                conn.execute(
+                    "UPDATE resources SET modified_at = ? WHERE id = ?",
+                    params![keep.0.modified_at, duplicate_id],
+                )?;
+            }
+        }
+
+        Ok(reclaimed)
+    }
+
+    /// 读取资源及其内容哈希，并确认磁盘上的文件自计算哈希后没有变化
+    fn load_verified(&self, resource_id: i64) -> Result<(ResourceItem, String)> {
+        let (item, hash) = {
+            let conn = self.db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.query_row(
+                &format!("SELECT {}, r.content_hash FROM resources r WHERE r.id = ?", RESOURCE_COLUMNS),
+                params![resource_id],
+                |row| Ok((map_resource(row)?, row.get::<_, Option<String>>(RESOURCE_COLUMN_COUNT)?)),
+            )
+            .optional()?
+            .ok_or_else(|| AppError::InvalidInput(format!("Resource not found: {}", resource_id)))?
+        };
+
+        let hash = hash.ok_or_else(|| {
+            AppError::InvalidInput(format!("{} has not been hashed yet", item.file_path))
+        })?;
+
+        let path = Path::new(&item.file_path);
+        let current = build_resource(path, &std::fs::metadata(path)?);
+        if current.file_size != item.file_size || current.modified_at != item.modified_at {
+            return Err(AppError::InvalidInput(format!(
+                "{} changed since it was scanned",
+                item.file_path
+            )));
+        }
+
+        Ok((item, hash))
+    }
+}
+
+/// 按哈希分组，只保留包含两个及以上文件的组（大小不同的文件不会被分到同一组）
+fn group_by<F>(candidates: Vec<Candidate>, key: F) -> Vec<Vec<Candidate>>
+where
+    F: Fn(&Candidate) -> Option<String>,
+{
+    let mut groups: HashMap<(i64, String), Vec<Candidate>> = HashMap::new();
+    for candidate in candidates {
+        if let Some(hash) = key(&candidate) {
+            groups
+                .entry((candidate.item.file_size, hash))
+                .or_default()
+                .push(candidate);
+        }
+    }
+    groups.into_values().filter(|group| group.len() > 1).collect()
+}
+
+/// 计算文件内容的 BLAKE3 哈希，limit 为 Some 时只读取开头的部分字节
+fn hash_file(path: &Path, limit: Option<u64>) -> std::io::Result<String> {
+    let file = File::open(path)?;
+    let mut reader: Box<dyn Read> = match limit {
+        Some(limit) => Box::new(file.take(limit)),
+        None => Box::new(file),
+    };
+    let mut hasher = blake3::Hasher::new();
+    std::io::copy(&mut reader, &mut hasher)?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+/// 用指向 source 的硬链接替换 target：先在同一目录创建临时链接再重命名覆盖，
+/// 失败时原文件保持不变
+fn replace_with_hard_link(source: &Path, target: &Path) -> Result<()> {
+    let file_name = target
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp = target.with_file_name(format!(".{}.{}.tmp", file_name, Uuid::new_v4()));
+
+    std::fs::hard_link(source, &temp)
+        .map_err(|e| AppError::Internal(format!("Failed to create hard link: {}", e)))?;
+    if let Err(e) = std::fs::rename(&temp, target) {
+        let _ = std::fs::remove_file(&temp);
+        return Err(AppError::Internal(format!("Failed to replace file: {}", e)));
+    }
+
+    Ok(())
+}
+
+/// 统计实际占用空间的文件数，互为硬链接的路径只算一份
+fn distinct_files(resources: &[ResourceItem]) -> usize {
+    let mut seen = HashSet::new();
+    resources
+        .iter()
+        .filter(|r| match file_identity(Path::new(&r.file_path)) {
+            Some(identity) => seen.insert(identity),
+            None => true,
+        })
+        .count()
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (file_identity(a), file_identity(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[cfg(unix)]
+fn file_identity(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).ok().map(|m| (m.dev(), m.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_identity(_path: &Path) -> Option<(u64, u64)> {
+    None
+}
+
+/// 后台重复文件检测任务管理器
+/// @description 排除范围相同的检测任务同一时间只运行一个，重复启动会返回正在运行的任务
+pub struct DuplicateJobManager {
+    jobs: JobRegistry<DuplicateJobStatus>,
+}
+
+impl Default for DuplicateJobManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DuplicateJobManager {
+    pub fn new() -> Self {
+        DuplicateJobManager {
+            jobs: JobRegistry::new("duplicate", DUPLICATE_PROGRESS_EVENT),
+        }
+    }
+
+    /// 启动后台检测，返回任务 ID；excluded_category_ids 中的分类不参与检测
+    /// 已有排除范围相同的任务在运行时直接返回其 ID
+    pub fn start<R: Runtime>(
+        &self,
+        app: AppHandle<R>,
+        db: Arc<Database>,
+        excluded_category_ids: Vec<i64>,
+    ) -> Result<String> {
+        let job_id = self.jobs.start(
+            app,
+            excluded_category_ids,
+            |_| true,
+            |job_id| DuplicateJobStatus {
+                job_id,
+                state: JobState::Running,
+                phase: DuplicatePhase::Grouping,
+                processed: 0,
+                total: 0,
+                groups: None,
+                wasted_bytes: 0,
+                error: None,
+                started_at: chrono::Utc::now().to_rfc3339(),
+                finished_at: None,
+            },
+            move |job, app| run_job(app, db, job),
+        );
+        Ok(job_id)
+    }
+
+    /// 获取任务状态，locked_category_ids 为调用方未解锁的分类
+    pub fn get(&self, job_id: &str, locked_category_ids: &[i64]) -> Result<DuplicateJobStatus> {
+        self.jobs.get(job_id, locked_category_ids)
+    }
+
+    /// 请求取消任务，已计算的哈希仍会保存
+    pub fn cancel(&self, job_id: &str, locked_category_ids: &[i64]) -> Result<()> {
+        self.jobs.cancel(job_id, locked_category_ids)
+    }
+}
+
+fn run_job<R: Runtime>(app: &AppHandle<R>, db: Arc<Database>, job: &Job<DuplicateJobStatus>) -> Result<()> {
+    let service = DuplicateService::new(db);
+
+    let groups = service.find_duplicates(job.excluded_category_ids(), &mut |phase, processed, total| {
+        if job.is_cancelled() {
+            return false;
+        }
+
+        // 阶段切换时立即推送，不受推送间隔限制
+        let phase_changed = job.snapshot().phase != phase;
+        let progress = job.update_progress(phase_changed, |status| {
+            status.phase = phase;
+            status.processed = processed;
+            status.total = total;
+        });
+        if let Some(snapshot) = progress {
+            let _ = app.emit(DUPLICATE_PROGRESS_EVENT, snapshot);
+        }
+        true
+    })?;
+
+    job.update(|status| {
+        status.wasted_bytes = groups.iter().map(|g| g.wasted_bytes).sum();
+        status.groups = Some(groups);
+    });
+    Ok(())
+}
@@ -0,0 +1,255 @@
+use crate::db::models::FileKind;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// 读取文件头的字节数，足以覆盖 tar、Office 等偏移较大的签名
+const HEADER_SIZE: usize = 8192;
+
+/// 魔数只能识别出通用容器格式时（如 docx 以外的 zip 包、OLE 复合文档、XML），
+/// 扩展名往往更准确，此时优先采用扩展名的识别结果
+const GENERIC_CONTAINERS: [&str; 5] = [
+    "application/zip",
+    "application/x-ole-storage",
+    "application/octet-stream",
+    "application/x-unix-archive",
+    "text/xml",
+];
+
+const CODE_EXTENSIONS: &[&str] = &[
+    "rs", "c", "h", "cc", "cpp", "cxx", "hpp", "cs", "go", "java", "kt", "kts", "scala", "swift",
+    "m", "mm", "py", "rb", "php", "pl", "lua", "r", "dart", "js", "mjs", "cjs", "jsx", "ts",
+    "tsx", "vue", "svelte", "html", "htm", "css", "scss", "sass", "less", "json", "jsonc", "yaml",
+    "yml", "toml", "ini", "xml", "sql", "sh", "bash", "zsh", "fish", "ps1", "bat", "cmd", "gradle",
+    "cmake", "proto", "graphql", "ipynb",
+];
+
+const DOCUMENT_EXTENSIONS: &[&str] = &[
+    "txt", "md", "markdown", "rst", "log", "csv", "tsv", "pdf", "doc", "docx", "xls", "xlsx",
+    "ppt", "pptx", "odt", "ods", "odp", "rtf", "epub", "mobi", "pages", "numbers", "key",
+];
+
+const ARCHIVE_EXTENSIONS: &[&str] = &[
+    "zip", "rar", "7z", "tar", "gz", "tgz", "bz2", "xz", "zst", "lz4", "lz", "cab", "iso", "jar",
+];
+
+const EXECUTABLE_EXTENSIONS: &[&str] = &[
+    "exe", "dll", "msi", "so", "dylib", "app", "apk", "ipa", "deb", "rpm", "appimage", "com",
+];
+
+/// 没有扩展名但通常是源码或构建脚本的文件名
+const CODE_FILE_NAMES: &[&str] = &[
+    "makefile", "dockerfile", "rakefile", "gemfile", "jenkinsfile", "vagrantfile", "cmakelists.txt",
+];
+
+/// 文件的格式识别结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTypeInfo {
+    pub file_type: String,
+    pub mime_type: String,
+    pub kind: FileKind,
+}
+
+/// 仅根据文件名识别格式，不读取文件内容
+pub fn from_name(path: &Path) -> FileTypeInfo {
+    let extension = extension(path);
+    let (mime_type, kind) = match &extension {
+        Some(ext) => classify_extension(ext),
+        None if is_code_file_name(path) => ("text/plain".to_string(), FileKind::Code),
+        None => ("application/octet-stream".to_string(), FileKind::Other),
+    };
+
+    FileTypeInfo {
+        file_type: extension.unwrap_or_else(|| "unknown".to_string()),
+        mime_type,
+        kind,
+    }
+}
+
+/// 读取文件头识别实际格式，无法识别时退回按文件名识别
+/// 用于处理没有扩展名或扩展名与内容不符的文件
+pub fn detect(path: &Path) -> FileTypeInfo {
+    let by_name = from_name(path);
+    let header = match read_header(path) {
+        Ok(header) => header,
+        Err(e) => {
+            log::debug!("Failed to read header of {}: {}", path.display(), e);
+            return by_name;
+        }
+    };
+
+    if let Some(sniffed) = infer::get(&header) {
+        let mime_type = sniffed.mime_type();
+        let generic = GENERIC_CONTAINERS.contains(&mime_type);
+        if generic && by_name.kind != FileKind::Other {
+            return by_name;
+        }
+        let file_type = if generic && by_name.file_type != "unknown" {
+            by_name.file_type
+        } else {
+            sniffed.extension().to_lowercase()
+        };
+        return FileTypeInfo {
+            file_type,
+            mime_type: mime_type.to_string(),
+            kind: classify_mime(mime_type),
+        };
+    }
+
+    // .ts 既可能是 TypeScript 源码也可能是 MPEG 传输流，按 188 字节的包同步字节区分
+    if is_mpeg_ts(&header) {
+        return FileTypeInfo {
+            file_type: "ts".to_string(),
+            mime_type: "video/mp2t".to_string(),
+            kind: FileKind::Video,
+        };
+    }
+
+    if by_name.kind == FileKind::Other && !header.is_empty() && is_text(&header) {
+        let kind = if header.starts_with(b"#!") {
+            FileKind::Code
+        } else {
+            FileKind::Document
+        };
+        return FileTypeInfo {
+            file_type: by_name.file_type,
+            mime_type: "text/plain".to_string(),
+            kind,
+        };
+    }
+
+    by_name
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| ext.to_lowercase())
+}
+
+fn is_code_file_name(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| CODE_FILE_NAMES.contains(&name.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+fn read_header(path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut header = Vec::with_capacity(HEADER_SIZE);
+    File::open(path)?
+        .take(HEADER_SIZE as u64)
+        .read_to_end(&mut header)?;
+    Ok(header)
+}
+
+/// 按扩展名识别 MIME 类型与分类，扩展名列表优先于 MIME 推断，
+/// 避免 .ts 之类的扩展名被识别为视频
+fn classify_extension(ext: &str) -> (String, FileKind) {
+    let guessed = mime_guess::from_ext(ext).first_raw();
+    let kind = if CODE_EXTENSIONS.contains(&ext) {
+        FileKind::Code
+    } else if DOCUMENT_EXTENSIONS.contains(&ext) {
+        FileKind::Document
+    } else if ARCHIVE_EXTENSIONS.contains(&ext) {
+        FileKind::Archive
+    } else if EXECUTABLE_EXTENSIONS.contains(&ext) {
+        FileKind::Executable
+    } else {
+        guessed.map(classify_mime).unwrap_or(FileKind::Other)
+    };
+
+    let mime_type = match guessed {
+        Some(mime) if kind != FileKind::Code || mime.starts_with("text/") || mime.starts_with("application/") => {
+            mime.to_string()
+        }
+        _ if kind == FileKind::Code => "text/plain".to_string(),
+        _ => "application/octet-stream".to_string(),
+    };
+    (mime_type, kind)
+}
+
+fn classify_mime(mime: &str) -> FileKind {
+    if mime.starts_with("image/") {
+        return FileKind::Image;
+    }
+    if mime.starts_with("video/") {
+        return FileKind::Video;
+    }
+    if mime.starts_with("audio/") {
+        return FileKind::Audio;
+    }
+
+    match mime {
+        "application/zip"
+        | "application/gzip"
+        | "application/x-gzip"
+        | "application/x-tar"
+        | "application/vnd.rar"
+        | "application/x-rar-compressed"
+        | "application/x-7z-compressed"
+        | "application/x-bzip2"
+        | "application/vnd.bzip3"
+        | "application/x-xz"
+        | "application/zstd"
+        | "application/x-lz4"
+        | "application/x-lzip"
+        | "application/x-compress"
+        | "application/x-cpio"
+        | "application/vnd.ms-cab-compressed"
+        | "application/x-iso9660-image"
+        | "application/java-archive" => FileKind::Archive,
+        "application/pdf"
+        | "application/rtf"
+        | "application/msword"
+        | "application/vnd.ms-excel"
+        | "application/vnd.ms-powerpoint"
+        | "application/epub+zip"
+        | "application/x-mobipocket-ebook"
+        | "application/postscript" => FileKind::Document,
+        "application/x-executable"
+        | "application/x-mach-binary"
+        | "application/vnd.microsoft.portable-executable"
+        | "application/x-msdownload"
+        | "application/x-msi"
+        | "application/vnd.android.package-archive"
+        | "application/vnd.android.dex"
+        | "application/vnd.debian.binary-package"
+        | "application/x-rpm"
+        | "application/wasm" => FileKind::Executable,
+        "application/json"
+        | "application/javascript"
+        | "application/x-javascript"
+        | "application/xml"
+        | "application/x-sh"
+        | "application/x-shellscript"
+        | "application/java"
+        | "text/html"
+        | "text/css"
+        | "text/javascript"
+        | "text/xml"
+        | "text/x-shellscript" => FileKind::Code,
+        _ if mime.starts_with("application/vnd.openxmlformats-officedocument")
+            || mime.starts_with("application/vnd.oasis.opendocument") =>
+        {
+            FileKind::Document
+        }
+        _ if mime.starts_with("text/") => FileKind::Document,
+        _ => FileKind::Other,
+    }
+}
+
+fn is_mpeg_ts(header: &[u8]) -> bool {
+    header.len() > 188 * 2 && header[0] == 0x47 && header[188] == 0x47 && header[188 * 2] == 0x47
+}
+
+/// 不含 NUL 字节且是合法的 UTF-8（末尾可能截断在多字节字符中间）即视为文本
+fn is_text(header: &[u8]) -> bool {
+    if header.contains(&0) {
+        return false;
+    }
+    match std::str::from_utf8(header) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    }
+}
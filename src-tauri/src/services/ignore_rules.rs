@@ -0,0 +1,221 @@
+use crate::db::models::DirectoryBinding;
+use crate::error::{AppError, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{Match, Walk, WalkBuilder};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// 绑定目录中会被读取的忽略规则文件，后者优先级更高
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".ignore"];
+
+/// 每个目录生效的忽略文件规则（绑定目录到该目录之间各级的忽略文件，浅层在前）
+type DirMatcherCache = Mutex<HashMap<PathBuf, Arc<Vec<Arc<Gitignore>>>>>;
+
+/// 绑定目录的扫描选项
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    pub show_hidden: bool,
+    pub follow_symlinks: bool,
+    /// 相对绑定目录的最大深度，直接位于绑定目录下的文件深度为 1
+    pub max_depth: Option<usize>,
+}
+
+impl From<&DirectoryBinding> for ScanOptions {
+    fn from(binding: &DirectoryBinding) -> Self {
+        ScanOptions {
+            show_hidden: binding.show_hidden,
+            follow_symlinks: binding.follow_symlinks,
+            max_depth: binding.max_depth.map(|d| d as usize),
+        }
+    }
+}
+
+/// 目录扫描的忽略规则
+/// @description 规则来源按优先级从低到高依次为：目录中的 .gitignore / .ignore 文件（深层覆盖浅层）、
+/// 绑定上保存的 gitignore 语法规则，因此绑定上的 ! 规则可以重新包含忽略文件排除的路径。
+/// 所有规则都不区分大小写。被忽略的目录在遍历时直接剪枝，不会再进入其子目录，
+/// 单个路径的检查（实时监听）按相同方式逐级判断，与扫描结果一致
+pub struct IgnoreRules {
+    root: PathBuf,
+    options: ScanOptions,
+    /// 绑定级别的规则
+    binding: Option<Arc<Gitignore>>,
+    /// 各目录的忽略文件规则，同一个 IgnoreRules 内只读取一次
+    dir_matchers: Arc<DirMatcherCache>,
+}
+
+impl IgnoreRules {
+    /// 以绑定目录为根构建规则，rules 为多行 gitignore 语法
+    pub fn new(root: &Path, rules: &str, options: ScanOptions) -> Self {
+        let mut builder = GitignoreBuilder::new(root);
+        builder.case_insensitive(true).ok();
+        for line in rules.lines() {
+            if let Err(e) = builder.add_line(None, line) {
+                log::warn!("Invalid ignore rule {}: {}", line, e);
+            }
+        }
+
+        IgnoreRules {
+            root: root.to_path_buf(),
+            options,
+            binding: build_matcher(builder),
+            dir_matchers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// 按绑定上保存的规则和选项构建
+    pub fn for_binding(binding: &DirectoryBinding) -> Self {
+        Self::new(
+            Path::new(&binding.directory_path),
+            &binding.ignore_rules,
+            ScanOptions::from(binding),
+        )
+    }
+
+    pub fn options(&self) -> &ScanOptions {
+        &self.options
+    }
+
+    /// 校验规则语法，返回第一条无效规则的错误
+    pub fn validate(rules: &str) -> Result<()> {
+        let mut builder = GitignoreBuilder::new("");
+        builder.case_insensitive(true).ok();
+        for line in rules.lines() {
+            builder
+                .add_line(None, line)
+                .map_err(|e| AppError::InvalidInput(format!("Invalid ignore rule {}: {}", line, e)))?;
+        }
+        builder
+            .build()
+            .map_err(|e| AppError::InvalidInput(format!("Invalid ignore rules: {}", e)))?;
+        Ok(())
+    }
+
+    /// 从 start（绑定目录或其子目录）开始遍历，遍历过程中读取各级目录的忽略文件并剪枝
+    pub fn walk(&self, start: &Path) -> Walk {
+        // 深度限制相对绑定目录计算
+        let start_depth = start
+            .strip_prefix(&self.root)
+            .map(|relative| relative.components().count())
+            .unwrap_or(0);
+        let max_depth = self.options.max_depth.map(|d| d.saturating_sub(start_depth));
+
+        // 忽略文件由 decide 统一处理，不使用 WalkBuilder 自带的 .gitignore 支持，以保证绑定规则优先
+        let root = self.root.clone();
+        let binding = self.binding.clone();
+        let dir_matchers = Arc::clone(&self.dir_matchers);
+        let mut builder = WalkBuilder::new(start);
+        builder
+            .standard_filters(false)
+            .hidden(!self.options.show_hidden)
+            .follow_links(self.options.follow_symlinks)
+            .max_depth(max_depth)
+            .filter_entry(move |entry| {
+                let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+                !is_ignored(&root, &dir_matchers, binding.as_deref(), entry.path(), is_dir)
+            });
+        builder.build()
+    }
+
+    /// 检查单个路径是否被忽略（用于实时监听）
+    /// 与遍历时的剪枝一致：路径中任一级目录被忽略时，其下的路径都视为被忽略
+    pub fn is_path_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let relative = match path.strip_prefix(&self.root) {
+            Ok(relative) => relative,
+            Err(_) => return false,
+        };
+        if let Some(max_depth) = self.options.max_depth {
+            if relative.components().count() > max_depth {
+                return true;
+            }
+        }
+        if !self.options.show_hidden && relative.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.')) {
+            return true;
+        }
+
+        let binding = self.binding.as_deref();
+        let mut dir = self.root.clone();
+        let mut components = relative.components().peekable();
+        while let Some(component) = components.next() {
+            dir.push(component);
+            let last = components.peek().is_none();
+            if is_ignored(&self.root, &self.dir_matchers, binding, &dir, !last || is_dir) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// 判断路径本身是否被忽略（不检查上级目录）：先应用所在目录生效的忽略文件规则，再应用绑定规则
+fn is_ignored(
+    root: &Path,
+    dir_matchers: &DirMatcherCache,
+    binding: Option<&Gitignore>,
+    path: &Path,
+    is_dir: bool,
+) -> bool {
+    let files = match path.parent() {
+        Some(parent) if path != root => matchers_for_dir(root, dir_matchers, parent),
+        _ => Arc::new(Vec::new()),
+    };
+    decide(files.iter().map(|m| m.as_ref()).chain(binding), path, is_dir)
+}
+
+/// 获取目录生效的忽略文件规则：上级目录的规则加上该目录中的忽略文件，结果按目录缓存
+fn matchers_for_dir(root: &Path, cache: &DirMatcherCache, dir: &Path) -> Arc<Vec<Arc<Gitignore>>> {
+    if !dir.starts_with(root) {
+        return Arc::new(Vec::new());
+    }
+    if let Some(matchers) = cache.lock().unwrap().get(dir) {
+        return Arc::clone(matchers);
+    }
+
+    let mut matchers = match dir.parent() {
+        Some(parent) if dir != root => matchers_for_dir(root, cache, parent).as_ref().clone(),
+        _ => Vec::new(),
+    };
+    for file_name in IGNORE_FILES {
+        let file = dir.join(file_name);
+        if file.is_file() {
+            let mut builder = GitignoreBuilder::new(dir);
+            builder.case_insensitive(true).ok();
+            if let Some(e) = builder.add(&file) {
+                log::warn!("Failed to read {}: {}", file.display(), e);
+            }
+            matchers.extend(build_matcher(builder));
+        }
+    }
+
+    let matchers = Arc::new(matchers);
+    cache.lock().unwrap().insert(dir.to_path_buf(), Arc::clone(&matchers));
+    matchers
+}
+
+fn build_matcher(builder: GitignoreBuilder) -> Option<Arc<Gitignore>> {
+    match builder.build() {
+        Ok(matcher) if !matcher.is_empty() => Some(Arc::new(matcher)),
+        Ok(_) => None,
+        Err(e) => {
+            log::warn!("Failed to build ignore rules: {}", e);
+            None
+        }
+    }
+}
+
+/// 依次应用各组规则，后面的组命中时（包括 ! 否定规则）覆盖前面的结果
+fn decide<'a, I>(matchers: I, path: &Path, is_dir: bool) -> bool
+where
+    I: IntoIterator<Item = &'a Gitignore>,
+{
+    let mut ignored = false;
+    for matcher in matchers {
+        match matcher.matched(path, is_dir) {
+            Match::Ignore(_) => ignored = true,
+            Match::Whitelist(_) => ignored = false,
+            Match::None => {}
+        }
+    }
+    ignored
+}
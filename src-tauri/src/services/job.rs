@@ -0,0 +1,214 @@
+use crate::error::{AppError, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Runtime};
+use uuid::Uuid;
+
+/// 两次进度事件之间的最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 已结束的任务保留多久以便查询
+const FINISHED_JOB_TTL: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+/// 后台任务的状态，同时作为进度事件的负载
+pub trait JobStatus: Clone + Serialize + Send + 'static {
+    fn job_id(&self) -> &str;
+
+    fn state(&self) -> JobState;
+
+    /// 记录任务结束时的状态与错误信息
+    fn finish(&mut self, state: JobState, error: Option<String>);
+}
+
+/// 单个后台任务
+pub struct Job<S> {
+    status: Mutex<S>,
+    /// 启动时未解锁、不参与处理的分类（已排序）
+    excluded_category_ids: Vec<i64>,
+    cancelled: AtomicBool,
+    finished: Mutex<Option<Instant>>,
+    last_progress: Mutex<Instant>,
+}
+
+impl<S: JobStatus> Job<S> {
+    pub fn snapshot(&self) -> S {
+        self.status.lock().unwrap().clone()
+    }
+
+    pub fn excluded_category_ids(&self) -> &[i64] {
+        &self.excluded_category_ids
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// 修改任务状态，如写入任务结果
+    pub fn update(&self, update: impl FnOnce(&mut S)) {
+        update(&mut self.status.lock().unwrap());
+    }
+
+    /// 修改任务进度；force 为 true 或距上次推送超过最小间隔时返回最新状态以便推送
+    pub fn update_progress(&self, force: bool, update: impl FnOnce(&mut S)) -> Option<S> {
+        let snapshot = {
+            let mut status = self.status.lock().unwrap();
+            update(&mut status);
+            status.clone()
+        };
+        let mut last_progress = self.last_progress.lock().unwrap();
+        if force || last_progress.elapsed() >= PROGRESS_INTERVAL {
+            *last_progress = Instant::now();
+            Some(snapshot)
+        } else {
+            None
+        }
+    }
+
+    /// 调用方未解锁的分类都不在任务处理范围内时才可查看或取消任务
+    fn is_accessible(&self, locked_category_ids: &[i64]) -> bool {
+        locked_category_ids
+            .iter()
+            .all(|id| self.excluded_category_ids.binary_search(id).is_ok())
+    }
+
+    fn is_expired(&self) -> bool {
+        self.finished
+            .lock()
+            .unwrap()
+            .map(|t| t.elapsed() >= FINISHED_JOB_TTL)
+            .unwrap_or(false)
+    }
+
+    fn finish(&self, kind: &str, result: Result<()>) -> S {
+        let snapshot = {
+            let mut status = self.status.lock().unwrap();
+            match result {
+                Ok(()) => status.finish(JobState::Completed, None),
+                Err(AppError::Cancelled) => status.finish(JobState::Cancelled, None),
+                Err(e) => {
+                    log::error!("{} job {} failed: {}", kind, status.job_id(), e);
+                    status.finish(JobState::Failed, Some(e.to_string()));
+                }
+            }
+            status.clone()
+        };
+        *self.finished.lock().unwrap() = Some(Instant::now());
+        snapshot
+    }
+}
+
+/// 后台任务注册表
+/// @description 在阻塞线程池中运行任务，结束后推送最终状态，并保留一段时间以便查询；
+/// 任务记录启动时排除的分类，调用方未解锁的分类参与过处理时不能查看或取消该任务
+pub struct JobRegistry<S> {
+    /// 任务类型，用于日志与错误信息
+    kind: &'static str,
+    /// 进度事件名
+    event: &'static str,
+    jobs: Mutex<HashMap<String, Arc<Job<S>>>>,
+}
+
+impl<S: JobStatus> JobRegistry<S> {
+    pub fn new(kind: &'static str, event: &'static str) -> Self {
+        JobRegistry {
+            kind,
+            event,
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 启动任务并返回任务 ID
+    /// @description 已有排除分类相同、且 is_same 判断范围相同的任务在运行时直接返回其 ID；
+    /// run 返回 Err(AppError::Cancelled) 表示任务被取消
+    pub fn start<R, F>(
+        &self,
+        app: AppHandle<R>,
+        mut excluded_category_ids: Vec<i64>,
+        is_same: impl Fn(&S) -> bool,
+        status: impl FnOnce(String) -> S,
+        run: F,
+    ) -> String
+    where
+        R: Runtime,
+        F: FnOnce(&Job<S>, &AppHandle<R>) -> Result<()> + Send + 'static,
+    {
+        excluded_category_ids.sort_unstable();
+        excluded_category_ids.dedup();
+
+        let mut jobs = self.jobs.lock().unwrap();
+        jobs.retain(|_, job| !job.is_expired());
+
+        let running = jobs.iter().find(|(_, job)| {
+            let status = job.status.lock().unwrap();
+            status.state() == JobState::Running
+                && job.excluded_category_ids == excluded_category_ids
+                && is_same(&status)
+        });
+        if let Some((job_id, _)) = running {
+            return job_id.clone();
+        }
+
+        let job_id = Uuid::new_v4().to_string();
+        let job = Arc::new(Job {
+            status: Mutex::new(status(job_id.clone())),
+            excluded_category_ids,
+            cancelled: AtomicBool::new(false),
+            finished: Mutex::new(None),
+            last_progress: Mutex::new(Instant::now()),
+        });
+        jobs.insert(job_id.clone(), Arc::clone(&job));
+        drop(jobs);
+
+        let (kind, event) = (self.kind, self.event);
+        log::info!("Starting {} job {}", kind, job_id);
+        tauri::async_runtime::spawn_blocking(move || {
+            let result = run(&job, &app);
+            let snapshot = job.finish(kind, result);
+            let _ = app.emit(event, snapshot);
+        });
+
+        job_id
+    }
+
+    /// 获取任务状态，locked_category_ids 为调用方未解锁的分类
+    pub fn get(&self, job_id: &str, locked_category_ids: &[i64]) -> Result<S> {
+        let jobs = self.jobs.lock().unwrap();
+        Ok(self.find(&jobs, job_id, locked_category_ids)?.snapshot())
+    }
+
+    /// 请求取消任务，任务在下一次检查取消标记时停止
+    pub fn cancel(&self, job_id: &str, locked_category_ids: &[i64]) -> Result<()> {
+        let jobs = self.jobs.lock().unwrap();
+        let job = self.find(&jobs, job_id, locked_category_ids)?;
+        job.cancelled.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// 查找任务；任务处理过调用方未解锁的分类时返回 Locked，避免会话锁定后仍能读取其中的文件
+    fn find<'a>(
+        &self,
+        jobs: &'a HashMap<String, Arc<Job<S>>>,
+        job_id: &str,
+        locked_category_ids: &[i64],
+    ) -> Result<&'a Arc<Job<S>>> {
+        let job = jobs
+            .get(job_id)
+            .ok_or_else(|| AppError::InvalidInput(format!("{} job not found: {}", self.kind, job_id)))?;
+        if !job.is_accessible(locked_category_ids) {
+            return Err(AppError::Locked);
+        }
+        Ok(job)
+    }
+}
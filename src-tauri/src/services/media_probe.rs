@@ -0,0 +1,514 @@
+use crate::db::models::FileKind;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::{MediaSourceStream, MediaSourceStreamOptions};
+use symphonia::core::meta::{MetadataOptions, MetadataRevision, StandardTagKey};
+use symphonia::core::probe::Hint;
+
+/// moov、Matroska 头部等结构读入内存的上限，超出时视为无法解析
+const MAX_HEADER_BOX: u64 = 16 * 1024 * 1024;
+
+/// 从媒体文件中提取的元数据，未能提取的字段为 None
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaInfo {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub duration_ms: Option<i64>,
+    /// 拍摄时间，EXIF 不含时区，保存为本地时间 YYYY-MM-DDTHH:MM:SS
+    pub captured_at: Option<String>,
+    pub camera_make: Option<String>,
+    pub camera_model: Option<String>,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub genre: Option<String>,
+}
+
+/// 按资源分类提取元数据，只读取文件头部等必要部分，不解码媒体内容
+pub fn probe(path: &Path, kind: FileKind) -> MediaInfo {
+    match kind {
+        FileKind::Image => probe_image(path),
+        FileKind::Audio => probe_audio(path),
+        FileKind::Video => probe_video(path),
+        _ => MediaInfo::default(),
+    }
+}
+
+fn probe_image(path: &Path) -> MediaInfo {
+    let mut info = MediaInfo::default();
+    if let Ok(size) = imagesize::size(path) {
+        info.width = u32::try_from(size.width).ok();
+        info.height = u32::try_from(size.height).ok();
+    } else if let Some((width, height)) = svg_size(path) {
+        info.width = Some(width);
+        info.height = Some(height);
+        return info;
+    }
+
+    let exif = File::open(path)
+        .ok()
+        .and_then(|file| exif::Reader::new().read_from_container(&mut BufReader::new(file)).ok());
+    if let Some(exif) = exif {
+        let ascii = |tag| {
+            exif.get_field(tag, exif::In::PRIMARY).and_then(|field| match &field.value {
+                exif::Value::Ascii(values) => values
+                    .first()
+                    .map(|v| String::from_utf8_lossy(v).trim().to_string())
+                    .filter(|v| !v.is_empty()),
+                _ => None,
+            })
+        };
+        info.captured_at = ascii(exif::Tag::DateTimeOriginal)
+            .or_else(|| ascii(exif::Tag::DateTime))
+            .and_then(|value| normalize_exif_datetime(&value));
+        info.camera_make = ascii(exif::Tag::Make);
+        info.camera_model = ascii(exif::Tag::Model);
+
+        // 方向为 5~8 时图片需要旋转 90 度显示，宽高互换为显示尺寸
+        let orientation = exif
+            .get_field(exif::Tag::Orientation, exif::In::PRIMARY)
+            .and_then(|field| field.value.get_uint(0));
+        if matches!(orientation, Some(5..=8)) {
+            std::mem::swap(&mut info.width, &mut info.height);
+        }
+    }
+
+    info
+}
+
+/// imagesize 不支持 SVG，按 usvg 解析出的画布尺寸计算
+fn svg_size(path: &Path) -> Option<(u32, u32)> {
+    let is_svg = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("svg"))
+        .unwrap_or(false);
+    if !is_svg {
+        return None;
+    }
+    let data = std::fs::read(path).ok()?;
+    let tree = resvg::usvg::Tree::from_data(&data, &resvg::usvg::Options::default()).ok()?;
+    let size = tree.size();
+    Some((size.width().round() as u32, size.height().round() as u32))
+}
+
+/// EXIF 日期格式为 "YYYY:MM:DD HH:MM:SS"，无效或全零的日期返回 None
+fn normalize_exif_datetime(value: &str) -> Option<String> {
+    let parsed = chrono::NaiveDateTime::parse_from_str(value, "%Y:%m:%d %H:%M:%S").ok()?;
+    Some(parsed.format("%Y-%m-%dT%H:%M:%S").to_string())
+}
+
+fn probe_audio(path: &Path) -> MediaInfo {
+    let mut info = MediaInfo::default();
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(_) => return info,
+    };
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+    let stream = MediaSourceStream::new(Box::new(file), MediaSourceStreamOptions::default());
+    let mut probed = match symphonia::default::get_probe().format(
+        &hint,
+        stream,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    ) {
+        Ok(probed) => probed,
+        Err(e) => {
+            log::debug!("Failed to probe audio {}: {}", path.display(), e);
+            return info;
+        }
+    };
+
+    if let Some(track) = probed.format.default_track() {
+        let params = &track.codec_params;
+        info.duration_ms = match (params.n_frames, params.time_base, params.sample_rate) {
+            (Some(frames), Some(time_base), _) => {
+                let time = time_base.calc_time(frames);
+                Some((time.seconds as f64 * 1000.0 + time.frac * 1000.0).round() as i64)
+            }
+            (Some(frames), None, Some(rate)) if rate > 0 => Some((frames * 1000 / rate as u64) as i64),
+            _ => None,
+        };
+    }
+
+    // 标签可能位于容器之前（如 ID3v2）或容器内部，容器内的优先
+    if let Some(revision) = probed.metadata.get().as_ref().and_then(|m| m.current()) {
+        apply_tags(&mut info, revision);
+    }
+    if let Some(revision) = probed.format.metadata().current() {
+        apply_tags(&mut info, revision);
+    }
+
+    info
+}
+
+fn apply_tags(info: &mut MediaInfo, revision: &MetadataRevision) {
+    for tag in revision.tags() {
+        // 部分格式的字符串以 NUL 结尾
+        let value = tag
+            .value
+            .to_string()
+            .trim_matches(|c: char| c == '\0' || c.is_whitespace())
+            .to_string();
+        if value.is_empty() {
+            continue;
+        }
+        let field = match tag.std_key {
+            Some(StandardTagKey::TrackTitle) => &mut info.title,
+            Some(StandardTagKey::Artist) => &mut info.artist,
+            Some(StandardTagKey::Album) => &mut info.album,
+            Some(StandardTagKey::Genre) => &mut info.genre,
+            _ => continue,
+        };
+        *field = Some(value);
+    }
+}
+
+fn probe_video(path: &Path) -> MediaInfo {
+    let result = File::open(path).and_then(|mut file| {
+        let mut magic = [0u8; 12];
+        let read = file.read(&mut magic)?;
+        file.seek(SeekFrom::Start(0))?;
+
+        if read >= 12 && &magic[0..4] == b"RIFF" && &magic[8..12] == b"AVI " {
+            probe_avi(&mut file)
+        } else if read >= 4 && magic[0..4] == [0x1A, 0x45, 0xDF, 0xA3] {
+            probe_matroska(&mut file)
+        } else if read >= 8 && matches!(&magic[4..8], b"ftyp" | b"moov" | b"mdat" | b"free" | b"wide") {
+            probe_mp4(&mut file)
+        } else {
+            Ok(MediaInfo::default())
+        }
+    });
+
+    result.unwrap_or_else(|e| {
+        log::debug!("Failed to probe video {}: {}", path.display(), e);
+        MediaInfo::default()
+    })
+}
+
+fn invalid_data(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn read_exact_vec<R: Read>(reader: &mut R, len: u64) -> std::io::Result<Vec<u8>> {
+    if len > MAX_HEADER_BOX {
+        return Err(invalid_data("header too large"));
+    }
+    let mut buf = vec![0u8; len as usize];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn be_u32(buf: &[u8], offset: usize) -> Option<u32> {
+    buf.get(offset..offset + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn be_u64(buf: &[u8], offset: usize) -> Option<u64> {
+    buf.get(offset..offset + 8).map(|b| {
+        u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
+    })
+}
+
+fn le_u32(buf: &[u8], offset: usize) -> Option<u32> {
+    buf.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// MP4 / MOV：在顶层查找 moov，从 mvhd 读取时长，从视频轨道的 tkhd 读取显示尺寸
+fn probe_mp4(file: &mut File) -> std::io::Result<MediaInfo> {
+    let file_len = file.metadata()?.len();
+    let mut offset = 0u64;
+
+    while offset + 8 <= file_len {
+        file.seek(SeekFrom::Start(offset))?;
+        let mut header = [0u8; 16];
+        file.read_exact(&mut header[..8])?;
+        let mut size = be_u32(&header, 0).unwrap_or(0) as u64;
+        let mut header_len = 8u64;
+        if size == 1 {
+            file.read_exact(&mut header[8..16])?;
+            size = be_u64(&header, 8).unwrap_or(0);
+            header_len = 16;
+        } else if size == 0 {
+            size = file_len - offset;
+        }
+        if size < header_len {
+            return Err(invalid_data("invalid box size"));
+        }
+
+        if &header[4..8] == b"moov" {
+            let moov = read_exact_vec(file, size - header_len)?;
+            return Ok(parse_moov(&moov));
+        }
+        offset = match offset.checked_add(size) {
+            Some(next) => next,
+            None => break,
+        };
+    }
+
+    Ok(MediaInfo::default())
+}
+
+/// 遍历一层 box，返回 (类型, 内容)
+fn mp4_children(buf: &[u8]) -> Vec<(&[u8], &[u8])> {
+    let mut children = Vec::new();
+    let mut offset = 0usize;
+    while offset + 8 <= buf.len() {
+        let size = be_u32(buf, offset).unwrap_or(0) as usize;
+        let (header_len, size) = match size {
+            0 => (8, buf.len() - offset),
+            1 => match be_u64(buf, offset + 8).and_then(|large| usize::try_from(large).ok()) {
+                Some(large) => (16, large),
+                None => break,
+            },
+            size => (8, size),
+        };
+        let end = match offset.checked_add(size) {
+            Some(end) if size >= header_len && end <= buf.len() => end,
+            _ => break,
+        };
+        children.push((&buf[offset + 4..offset + 8], &buf[offset + header_len..end]));
+        offset = end;
+    }
+    children
+}
+
+fn parse_moov(moov: &[u8]) -> MediaInfo {
+    let mut info = MediaInfo::default();
+
+    for (kind, body) in mp4_children(moov) {
+        match kind {
+            b"mvhd" => {
+                // version 1 使用 64 位的时间字段
+                let (timescale, duration) = if body.first() == Some(&1) {
+                    (be_u32(body, 20), be_u64(body, 24))
+                } else {
+                    (be_u32(body, 12), be_u32(body, 16).map(u64::from))
+                };
+                if let (Some(timescale), Some(duration)) = (timescale, duration) {
+                    if timescale > 0 && duration != u64::MAX && duration != u32::MAX as u64 {
+                        info.duration_ms = Some((duration as u128 * 1000 / timescale as u128) as i64);
+                    }
+                }
+            }
+            b"trak" if info.width.is_none() => {
+                let children = mp4_children(body);
+                let is_video = children
+                    .iter()
+                    .filter(|(kind, _)| *kind == b"mdia")
+                    .flat_map(|(_, mdia)| mp4_children(mdia))
+                    .any(|(kind, hdlr)| kind == b"hdlr" && hdlr.get(8..12) == Some(b"vide"));
+                if !is_video {
+                    continue;
+                }
+                if let Some((_, tkhd)) = children.iter().find(|(kind, _)| *kind == b"tkhd") {
+                    // 宽高为 16.16 定点数，位于矩阵之后
+                    let offset = if tkhd.first() == Some(&1) { 88 } else { 76 };
+                    let width = be_u32(tkhd, offset).map(|w| w >> 16);
+                    let height = be_u32(tkhd, offset + 4).map(|h| h >> 16);
+                    if let (Some(width), Some(height)) = (width, height) {
+                        if width > 0 && height > 0 {
+                            info.width = Some(width);
+                            info.height = Some(height);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    info
+}
+
+const EBML_SEGMENT: u32 = 0x1853_8067;
+const EBML_INFO: u32 = 0x1549_A966;
+const EBML_TIMECODE_SCALE: u32 = 0x2A_D7B1;
+const EBML_DURATION: u32 = 0x4489;
+const EBML_TRACKS: u32 = 0x1654_AE6B;
+const EBML_TRACK_ENTRY: u32 = 0xAE;
+const EBML_TRACK_TYPE: u32 = 0x83;
+const EBML_VIDEO: u32 = 0xE0;
+const EBML_PIXEL_WIDTH: u32 = 0xB0;
+const EBML_PIXEL_HEIGHT: u32 = 0xBA;
+const EBML_DISPLAY_WIDTH: u32 = 0x54B0;
+const EBML_DISPLAY_HEIGHT: u32 = 0x54BA;
+const EBML_CLUSTER: u32 = 0x1F43_B675;
+
+/// 读取 EBML 变长整数，返回 (值, 长度)；keep_marker 为 true 时保留长度标记位（用于元素 ID）
+fn read_vint<R: Read>(reader: &mut R, keep_marker: bool) -> std::io::Result<(u64, usize)> {
+    let mut first = [0u8; 1];
+    reader.read_exact(&mut first)?;
+    let len = first[0].leading_zeros() as usize + 1;
+    if len > 8 {
+        return Err(invalid_data("invalid EBML variable-length integer"));
+    }
+
+    let mut value = if keep_marker {
+        first[0] as u64
+    } else {
+        (first[0] as u64) & (0xFF >> len)
+    };
+    let mut rest = [0u8; 7];
+    reader.read_exact(&mut rest[..len - 1])?;
+    for byte in &rest[..len - 1] {
+        value = (value << 8) | *byte as u64;
+    }
+    Ok((value, len))
+}
+
+/// 读取元素头，返回 (ID, 内容长度)，长度未知时为 None
+fn read_ebml_header<R: Read>(reader: &mut R) -> std::io::Result<(u32, Option<u64>)> {
+    let (id, _) = read_vint(reader, true)?;
+    let (size, len) = read_vint(reader, false)?;
+    let unknown = size == (1u64 << (7 * len)) - 1;
+    Ok((id as u32, if unknown { None } else { Some(size) }))
+}
+
+/// 遍历内存中一层 EBML 元素，返回 (ID, 内容)
+fn ebml_children(buf: &[u8]) -> Vec<(u32, &[u8])> {
+    let mut children = Vec::new();
+    let mut cursor = std::io::Cursor::new(buf);
+    while (cursor.position() as usize) < buf.len() {
+        let (id, size) = match read_ebml_header(&mut cursor) {
+            Ok((id, Some(size))) => (id, size),
+            _ => break,
+        };
+        let start = cursor.position() as usize;
+        let end = match usize::try_from(size).ok().and_then(|size| start.checked_add(size)) {
+            Some(end) if end <= buf.len() => end,
+            _ => break,
+        };
+        children.push((id, &buf[start..end]));
+        cursor.set_position(end as u64);
+    }
+    children
+}
+
+fn ebml_uint(buf: &[u8]) -> Option<u64> {
+    if buf.is_empty() || buf.len() > 8 {
+        return None;
+    }
+    Some(buf.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+}
+
+fn ebml_float(buf: &[u8]) -> Option<f64> {
+    match buf.len() {
+        4 => Some(f32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64),
+        8 => Some(f64::from_be_bytes([
+            buf[0], buf[1], buf[2], buf[3], buf[4], buf[5], buf[6], buf[7],
+        ])),
+        _ => None,
+    }
+}
+
+/// Matroska / WebM：读取 Segment 下的 Info（时长）与 Tracks（视频尺寸），遇到 Cluster 即停止
+fn probe_matroska(file: &mut File) -> std::io::Result<MediaInfo> {
+    let file_len = file.metadata()?.len();
+    let mut info = MediaInfo::default();
+
+    // 跳过 EBML 头
+    let (_, size) = read_ebml_header(file)?;
+    file.seek(SeekFrom::Current(size.ok_or_else(|| invalid_data("unknown EBML header size"))? as i64))?;
+
+    let (id, segment_size) = read_ebml_header(file)?;
+    if id != EBML_SEGMENT {
+        return Ok(info);
+    }
+    let segment_end = segment_size
+        .map(|size| file.stream_position().map(|pos| pos + size))
+        .transpose()?
+        .unwrap_or(file_len)
+        .min(file_len);
+
+    let mut found_info = false;
+    let mut found_tracks = false;
+    while file.stream_position()? < segment_end && !(found_info && found_tracks) {
+        let (id, size) = read_ebml_header(file)?;
+        if id == EBML_CLUSTER {
+            break;
+        }
+        let size = size.ok_or_else(|| invalid_data("unknown element size"))?;
+        match id {
+            EBML_INFO => {
+                let body = read_exact_vec(file, size)?;
+                let mut scale = 1_000_000u64;
+                let mut duration = None;
+                for (id, value) in ebml_children(&body) {
+                    match id {
+                        EBML_TIMECODE_SCALE => scale = ebml_uint(value).unwrap_or(scale),
+                        EBML_DURATION => duration = ebml_float(value),
+                        _ => {}
+                    }
+                }
+                // Duration 以 TimecodeScale 纳秒为单位
+                info.duration_ms = duration.map(|d| (d * scale as f64 / 1_000_000.0).round() as i64);
+                found_info = true;
+            }
+            EBML_TRACKS => {
+                let body = read_exact_vec(file, size)?;
+                for (id, entry) in ebml_children(&body) {
+                    if id != EBML_TRACK_ENTRY || info.width.is_some() {
+                        continue;
+                    }
+                    let fields = ebml_children(entry);
+                    let is_video = fields
+                        .iter()
+                        .any(|(id, value)| *id == EBML_TRACK_TYPE && ebml_uint(value) == Some(1));
+                    let video = fields.iter().find(|(id, _)| *id == EBML_VIDEO);
+                    if let (true, Some((_, video))) = (is_video, video) {
+                        let video = ebml_children(video);
+                        let get = |wanted: u32| {
+                            video
+                                .iter()
+                                .find(|(id, _)| *id == wanted)
+                                .and_then(|(_, value)| ebml_uint(value))
+                                .and_then(|value| u32::try_from(value).ok())
+                        };
+                        // 优先使用显示尺寸（考虑了像素宽高比）
+                        info.width = get(EBML_DISPLAY_WIDTH).or_else(|| get(EBML_PIXEL_WIDTH));
+                        info.height = get(EBML_DISPLAY_HEIGHT).or_else(|| get(EBML_PIXEL_HEIGHT));
+                    }
+                }
+                found_tracks = true;
+            }
+            _ => {
+                file.seek(SeekFrom::Current(size as i64))?;
+            }
+        }
+    }
+
+    Ok(info)
+}
+
+/// AVI：从 hdrl 列表中的 avih 主头读取每帧时长、总帧数与尺寸
+fn probe_avi(file: &mut File) -> std::io::Result<MediaInfo> {
+    let mut header = [0u8; 88];
+    let read = file.read(&mut header)?;
+    let header = &header[..read];
+
+    let mut info = MediaInfo::default();
+    if header.get(12..16) != Some(b"LIST") || header.get(20..28) != Some(b"hdrlavih") {
+        return Ok(info);
+    }
+
+    // avih 内容从偏移 32 开始，文件过短时没有可读的字段
+    let Some(avih) = header.get(32..) else {
+        return Ok(info);
+    };
+    let micro_sec_per_frame = le_u32(avih, 0).unwrap_or(0) as i64;
+    let total_frames = le_u32(avih, 16).unwrap_or(0) as i64;
+    if micro_sec_per_frame > 0 && total_frames > 0 {
+        info.duration_ms = Some(micro_sec_per_frame * total_frames / 1000);
+    }
+    info.width = le_u32(avih, 32).filter(|w| *w > 0);
+    info.height = le_u32(avih, 36).filter(|h| *h > 0);
+
+    Ok(info)
+}
@@ -0,0 +1,322 @@
+use crate::db::models::{FileKind, ResourceMetadata};
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::services::job::{Job, JobRegistry, JobState, JobStatus};
+use crate::services::media_probe::{self, MediaInfo};
+use crate::services::resource::SUBTREE_CTE;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// 媒体元数据提取进度事件名
+pub const METADATA_PROGRESS_EVENT: &str = "metadata://progress";
+
+/// 每提取多少个文件写入一次数据库
+const WRITE_BATCH_SIZE: usize = 64;
+
+const METADATA_COLUMNS: &str = "resource_id, width, height, duration_ms, captured_at, camera_make, camera_model, \
+     title, artist, album, genre, extracted_at";
+
+/// 待提取元数据的资源
+struct PendingResource {
+    id: i64,
+    file_path: String,
+    kind: FileKind,
+    file_size: i64,
+    modified_at: String,
+}
+
+/// 媒体元数据服务
+pub struct MetadataService {
+    db: Arc<Database>,
+}
+
+impl MetadataService {
+    pub fn new(db: Arc<Database>) -> Self {
+        MetadataService { db }
+    }
+
+    /// 获取资源的媒体元数据，尚未提取时返回 None
+    pub fn get_metadata(&self, resource_id: i64) -> Result<Option<ResourceMetadata>> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+
+        let metadata = conn
+            .query_row(
+                &format!("SELECT {} FROM resource_metadata WHERE resource_id = ?", METADATA_COLUMNS),
+                params![resource_id],
+                map_metadata,
+            )
+            .optional()?;
+        Ok(metadata)
+    }
+
+    /// 获取资源所属的分类 ID
+    pub fn resource_category_id(&self, resource_id: i64) -> Result<i64> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+
+        conn.query_row(
+            "SELECT category_id FROM resources WHERE id = ?",
+            params![resource_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| AppError::InvalidInput(format!("Resource not found: {}", resource_id)))
+    }
+
+    /// 为尚未提取元数据的图片、音频、视频资源提取元数据，返回处理的资源数
+    /// category_id 为空时处理所有分类，excluded_category_ids 中的分类会被跳过；
+    /// 每处理一个文件调用一次 on_progress(已处理数, 总数)，返回 false 时停止，已提取的结果会保留
+    pub fn extract_pending(
+        &self,
+        category_id: Option<i64>,
+        excluded_category_ids: &[i64],
+        on_progress: &mut dyn FnMut(i64, i64) -> bool,
+    ) -> Result<i64> {
+        let pending = self.pending_resources(category_id, excluded_category_ids)?;
+        let total = pending.len() as i64;
+        log::info!("Extracting media metadata for {} resources", total);
+
+        let mut batch = Vec::with_capacity(WRITE_BATCH_SIZE);
+        let mut processed = 0i64;
+        for resource in &pending {
+            if !on_progress(processed, total) {
+                self.save_metadata(&batch)?;
+                return Err(AppError::Cancelled);
+            }
+
+            // 文件读取在数据库锁之外进行
+            let info = media_probe::probe(Path::new(&resource.file_path), resource.kind);
+            batch.push((resource, info));
+            processed += 1;
+
+            if batch.len() >= WRITE_BATCH_SIZE {
+                self.save_metadata(&batch)?;
+                batch.clear();
+            }
+        }
+        self.save_metadata(&batch)?;
+        on_progress(processed, total);
+
+        Ok(processed)
+    }
+
+    fn pending_resources(&self, category_id: Option<i64>, excluded_category_ids: &[i64]) -> Result<Vec<PendingResource>> {
+        let mut conditions = vec![
+            "m.resource_id IS NULL".to_string(),
+            "r.kind IN ('image', 'audio', 'video')".to_string(),
+        ];
+        let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        if let Some(category_id) = category_id {
+            conditions.push("r.category_id IN (SELECT id FROM subtree)".to_string());
+            values.push(Box::new(category_id));
+        }
+        if !excluded_category_ids.is_empty() {
+            conditions.push(format!(
+                "r.category_id NOT IN ({})",
+                vec!["?"; excluded_category_ids.len()].join(", ")
+            ));
+            for id in excluded_category_ids {
+                values.push(Box::new(*id));
+            }
+        }
+
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "{}SELECT r.id, r.file_path, r.kind, r.file_size, r.modified_at
+             FROM resources r
+             LEFT JOIN resource_metadata m ON m.resource_id = r.id
+             WHERE {}
+             ORDER BY r.id",
+            if category_id.is_some() { SUBTREE_CTE } else { "" },
+            conditions.join(" AND ")
+        ))?;
+        let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+        let pending = stmt
+            .query_map(params.as_slice(), |row| {
+                Ok(PendingResource {
+                    id: row.get(0)?,
+                    file_path: row.get(1)?,
+                    kind: FileKind::parse(&row.get::<_, String>(2)?),
+                    file_size: row.get(3)?,
+                    modified_at: row.get(4)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(pending)
+    }
+
+    /// 保存提取结果；提取期间文件已变化或资源已删除时丢弃该结果
+    fn save_metadata(&self, batch: &[(&PendingResource, MediaInfo)]) -> Result<()> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let conn = self.db.get_connection();
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(&format!(
+                "INSERT OR REPLACE INTO resource_metadata ({})
+                 SELECT ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?
+                 WHERE EXISTS (SELECT 1 FROM resources WHERE id = ? AND file_size = ? AND modified_at = ?)",
+                METADATA_COLUMNS
+            ))?;
+            let extracted_at = chrono::Utc::now().to_rfc3339();
+            for (resource, info) in batch {
+                stmt.execute(params![
+                    resource.id,
+                    info.width,
+                    info.height,
+                    info.duration_ms,
+                    info.captured_at,
+                    info.camera_make,
+                    info.camera_model,
+                    info.title,
+                    info.artist,
+                    info.album,
+                    info.genre,
+                    extracted_at,
+                    resource.id,
+                    resource.file_size,
+                    resource.modified_at,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+fn map_metadata(row: &rusqlite::Row) -> rusqlite::Result<ResourceMetadata> {
+    Ok(ResourceMetadata {
+        resource_id: row.get(0)?,
+        width: row.get(1)?,
+        height: row.get(2)?,
+        duration_ms: row.get(3)?,
+        captured_at: row.get(4)?,
+        camera_make: row.get(5)?,
+        camera_model: row.get(6)?,
+        title: row.get(7)?,
+        artist: row.get(8)?,
+        album: row.get(9)?,
+        genre: row.get(10)?,
+        extracted_at: row.get(11)?,
+    })
+}
+
+/// 元数据提取任务状态，同时作为 metadata://progress 事件的负载
+#[derive(Debug, Clone, Serialize)]
+pub struct MetadataJobStatus {
+    pub job_id: String,
+    /// 为空表示处理所有分类
+    pub category_id: Option<i64>,
+    pub state: JobState,
+    pub processed: i64,
+    pub total: i64,
+    pub error: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+impl JobStatus for MetadataJobStatus {
+    fn job_id(&self) -> &str {
+        &self.job_id
+    }
+
+    fn state(&self) -> JobState {
+        self.state
+    }
+
+    fn finish(&mut self, state: JobState, error: Option<String>) {
+        self.state = state;
+        self.error = error;
+        self.finished_at = Some(chrono::Utc::now().to_rfc3339());
+    }
+}
+
+/// 后台媒体元数据提取任务管理器
+/// @description 已有覆盖相同范围且排除分类相同的任务在运行时，重复启动会返回该任务
+pub struct MetadataJobManager {
+    jobs: JobRegistry<MetadataJobStatus>,
+}
+
+impl Default for MetadataJobManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetadataJobManager {
+    pub fn new() -> Self {
+        MetadataJobManager {
+            jobs: JobRegistry::new("metadata", METADATA_PROGRESS_EVENT),
+        }
+    }
+
+    /// 启动后台提取，返回任务 ID；category_id 为空时处理所有分类
+    pub fn start<R: Runtime>(
+        &self,
+        app: AppHandle<R>,
+        db: Arc<Database>,
+        category_id: Option<i64>,
+        excluded_category_ids: Vec<i64>,
+    ) -> Result<String> {
+        let job_id = self.jobs.start(
+            app,
+            excluded_category_ids,
+            |status| status.category_id.is_none() || status.category_id == category_id,
+            |job_id| MetadataJobStatus {
+                job_id,
+                category_id,
+                state: JobState::Running,
+                processed: 0,
+                total: 0,
+                error: None,
+                started_at: chrono::Utc::now().to_rfc3339(),
+                finished_at: None,
+            },
+            move |job, app| run_job(app, db, job),
+        );
+        Ok(job_id)
+    }
+
+    /// 获取任务状态，locked_category_ids 为调用方未解锁的分类
+    pub fn get(&self, job_id: &str, locked_category_ids: &[i64]) -> Result<MetadataJobStatus> {
+        self.jobs.get(job_id, locked_category_ids)
+    }
+
+    /// 请求取消任务，已提取的元数据仍会保存
+    pub fn cancel(&self, job_id: &str, locked_category_ids: &[i64]) -> Result<()> {
+        self.jobs.cancel(job_id, locked_category_ids)
+    }
+}
+
+fn run_job<R: Runtime>(app: &AppHandle<R>, db: Arc<Database>, job: &Job<MetadataJobStatus>) -> Result<()> {
+    let category_id = job.snapshot().category_id;
+    let service = MetadataService::new(db);
+
+    let processed = service.extract_pending(category_id, job.excluded_category_ids(), &mut |processed, total| {
+        if job.is_cancelled() {
+            return false;
+        }
+
+        let progress = job.update_progress(false, |status| {
+            status.processed = processed;
+            status.total = total;
+        });
+        if let Some(snapshot) = progress {
+            let _ = app.emit(METADATA_PROGRESS_EVENT, snapshot);
+        }
+        true
+    })?;
+
+    job.update(|status| status.processed = processed);
+    Ok(())
+}
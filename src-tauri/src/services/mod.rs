@@ -1,9 +1,38 @@
 pub mod category;
 pub mod directory;
+pub mod duplicate;
 pub mod wallpaper;
+pub mod wallpaper_compose;
+pub mod wallpaper_dedup;
+pub mod wallpaper_download;
+pub mod wallpaper_folder;
+pub mod wallpaper_library;
+pub mod wallpaper_source;
 pub mod env_var;
+pub mod file_type;
+pub mod ignore_rules;
+pub mod job;
+pub mod media_probe;
+pub mod metadata;
+pub mod password;
+pub mod resource;
+pub mod scan_job;
+pub mod session;
+pub mod slideshow;
+pub mod stats;
+pub mod thumbnail;
+pub mod vault;
+pub mod watcher;
 
 pub use category::CategoryService;
 pub use directory::DirectoryService;
+pub use duplicate::DuplicateService;
 pub use wallpaper::WallpaperService;
+pub use wallpaper_dedup::WallpaperDedupService;
+pub use wallpaper_folder::WallpaperFolderService;
+pub use wallpaper_library::WallpaperLibraryService;
 pub use env_var::EnvVarService;
+pub use metadata::MetadataService;
+pub use password::PasswordService;
+pub use resource::ResourceService;
+pub use stats::StatsService;
@@ -0,0 +1,291 @@
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::services::vault::{self, VaultKey, ENCRYPTION_SALT_KEY};
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::Arc;
+
+/// app_settings 中存放全局密码哈希的键
+pub const GLOBAL_PASSWORD_KEY: &str = "global_password_hash";
+
+/// 使用 Argon2id 生成 PHC 格式的密码哈希
+pub fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppError::Internal(format!("Failed to hash password: {}", e)))
+}
+
+/// 校验密码与哈希是否匹配（内部为常量时间比较），哈希格式错误时视为不匹配
+pub fn verify_hash(password: &str, hash: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(parsed) => Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_ok(),
+        Err(e) => {
+            log::warn!("Stored password hash is malformed: {}", e);
+            false
+        }
+    }
+}
+
+/// 密码服务
+pub struct PasswordService {
+    db: Arc<Database>,
+}
+
+impl PasswordService {
+    pub fn new(db: Arc<Database>) -> Self {
+        PasswordService { db }
+    }
+
+    /// 是否设置了全局密码
+    pub fn has_global_password(&self) -> Result<bool> {
+        Ok(self.global_password_hash()?.is_some())
+    }
+
+    /// 设置全局密码，已存在密码时需要提供当前密码
+    /// 加密快捷方式会用旧密码派生的密钥解密，再用新密码派生的密钥重新加密
+    pub fn set_global_password(&self, password: &str, current_password: Option<&str>) -> Result<()> {
+        validate_password(password)?;
+
+        let old_key = match self.global_password_hash()? {
+            Some(hash) => {
+                let current = current_password.ok_or(AppError::InvalidPassword)?;
+                if !verify_hash(current, &hash) {
+                    return Err(AppError::InvalidPassword);
+                }
+                match self.encryption_salt()? {
+                    Some(salt) => Some(VaultKey::derive(current, &vault::decode_salt(&salt)?)?),
+                    None => None,
+                }
+            }
+            None => None,
+        };
+
+        let hash = hash_password(password)?;
+        let salt = vault::generate_salt();
+        let new_key = VaultKey::derive(password, &vault::decode_salt(&salt)?)?;
+
+        let conn = self.db.get_connection();
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        reseal_shortcuts(&tx, old_key.as_ref(), &new_key, false)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO app_settings (key, value, updated_at) VALUES (?, ?, CURRENT_TIMESTAMP)",
+            params![GLOBAL_PASSWORD_KEY, hash],
+        )?;
+        tx.execute(
+            "INSERT OR REPLACE INTO app_settings (key, value, updated_at) VALUES (?, ?, CURRENT_TIMESTAMP)",
+            params![ENCRYPTION_SALT_KEY, salt],
+        )?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 校验全局密码并派生加密密钥，校验失败时返回 None
+    /// 同时把仍以明文保存的加密快捷方式补加密
+    pub fn unlock_global(&self, password: &str) -> Result<Option<VaultKey>> {
+        if !self.verify_global_password(password)? {
+            return Ok(None);
+        }
+
+        let conn = self.db.get_connection();
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        // 从明文密码迁移过来的数据库还没有盐值，此时也不存在任何密文
+        let salt: Option<String> = tx
+            .query_row(
+                "SELECT value FROM app_settings WHERE key = ?",
+                params![ENCRYPTION_SALT_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let salt = match salt {
+            Some(salt) => salt,
+            None => {
+                let salt = vault::generate_salt();
+                tx.execute(
+                    "INSERT INTO app_settings (key, value, updated_at) VALUES (?, ?, CURRENT_TIMESTAMP)",
+                    params![ENCRYPTION_SALT_KEY, salt],
+                )?;
+                salt
+            }
+        };
+
+        let key = VaultKey::derive(password, &vault::decode_salt(&salt)?)?;
+        reseal_shortcuts(&tx, None, &key, true)?;
+
+        tx.commit()?;
+        Ok(Some(key))
+    }
+
+    /// 校验全局密码，未设置密码时返回 false
+    pub fn verify_global_password(&self, password: &str) -> Result<bool> {
+        Ok(self
+            .global_password_hash()?
+            .map(|hash| verify_hash(password, &hash))
+            .unwrap_or(false))
+    }
+
+    /// 设置分类密码，已存在密码时需要提供当前密码
+    pub fn set_category_password(
+        &self,
+        category_id: i64,
+        password: &str,
+        current_password: Option<&str>,
+    ) -> Result<()> {
+        validate_password(password)?;
+
+        if let Some(hash) = self.category_password_hash(category_id)? {
+            let current = current_password.ok_or(AppError::InvalidPassword)?;
+            if !verify_hash(current, &hash) {
+                return Err(AppError::InvalidPassword);
+            }
+        }
+
+        let hash = hash_password(password)?;
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+        conn.execute(
+            "UPDATE categories SET password_hash = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+            params![hash, category_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// 校验分类密码，分类未设置密码时返回 false
+    pub fn verify_category_password(&self, category_id: i64, password: &str) -> Result<bool> {
+        Ok(self
+            .category_password_hash(category_id)?
+            .map(|hash| verify_hash(password, &hash))
+            .unwrap_or(false))
+    }
+
+    /// 清除分类密码，需要提供当前密码
+    pub fn clear_category_password(&self, category_id: i64, password: &str) -> Result<()> {
+        if !self.verify_category_password(category_id, password)? {
+            return Err(AppError::InvalidPassword);
+        }
+
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+        conn.execute(
+            "UPDATE categories SET password_hash = NULL, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+            params![category_id],
+        )?;
+
+        Ok(())
+    }
+
+    fn encryption_salt(&self) -> Result<Option<String>> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+        let salt = conn
+            .query_row(
+                "SELECT value FROM app_settings WHERE key = ?",
+                params![ENCRYPTION_SALT_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(salt)
+    }
+
+    fn global_password_hash(&self) -> Result<Option<String>> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+        let hash = conn
+            .query_row(
+                "SELECT value FROM app_settings WHERE key = ?",
+                params![GLOBAL_PASSWORD_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(hash)
+    }
+
+    /// 获取分类密码哈希，分类不存在时返回 CategoryNotFound
+    fn category_password_hash(&self, category_id: i64) -> Result<Option<String>> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+        let hash: Option<Option<String>> = conn
+            .query_row(
+                "SELECT password_hash FROM categories WHERE id = ?",
+                params![category_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        hash.ok_or(AppError::CategoryNotFound)
+    }
+}
+
+fn validate_password(password: &str) -> Result<()> {
+    if password.is_empty() {
+        return Err(AppError::InvalidInput("Password cannot be empty".to_string()));
+    }
+    Ok(())
+}
+
+/// 使用新密钥重新加密所有标记为加密的快捷方式
+/// 已加密字段用旧密钥解密（旧密钥缺失时报错），历史明文字段直接加密；
+/// plaintext_only 为 true 时跳过已加密字段，只补加密明文字段
+fn reseal_shortcuts(
+    conn: &Connection,
+    old_key: Option<&VaultKey>,
+    new_key: &VaultKey,
+    plaintext_only: bool,
+) -> Result<()> {
+    let mut stmt = conn.prepare("SELECT id, target, description FROM shortcuts WHERE encrypted = 1")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let reseal = |value: &str| -> Result<Option<String>> {
+        if vault::is_sealed(value) {
+            if plaintext_only {
+                return Ok(None);
+            }
+            let key = old_key.ok_or_else(|| {
+                AppError::Internal("Encrypted shortcut found without an encryption key".to_string())
+            })?;
+            Ok(Some(new_key.seal(&key.open(value)?)?))
+        } else {
+            Ok(Some(new_key.seal(value)?))
+        }
+    };
+
+    for (id, target, description) in rows {
+        let new_target = reseal(&target)?;
+        let new_description = match &description {
+            Some(d) => reseal(d)?,
+            None => None,
+        };
+        if new_target.is_none() && new_description.is_none() {
+            continue;
+        }
+
+        conn.execute(
+            "UPDATE shortcuts SET target = ?, description = ? WHERE id = ?",
+            params![
+                new_target.unwrap_or(target),
+                new_description.or(description),
+                id
+            ],
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,401 @@
+use crate::db::models::{
+    FileKind, ResourceItem, ResourcePage, ResourceSort, ResourceSortField, SearchMode, SearchResourcesRequest,
+    SearchResourcesResult, SortDirection,
+};
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use rusqlite::types::Value;
+use rusqlite::OptionalExtension;
+use std::sync::Arc;
+
+/// 默认每页条数
+const DEFAULT_PAGE_SIZE: u32 = 50;
+/// 单页最大条数
+const MAX_PAGE_SIZE: u32 = 500;
+/// trigram 分词器只能匹配至少 3 个字符的关键字，更短的关键字退回 LIKE 匹配
+const MIN_FTS_TOKEN_CHARS: usize = 3;
+
+/// 以参数中的分类为根，递归展开其所有子分类
+pub(crate) const SUBTREE_CTE: &str = "WITH RECURSIVE subtree(id) AS (
+    SELECT ?
+    UNION ALL
+    SELECT c.id FROM categories c JOIN subtree s ON c.parent_id = s.id
+) ";
+
+pub(crate) const RESOURCE_COLUMNS: &str = "r.id, r.category_id, r.file_name, r.file_path, r.file_size, \
+     r.file_type, r.mime_type, r.kind, r.modified_at, r.scanned_at";
+/// RESOURCE_COLUMNS 的列数，在其后追加查询的列从该下标开始
+pub(crate) const RESOURCE_COLUMN_COUNT: usize = 10;
+
+/// 资源查询服务
+pub struct ResourceService {
+    db: Arc<Database>,
+}
+
+impl ResourceService {
+    pub fn new(db: Arc<Database>) -> Self {
+        ResourceService { db }
+    }
+
+    /// 按 ID 获取单个资源
+    pub fn get_resource(&self, resource_id: i64) -> Result<ResourceItem> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+
+        conn.query_row(
+            &format!("SELECT {} FROM resources r WHERE r.id = ?", RESOURCE_COLUMNS),
+            [resource_id],
+            map_resource,
+        )
+        .optional()?
+        .ok_or_else(|| AppError::InvalidInput(format!("Resource not found: {}", resource_id)))
+    }
+
+    /// 按分类列出资源，使用键集分页：cursor 为上一页返回的 next_page，
+    /// 翻页开销与所在页数无关，适合浏览大量资源；excluded_category_ids 中的子分类会被跳过
+    pub fn list_resources(
+        &self,
+        category_id: i64,
+        include_descendants: bool,
+        excluded_category_ids: &[i64],
+        sort: ResourceSort,
+        cursor: Option<&str>,
+        page_size: Option<u32>,
+    ) -> Result<ResourcePage> {
+        let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+        let (with_clause, mut category_condition) = if include_descendants {
+            (SUBTREE_CTE, "r.category_id IN (SELECT id FROM subtree)".to_string())
+        } else {
+            ("", "r.category_id = ?".to_string())
+        };
+        let mut values: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(category_id)];
+        if !excluded_category_ids.is_empty() {
+            category_condition.push_str(&format!(
+                " AND r.category_id NOT IN ({})",
+                vec!["?"; excluded_category_ids.len()].join(", ")
+            ));
+            for id in excluded_category_ids {
+                values.push(Box::new(*id));
+            }
+        }
+        let sort_expr = match sort.field {
+            ResourceSortField::Name => "r.file_name COLLATE NOCASE",
+            ResourceSortField::Size => "r.file_size",
+            ResourceSortField::Type => "r.file_type COLLATE NOCASE",
+            ResourceSortField::Modified => "r.modified_at",
+        };
+        let (op, order) = match sort.direction {
+            SortDirection::Asc => (">", "ASC"),
+            SortDirection::Desc => ("<", "DESC"),
+        };
+
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+
+        let total: i64 = {
+            let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+            conn.query_row(
+                &format!(
+                    "{}SELECT COUNT(*) FROM resources r WHERE {}",
+                    with_clause, category_condition
+                ),
+                params.as_slice(),
+                |row| row.get(0),
+            )?
+        };
+
+        let mut keyset_condition = String::new();
+        if let Some(cursor) = cursor {
+            let (id, value) = decode_cursor(cursor, sort.field)?;
+            keyset_condition = format!(
+                " AND ({expr} {op} ? OR ({expr} = ? AND r.id {op} ?))",
+                expr = sort_expr,
+                op = op
+            );
+            values.push(Box::new(value.clone()));
+            values.push(Box::new(value));
+            values.push(Box::new(id));
+        }
+
+        // 多取一条用于判断是否还有下一页
+        let mut stmt = conn.prepare(&format!(
+            "{}SELECT {} FROM resources r WHERE {}{} ORDER BY {} {}, r.id {} LIMIT {}",
+            with_clause,
+            RESOURCE_COLUMNS,
+            category_condition,
+            keyset_condition,
+            sort_expr,
+            order,
+            order,
+            page_size + 1
+        ))?;
+        let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+        let mut items = stmt
+            .query_map(params.as_slice(), map_resource)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let next_page = if items.len() > page_size as usize {
+            items.truncate(page_size as usize);
+            items.last().map(|last| encode_cursor(last, sort.field))
+        } else {
+            None
+        };
+
+        Ok(ResourcePage {
+            items,
+            total,
+            next_page,
+        })
+    }
+
+    /// 搜索资源，excluded_category_ids 中的分类（通常是仍处于锁定状态的分类）不会出现在结果中
+    pub fn search_resources(
+        &self,
+        req: &SearchResourcesRequest,
+        excluded_category_ids: &[i64],
+    ) -> Result<SearchResourcesResult> {
+        let page = req.page.unwrap_or(1).max(1);
+        let page_size = req.page_size.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+        let mut conditions: Vec<String> = Vec::new();
+        let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        // 构建全文检索表达式，每个关键字作为短语匹配，多个关键字之间为 AND
+        let query = req.query.trim();
+        let mut fts_phrases = Vec::new();
+        match req.mode {
+            SearchMode::Prefix if !query.is_empty() => {
+                conditions.push("r.file_name LIKE ? ESCAPE '\\'".to_string());
+                values.push(Box::new(format!("{}%", escape_like(query))));
+                if query.chars().count() >= MIN_FTS_TOKEN_CHARS {
+                    fts_phrases.push(format!("file_name : {}", quote_phrase(query)));
+                }
+            }
+            SearchMode::Substring => {
+                for token in query.split_whitespace() {
+                    if token.chars().count() >= MIN_FTS_TOKEN_CHARS {
+                        fts_phrases.push(quote_phrase(token));
+                    } else {
+                        conditions.push(
+                            "(r.file_name LIKE ? ESCAPE '\\' OR r.file_path LIKE ? ESCAPE '\\')".to_string(),
+                        );
+                        let pattern = format!("%{}%", escape_like(token));
+                        values.push(Box::new(pattern.clone()));
+                        values.push(Box::new(pattern));
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        let use_fts = !fts_phrases.is_empty();
+        if use_fts {
+            conditions.insert(0, "resources_fts MATCH ?".to_string());
+            values.insert(0, Box::new(fts_phrases.join(" AND ")));
+        }
+
+        // 分类 ID 绑定在 WITH 子句中，位于所有 WHERE 参数之前
+        if let Some(category_id) = req.category_id {
+            conditions.push("r.category_id IN (SELECT id FROM subtree)".to_string());
+            values.insert(0, Box::new(category_id));
+        }
+        if !excluded_category_ids.is_empty() {
+            conditions.push(format!(
+                "r.category_id NOT IN ({})",
+                vec!["?"; excluded_category_ids.len()].join(", ")
+            ));
+            for id in excluded_category_ids {
+                values.push(Box::new(*id));
+            }
+        }
+        if !req.file_types.is_empty() {
+            conditions.push(format!(
+                "LOWER(r.file_type) IN ({})",
+                vec!["?"; req.file_types.len()].join(", ")
+            ));
+            for file_type in &req.file_types {
+                values.push(Box::new(file_type.to_lowercase()));
+            }
+        }
+        if !req.kinds.is_empty() {
+            conditions.push(format!("r.kind IN ({})", vec!["?"; req.kinds.len()].join(", ")));
+            for kind in &req.kinds {
+                values.push(Box::new(kind.as_str()));
+            }
+        }
+        if let Some(min_size) = req.min_size {
+            conditions.push("r.file_size >= ?".to_string());
+            values.push(Box::new(min_size));
+        }
+        if let Some(max_size) = req.max_size {
+            conditions.push("r.file_size <= ?".to_string());
+            values.push(Box::new(max_size));
+        }
+        if let Some(after) = &req.modified_after {
+            conditions.push("r.modified_at >= ?".to_string());
+            values.push(Box::new(normalize_timestamp(after)));
+        }
+        if let Some(before) = &req.modified_before {
+            conditions.push("r.modified_at <= ?".to_string());
+            values.push(Box::new(normalize_timestamp(before)));
+        }
+
+        // 媒体元数据条件，任一条件存在时连接元数据表
+        let metadata_start = conditions.len();
+        let ranges = [
+            ("m.width", req.min_width.map(i64::from), req.max_width.map(i64::from)),
+            ("m.height", req.min_height.map(i64::from), req.max_height.map(i64::from)),
+            ("m.duration_ms", req.min_duration_ms, req.max_duration_ms),
+        ];
+        for (column, min, max) in ranges {
+            if let Some(min) = min {
+                conditions.push(format!("{} >= ?", column));
+                values.push(Box::new(min));
+            }
+            if let Some(max) = max {
+                conditions.push(format!("{} <= ?", column));
+                values.push(Box::new(max));
+            }
+        }
+        if let Some(after) = &req.captured_after {
+            conditions.push("m.captured_at >= ?".to_string());
+            values.push(Box::new(after.clone()));
+        }
+        if let Some(before) = &req.captured_before {
+            // 按前缀比较，只给出日期时包含当天
+            conditions.push("substr(m.captured_at, 1, length(?)) <= ?".to_string());
+            values.push(Box::new(before.clone()));
+            values.push(Box::new(before.clone()));
+        }
+        if let Some(query) = req.metadata_query.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            let columns = ["m.camera_make", "m.camera_model", "m.title", "m.artist", "m.album", "m.genre"];
+            conditions.push(format!(
+                "({})",
+                columns
+                    .iter()
+                    .map(|column| format!("{} LIKE ? ESCAPE '\\'", column))
+                    .collect::<Vec<_>>()
+                    .join(" OR ")
+            ));
+            let pattern = format!("%{}%", escape_like(query));
+            for _ in columns {
+                values.push(Box::new(pattern.clone()));
+            }
+        }
+        let use_metadata = conditions.len() > metadata_start;
+
+        let with_clause = if req.category_id.is_some() {
+            SUBTREE_CTE
+        } else {
+            ""
+        };
+        let mut join_clause = String::new();
+        if use_fts {
+            join_clause.push_str("JOIN resources_fts ON resources_fts.rowid = r.id ");
+        }
+        if use_metadata {
+            join_clause.push_str("JOIN resource_metadata m ON m.resource_id = r.id");
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        let order_clause = if use_fts {
+            "ORDER BY bm25(resources_fts), r.file_name COLLATE NOCASE, r.id"
+        } else {
+            "ORDER BY r.file_name COLLATE NOCASE, r.id"
+        };
+
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+
+        let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+        let total: i64 = conn.query_row(
+            &format!(
+                "{}SELECT COUNT(*) FROM resources r {} {}",
+                with_clause, join_clause, where_clause
+            ),
+            params.as_slice(),
+            |row| row.get(0),
+        )?;
+
+        let offset = (page as i64 - 1) * page_size as i64;
+        let mut stmt = conn.prepare(&format!(
+            "{}SELECT {} FROM resources r {} {} {} LIMIT {} OFFSET {}",
+            with_clause, RESOURCE_COLUMNS, join_clause, where_clause, order_clause, page_size, offset
+        ))?;
+        let items = stmt
+            .query_map(params.as_slice(), map_resource)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(SearchResourcesResult {
+            has_more: offset + (items.len() as i64) < total,
+            items,
+            total,
+            page,
+            page_size,
+        })
+    }
+}
+
+pub(crate) fn map_resource(row: &rusqlite::Row) -> rusqlite::Result<ResourceItem> {
+    Ok(ResourceItem {
+        id: row.get(0)?,
+        category_id: row.get(1)?,
+        file_name: row.get(2)?,
+        file_path: row.get(3)?,
+        file_size: row.get(4)?,
+        file_type: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+        mime_type: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+        kind: row
+            .get::<_, Option<String>>(7)?
+            .map(|kind| FileKind::parse(&kind))
+            .unwrap_or_default(),
+        modified_at: row.get(8)?,
+        scanned_at: row.get(9)?,
+    })
+}
+
+/// 分页游标格式为 "<id>:<排序列的值>"
+fn encode_cursor(item: &ResourceItem, field: ResourceSortField) -> String {
+    let value = match field {
+        ResourceSortField::Name => item.file_name.clone(),
+        ResourceSortField::Size => item.file_size.to_string(),
+        ResourceSortField::Type => item.file_type.clone(),
+        ResourceSortField::Modified => item.modified_at.clone(),
+    };
+    format!("{}:{}", item.id, value)
+}
+
+fn decode_cursor(cursor: &str, field: ResourceSortField) -> Result<(i64, Value)> {
+    let invalid = || AppError::InvalidInput(format!("Invalid page cursor: {}", cursor));
+    let (id, value) = cursor.split_once(':').ok_or_else(invalid)?;
+    let id: i64 = id.parse().map_err(|_| invalid())?;
+    let value = match field {
+        ResourceSortField::Size => Value::Integer(value.parse().map_err(|_| invalid())?),
+        _ => Value::Text(value.to_string()),
+    };
+    Ok((id, value))
+}
+
+/// 将关键字包装为 FTS5 短语，内部的双引号需要转义
+fn quote_phrase(token: &str) -> String {
+    format!("\"{}\"", token.replace('"', "\"\""))
+}
+
+/// 转义 LIKE 通配符
+pub(crate) fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// modified_at 以 UTC 的 RFC 3339 字符串存储，把带时区的时间换算到 UTC 后才能按字符串比较
+fn normalize_timestamp(value: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&chrono::Utc).to_rfc3339())
+        .unwrap_or_else(|_| value.to_string())
+}
@@ -0,0 +1,126 @@
+use crate::db::models::ScanSummary;
+use crate::db::Database;
+use crate::error::Result;
+use crate::services::job::{Job, JobRegistry, JobState, JobStatus};
+use crate::services::DirectoryService;
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// 扫描进度事件名
+pub const SCAN_PROGRESS_EVENT: &str = "scan://progress";
+
+/// 扫描任务状态，同时作为 scan://progress 事件的负载
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanJobStatus {
+    pub job_id: String,
+    pub category_id: i64,
+    pub state: JobState,
+    pub files_scanned: i64,
+    pub current_path: Option<String>,
+    pub summary: Option<ScanSummary>,
+    pub error: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+impl JobStatus for ScanJobStatus {
+    fn job_id(&self) -> &str {
+        &self.job_id
+    }
+
+    fn state(&self) -> JobState {
+        self.state
+    }
+
+    fn finish(&mut self, state: JobState, error: Option<String>) {
+        self.state = state;
+        self.error = error;
+        self.current_path = None;
+        self.finished_at = Some(chrono::Utc::now().to_rfc3339());
+    }
+}
+
+/// 后台扫描任务管理器
+/// @description 每个分类同时只有一个扫描任务，不同分类的扫描可以并发进行
+pub struct ScanJobManager {
+    jobs: JobRegistry<ScanJobStatus>,
+}
+
+impl Default for ScanJobManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScanJobManager {
+    pub fn new() -> Self {
+        ScanJobManager {
+            jobs: JobRegistry::new("scan", SCAN_PROGRESS_EVENT),
+        }
+    }
+
+    /// 启动后台扫描，返回任务 ID；该分类已有进行中的任务时直接返回其 ID
+    pub fn start<R: Runtime>(
+        &self,
+        app: AppHandle<R>,
+        db: Arc<Database>,
+        category_id: i64,
+    ) -> Result<String> {
+        let job_id = self.jobs.start(
+            app,
+            Vec::new(),
+            |status| status.category_id == category_id,
+            |job_id| ScanJobStatus {
+                job_id,
+                category_id,
+                state: JobState::Running,
+                files_scanned: 0,
+                current_path: None,
+                summary: None,
+                error: None,
+                started_at: chrono::Utc::now().to_rfc3339(),
+                finished_at: None,
+            },
+            move |job, app| run_job(app, db, job),
+        );
+        Ok(job_id)
+    }
+
+    /// 获取任务状态，调用方需自行检查任务所属分类是否已解锁
+    pub fn get(&self, job_id: &str) -> Result<ScanJobStatus> {
+        self.jobs.get(job_id, &[])
+    }
+
+    /// 请求取消任务，任务会在处理下一个文件时停止，已扫描的结果不会写入数据库
+    pub fn cancel(&self, job_id: &str) -> Result<()> {
+        self.jobs.cancel(job_id, &[])
+    }
+}
+
+fn run_job<R: Runtime>(app: &AppHandle<R>, db: Arc<Database>, job: &Job<ScanJobStatus>) -> Result<()> {
+    let category_id = job.snapshot().category_id;
+    let service = DirectoryService::new(db);
+
+    let mut files_scanned = 0i64;
+    let result = service.scan_directory_with_progress(category_id, |path| {
+        if job.is_cancelled() {
+            return false;
+        }
+
+        files_scanned += 1;
+        let progress = job.update_progress(false, |status| {
+            status.files_scanned = files_scanned;
+            status.current_path = Some(path.to_string_lossy().to_string());
+        });
+        if let Some(snapshot) = progress {
+            let _ = app.emit(SCAN_PROGRESS_EVENT, snapshot);
+        }
+        true
+    });
+
+    job.update(|status| status.files_scanned = files_scanned);
+    let (_, summary) = result?;
+    job.update(|status| status.summary = Some(summary));
+    Ok(())
+}
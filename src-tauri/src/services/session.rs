@@ -0,0 +1,176 @@
+use crate::error::{AppError, Result};
+use crate::services::vault::VaultKey;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// 默认空闲自动锁定时间（分钟）
+pub const DEFAULT_AUTO_LOCK_MINUTES: u64 = 5;
+
+/// 单个解锁会话
+struct Session {
+    /// 通过全局密码校验后派生的加密密钥（可访问隐藏/加密的快捷方式）
+    vault_key: Option<VaultKey>,
+    /// 已通过密码校验的分类
+    categories: HashSet<i64>,
+    last_active: Instant,
+}
+
+impl Session {
+    /// 超时时间为 0 表示不自动锁定
+    fn is_expired(&self, timeout: Duration) -> bool {
+        !timeout.is_zero() && self.last_active.elapsed() >= timeout
+    }
+}
+
+/// 解锁会话管理器
+/// @description 校验密码成功后签发短期令牌，受保护的命令需要携带有效令牌；
+/// 会话在空闲超时或主窗口隐藏到托盘时自动失效
+pub struct SessionManager {
+    sessions: Mutex<HashMap<String, Session>>,
+    idle_timeout: Mutex<Duration>,
+}
+
+impl SessionManager {
+    pub fn new(idle_timeout_minutes: u64) -> Self {
+        SessionManager {
+            sessions: Mutex::new(HashMap::new()),
+            idle_timeout: Mutex::new(Duration::from_secs(idle_timeout_minutes * 60)),
+        }
+    }
+
+    /// 全局密码校验通过后调用，复用仍然有效的令牌，否则签发新令牌
+    pub fn unlock_global(&self, token: Option<&str>, key: VaultKey) -> String {
+        self.unlock(token, |session| session.vault_key = Some(key))
+    }
+
+    /// 分类密码校验通过后调用，复用仍然有效的令牌，否则签发新令牌
+    pub fn unlock_category(&self, token: Option<&str>, category_id: i64) -> String {
+        self.unlock(token, |session| {
+            session.categories.insert(category_id);
+        })
+    }
+
+    /// 要求令牌已通过全局密码校验
+    pub fn require_global(&self, token: Option<&str>) -> Result<()> {
+        self.global_key(token).map(|_| ())
+    }
+
+    /// 获取令牌对应会话的加密密钥，要求已通过全局密码校验
+    pub fn global_key(&self, token: Option<&str>) -> Result<VaultKey> {
+        let mut key = None;
+        self.with_session(token, |session| {
+            key = session.vault_key.clone();
+            key.is_some()
+        })?;
+        key.ok_or(AppError::Locked)
+    }
+
+    /// 要求令牌已解锁给定的全部分类，分类列表为空时直接放行
+    pub fn require_categories(&self, token: Option<&str>, category_ids: &[i64]) -> Result<()> {
+        if category_ids.is_empty() {
+            return Ok(());
+        }
+        self.with_session(token, |session| {
+            category_ids.iter().all(|id| session.categories.contains(id))
+        })
+    }
+
+    /// 获取令牌已解锁的分类，令牌无效时返回空集合
+    pub fn unlocked_categories(&self, token: Option<&str>) -> HashSet<i64> {
+        let mut categories = HashSet::new();
+        let _ = self.with_session(token, |session| {
+            categories = session.categories.clone();
+            true
+        });
+        categories
+    }
+
+    /// 注销指定会话
+    pub fn lock(&self, token: &str) {
+        self.sessions.lock().unwrap().remove(token);
+    }
+
+    /// 注销所有会话
+    pub fn lock_all(&self) {
+        let mut sessions = self.sessions.lock().unwrap();
+        if !sessions.is_empty() {
+            log::info!("Locking {} unlock session(s)", sessions.len());
+            sessions.clear();
+        }
+    }
+
+    /// 在所有会话中重新锁定指定分类，用于分类密码修改或清除后
+    pub fn lock_category(&self, category_id: i64) {
+        for session in self.sessions.lock().unwrap().values_mut() {
+            session.categories.remove(&category_id);
+        }
+    }
+
+    pub fn idle_timeout_minutes(&self) -> u64 {
+        self.idle_timeout.lock().unwrap().as_secs() / 60
+    }
+
+    pub fn set_idle_timeout_minutes(&self, minutes: u64) {
+        *self.idle_timeout.lock().unwrap() = Duration::from_secs(minutes * 60);
+    }
+
+    fn unlock<F>(&self, token: Option<&str>, grant: F) -> String
+    where
+        F: FnOnce(&mut Session),
+    {
+        let timeout = *self.idle_timeout.lock().unwrap();
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, session| !session.is_expired(timeout));
+
+        let token = match token {
+            Some(token) if sessions.contains_key(token) => token.to_string(),
+            _ => {
+                let token = Uuid::new_v4().to_string();
+                sessions.insert(
+                    token.clone(),
+                    Session {
+                        vault_key: None,
+                        categories: HashSet::new(),
+                        last_active: Instant::now(),
+                    },
+                );
+                token
+            }
+        };
+
+        let session = sessions.get_mut(&token).unwrap();
+        grant(session);
+        session.last_active = Instant::now();
+
+        token
+    }
+
+    /// 校验令牌有效且满足条件，成功时刷新最近活动时间
+    fn with_session<F>(&self, token: Option<&str>, allowed: F) -> Result<()>
+    where
+        F: FnOnce(&Session) -> bool,
+    {
+        let token = token.ok_or(AppError::Locked)?;
+        let timeout = *self.idle_timeout.lock().unwrap();
+        let mut sessions = self.sessions.lock().unwrap();
+
+        let expired = match sessions.get(token) {
+            Some(session) => session.is_expired(timeout),
+            None => return Err(AppError::Locked),
+        };
+        if expired {
+            sessions.remove(token);
+            return Err(AppError::Locked);
+        }
+
+        let session = sessions.get_mut(token).unwrap();
+        if !allowed(session) {
+            return Err(AppError::Locked);
+        }
+        session.last_active = Instant::now();
+
+        Ok(())
+    }
+}
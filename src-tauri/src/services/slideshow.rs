@@ -0,0 +1,523 @@
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::services::wallpaper::WallpaperFitMode;
+use crate::services::WallpaperService;
+use chrono::{DateTime, Local, NaiveTime, Utc};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+use uuid::Uuid;
+
+/// 轮播状态变化（切换壁纸、暂停、继续等）时推送给前端的事件名
+pub const SLIDESHOW_CHANGED_EVENT: &str = "slideshow://changed";
+
+/// 轮播状态存放在 app_settings 中的键
+const SETTINGS_KEY: &str = "wallpaper_slideshow";
+
+/// 调度线程最长休眠时间，到点后重新检查时段规则，系统休眠或调整时钟后也能及时恢复
+const MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// 播放列表来源
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PlaylistSource {
+    /// 所有本地壁纸，按导入时间排列
+    All,
+    /// 指定的壁纸，按给定顺序排列
+    Wallpapers { ids: Vec<String> },
+    /// 收藏的壁纸，按导入时间排列
+    Favorites,
+    /// 合集中的壁纸，按加入合集的时间排列
+    Collection { collection_id: i64 },
+    /// 带有该标签的壁纸，按导入时间排列
+    Tag { tag_id: i64 },
+}
+
+/// 播放顺序
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlideshowOrder {
+    #[default]
+    Sequential,
+    /// 每轮播放完后重新打乱
+    Shuffle,
+}
+
+/// 时段规则，当前时间处于 [start, end) 内时改用该规则的播放列表
+/// start 晚于 end 时表示跨越午夜，如 22:00 ~ 06:00；两者相同时表示全天
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeRule {
+    /// 本地时间 HH:MM
+    pub start: String,
+    pub end: String,
+    pub source: PlaylistSource,
+}
+
+/// 轮播配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlideshowConfig {
+    pub source: PlaylistSource,
+    pub interval_minutes: u32,
+    #[serde(default)]
+    pub order: SlideshowOrder,
+    #[serde(default)]
+    pub fit_mode: WallpaperFitMode,
+    /// 只轮播该显示器的壁纸，不指定时设置到所有显示器
+    #[serde(default)]
+    pub monitor_id: Option<String>,
+    /// 按顺序匹配，第一条命中的规则生效，都不命中时使用 source
+    #[serde(default)]
+    pub rules: Vec<TimeRule>,
+}
+
+/// 轮播状态，会持久化到数据库，重启后继续轮播
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SlideshowStatus {
+    pub enabled: bool,
+    pub paused: bool,
+    pub config: Option<SlideshowConfig>,
+    /// 本轮播放顺序（壁纸 ID），随机播放时为打乱后的顺序
+    pub playlist: Vec<String>,
+    /// 当前壁纸在 playlist 中的下标
+    pub position: Option<usize>,
+    pub current_wallpaper_id: Option<String>,
+    /// 生效的时段规则下标，None 表示使用默认播放列表
+    pub active_rule: Option<usize>,
+    pub last_changed_at: Option<String>,
+    /// 下次切换时间，暂停或停止时为 None
+    pub next_change_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+/// 切换方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Next,
+    Previous,
+}
+
+/// 壁纸轮播调度器
+/// @description 调度线程独立于窗口运行，窗口隐藏到托盘后仍会按时切换；
+/// 所有会修改状态的操作都持有 apply_lock，保证切换壁纸与暂停、停止等操作不会交错
+pub struct SlideshowManager {
+    status: Mutex<SlideshowStatus>,
+    wake: Condvar,
+    apply_lock: Mutex<()>,
+}
+
+impl Default for SlideshowManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SlideshowManager {
+    pub fn new() -> Self {
+        SlideshowManager {
+            status: Mutex::new(SlideshowStatus::default()),
+            wake: Condvar::new(),
+            apply_lock: Mutex::new(()),
+        }
+    }
+
+    /// 恢复上次保存的轮播状态并启动调度线程
+    pub fn restore<R: Runtime>(self: &Arc<Self>, app: AppHandle<R>, db: Arc<Database>) -> Result<()> {
+        if let Some(saved) = load_status(&db)? {
+            *self.status.lock().unwrap() = saved;
+        }
+
+        let manager = Arc::clone(self);
+        std::thread::Builder::new()
+            .name("wallpaper-slideshow".to_string())
+            .spawn(move || manager.run(app, db))?;
+        Ok(())
+    }
+
+    pub fn status(&self) -> SlideshowStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// 按新配置开始轮播，并立即切换到播放列表中的第一张
+    pub fn start<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        db: &Arc<Database>,
+        config: SlideshowConfig,
+    ) -> Result<SlideshowStatus> {
+        validate_config(&config)?;
+        let _apply = self.apply_lock.lock().unwrap();
+
+        let mut status = SlideshowStatus {
+            enabled: true,
+            config: Some(config),
+            ..Default::default()
+        };
+        if let Err(e) = self.advance(app, db, &mut status, Step::Next) {
+            // 第一张就无法设置时不开启轮播，保留配置与错误信息
+            status.enabled = false;
+            status.next_change_at = None;
+            self.commit(app, db, &status)?;
+            return Err(e);
+        }
+        Ok(status)
+    }
+
+    /// 停止轮播，保留配置以便下次启动
+    pub fn stop<R: Runtime>(&self, app: &AppHandle<R>, db: &Database) -> Result<SlideshowStatus> {
+        self.update(app, db, |status| {
+            status.enabled = false;
+            status.paused = false;
+            status.next_change_at = None;
+            Ok(())
+        })
+    }
+
+    pub fn pause<R: Runtime>(&self, app: &AppHandle<R>, db: &Database) -> Result<SlideshowStatus> {
+        self.update(app, db, |status| {
+            ensure_enabled(status)?;
+            status.paused = true;
+            status.next_change_at = None;
+            Ok(())
+        })
+    }
+
+    /// 继续轮播，从现在起重新计算切换间隔
+    pub fn resume<R: Runtime>(&self, app: &AppHandle<R>, db: &Database) -> Result<SlideshowStatus> {
+        self.update(app, db, |status| {
+            ensure_enabled(status)?;
+            status.paused = false;
+            status.next_change_at = next_change_at(status);
+            Ok(())
+        })
+    }
+
+    /// 在暂停与继续之间切换，供托盘菜单使用
+    pub fn toggle_pause<R: Runtime>(&self, app: &AppHandle<R>, db: &Database) -> Result<SlideshowStatus> {
+        if self.status().paused {
+            self.resume(app, db)
+        } else {
+            self.pause(app, db)
+        }
+    }
+
+    pub fn next<R: Runtime>(&self, app: &AppHandle<R>, db: &Arc<Database>) -> Result<SlideshowStatus> {
+        self.step(app, db, Step::Next)
+    }
+
+    pub fn previous<R: Runtime>(&self, app: &AppHandle<R>, db: &Arc<Database>) -> Result<SlideshowStatus> {
+        self.step(app, db, Step::Previous)
+    }
+
+    fn step<R: Runtime>(&self, app: &AppHandle<R>, db: &Arc<Database>, step: Step) -> Result<SlideshowStatus> {
+        let _apply = self.apply_lock.lock().unwrap();
+        let mut status = self.status();
+        ensure_enabled(&status)?;
+        self.advance(app, db, &mut status, step)?;
+        Ok(status)
+    }
+
+    /// 修改状态后保存并通知调度线程与前端
+    fn update<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        db: &Database,
+        change: impl FnOnce(&mut SlideshowStatus) -> Result<()>,
+    ) -> Result<SlideshowStatus> {
+        let _apply = self.apply_lock.lock().unwrap();
+        let mut status = self.status();
+        change(&mut status)?;
+        self.commit(app, db, &status)?;
+        Ok(status)
+    }
+
+    fn commit<R: Runtime>(&self, app: &AppHandle<R>, db: &Database, status: &SlideshowStatus) -> Result<()> {
+        save_status(db, status)?;
+        *self.status.lock().unwrap() = status.clone();
+        self.wake.notify_all();
+        let _ = app.emit(SLIDESHOW_CHANGED_EVENT, status.clone());
+        Ok(())
+    }
+
+    /// 切换到上一张或下一张壁纸，调用方需持有 apply_lock
+    /// 时段规则变化或播放列表为空时重新生成播放列表；文件已不存在的壁纸会被移出播放列表
+    fn advance<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        db: &Arc<Database>,
+        status: &mut SlideshowStatus,
+        step: Step,
+    ) -> Result<()> {
+        let config = status
+            .config
+            .clone()
+            .ok_or_else(|| AppError::InvalidInput("尚未配置壁纸轮播".to_string()))?;
+
+        let rule = active_rule(&config.rules, Local::now().time());
+        if rule != status.active_rule || status.playlist.is_empty() {
+            let source = rule.map(|i| &config.rules[i].source).unwrap_or(&config.source);
+            status.playlist = build_playlist(db, source, config.order, status.current_wallpaper_id.as_deref())?;
+            status.position = None;
+            status.active_rule = rule;
+        }
+
+        let service = WallpaperService::new(db.clone());
+        let result = loop {
+            if status.playlist.is_empty() {
+                break Err(AppError::InvalidInput("播放列表中没有可用的壁纸".to_string()));
+            }
+
+            let len = status.playlist.len();
+            let position = match (status.position, step) {
+                (None, _) => 0,
+                (Some(p), Step::Next) if p + 1 >= len => {
+                    // 随机播放时每轮结束后重新打乱
+                    if config.order == SlideshowOrder::Shuffle {
+                        shuffle(&mut status.playlist, status.current_wallpaper_id.as_deref());
+                    }
+                    0
+                }
+                (Some(p), Step::Next) => p + 1,
+                (Some(p), Step::Previous) => (p + len - 1) % len,
+            };
+
+            let id = status.playlist[position].clone();
+            match wallpaper_file_path(db, &id)? {
+                Some(file_path) if Path::new(&file_path).is_file() => {
+                    status.position = Some(position);
+                    break service
+                        .apply_wallpaper(app, &file_path, config.fit_mode, config.monitor_id.as_deref())
+                        .map(|_| id);
+                }
+                _ => {
+                    log::info!("Removing missing wallpaper {} from slideshow", id);
+                    status.playlist.remove(position);
+                    // 移除后让下标指向前一项，下一次循环仍按同一方向前进
+                    status.position = match step {
+                        Step::Next => position.checked_sub(1),
+                        Step::Previous => Some(position % status.playlist.len().max(1)),
+                    };
+                }
+            }
+        };
+
+        let now = Utc::now();
+        match &result {
+            Ok(id) => {
+                status.current_wallpaper_id = Some(id.clone());
+                status.last_changed_at = Some(now.to_rfc3339());
+                status.last_error = None;
+            }
+            Err(e) => {
+                log::warn!("Slideshow failed to change wallpaper: {}", e);
+                status.last_error = Some(e.to_string());
+            }
+        }
+        // 切换失败时同样按间隔重试，避免在壁纸无法设置时反复尝试
+        status.next_change_at = if status.paused { None } else { next_change_at(status) };
+        self.commit(app, db, status)?;
+        result.map(|_| ())
+    }
+
+    /// 调度线程：到达切换时间或时段规则变化时切换壁纸
+    fn run<R: Runtime>(self: Arc<Self>, app: AppHandle<R>, db: Arc<Database>) {
+        loop {
+            let due = {
+                let status = self.status.lock().unwrap();
+                let wait = match (&status.config, status.enabled && !status.paused) {
+                    (Some(config), true) => {
+                        let rule_changed = active_rule(&config.rules, Local::now().time()) != status.active_rule;
+                        let until_due = status
+                            .next_change_at
+                            .as_deref()
+                            .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+                            .map(|at| (at.with_timezone(&Utc) - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+                            .unwrap_or(Duration::ZERO);
+                        if rule_changed || until_due.is_zero() {
+                            None
+                        } else {
+                            Some(until_due.min(MAX_WAIT))
+                        }
+                    }
+                    _ => Some(MAX_WAIT),
+                };
+                match wait {
+                    None => true,
+                    Some(wait) => {
+                        let _ = self.wake.wait_timeout(status, wait).unwrap();
+                        false
+                    }
+                }
+            };
+
+            if due {
+                if let Err(e) = self.step(&app, &db, Step::Next) {
+                    log::warn!("Scheduled wallpaper change failed: {}", e);
+                    // 读取数据库等失败时状态未更新，等待一段时间再重试
+                    let status = self.status.lock().unwrap();
+                    let _ = self.wake.wait_timeout(status, MAX_WAIT).unwrap();
+                }
+            }
+        }
+    }
+}
+
+fn ensure_enabled(status: &SlideshowStatus) -> Result<()> {
+    if status.enabled {
+        Ok(())
+    } else {
+        Err(AppError::InvalidInput("壁纸轮播未开启".to_string()))
+    }
+}
+
+fn validate_config(config: &SlideshowConfig) -> Result<()> {
+    if config.interval_minutes == 0 {
+        return Err(AppError::InvalidInput("轮播间隔至少为 1 分钟".to_string()));
+    }
+    for rule in &config.rules {
+        parse_time(&rule.start)?;
+        parse_time(&rule.end)?;
+    }
+    Ok(())
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .map_err(|_| AppError::InvalidInput(format!("无效的时间: {}，应为 HH:MM", value)))
+}
+
+/// 返回当前生效的时段规则下标
+fn active_rule(rules: &[TimeRule], now: NaiveTime) -> Option<usize> {
+    rules.iter().position(|rule| {
+        let (Ok(start), Ok(end)) = (parse_time(&rule.start), parse_time(&rule.end)) else {
+            return false;
+        };
+        if start < end {
+            start <= now && now < end
+        } else if start > end {
+            now >= start || now < end
+        } else {
+            true
+        }
+    })
+}
+
+fn next_change_at(status: &SlideshowStatus) -> Option<String> {
+    let config = status.config.as_ref()?;
+    let next = Utc::now() + chrono::Duration::minutes(i64::from(config.interval_minutes));
+    Some(next.to_rfc3339())
+}
+
+/// 按来源生成播放列表，随机播放时打乱顺序并避免第一张与当前壁纸相同
+fn build_playlist(
+    db: &Database,
+    source: &PlaylistSource,
+    order: SlideshowOrder,
+    current: Option<&str>,
+) -> Result<Vec<String>> {
+    let conn = db.get_connection();
+    let conn = conn.lock().unwrap();
+    let mut playlist: Vec<String> = match source {
+        PlaylistSource::All => query_ids(
+            &conn,
+            "SELECT id FROM wallpapers WHERE missing = 0 ORDER BY created_at, id",
+            params![],
+        )?,
+        PlaylistSource::Favorites => query_ids(
+            &conn,
+            "SELECT id FROM wallpapers WHERE missing = 0 AND favorite = 1 ORDER BY created_at, id",
+            params![],
+        )?,
+        PlaylistSource::Collection { collection_id } => query_ids(
+            &conn,
+            "SELECT w.id FROM wallpaper_collection_items i JOIN wallpapers w ON w.id = i.wallpaper_id
+             WHERE i.collection_id = ?1 AND w.missing = 0 ORDER BY i.added_at, w.id",
+            params![collection_id],
+        )?,
+        PlaylistSource::Tag { tag_id } => query_ids(
+            &conn,
+            "SELECT w.id FROM wallpaper_tag_links l JOIN wallpapers w ON w.id = l.wallpaper_id
+             WHERE l.tag_id = ?1 AND w.missing = 0 ORDER BY w.created_at, w.id",
+            params![tag_id],
+        )?,
+        PlaylistSource::Wallpapers { ids } => {
+            let mut stmt = conn.prepare("SELECT 1 FROM wallpapers WHERE id = ?1 AND missing = 0")?;
+            let mut existing = vec![];
+            for id in ids {
+                if stmt.exists(params![id])? && !existing.contains(id) {
+                    existing.push(id.clone());
+                }
+            }
+            existing
+        }
+    };
+    drop(conn);
+
+    if order == SlideshowOrder::Shuffle {
+        shuffle(&mut playlist, current);
+    }
+    Ok(playlist)
+}
+
+fn query_ids(conn: &rusqlite::Connection, sql: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(sql)?;
+    let ids = stmt
+        .query_map(params, |row| row.get(0))?
+        .collect::<std::result::Result<Vec<String>, _>>()?;
+    Ok(ids)
+}
+
+fn shuffle(playlist: &mut [String], current: Option<&str>) {
+    playlist.sort_by_cached_key(|_| Uuid::new_v4());
+    if playlist.len() > 1 && playlist.first().map(String::as_str) == current {
+        let last = playlist.len() - 1;
+        playlist.swap(0, last);
+    }
+}
+
+fn wallpaper_file_path(db: &Database, wallpaper_id: &str) -> Result<Option<String>> {
+    let conn = db.get_connection();
+    let conn = conn.lock().unwrap();
+    Ok(conn
+        .query_row(
+            "SELECT file_path FROM wallpapers WHERE id = ?1",
+            params![wallpaper_id],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+fn load_status(db: &Database) -> Result<Option<SlideshowStatus>> {
+    let conn = db.get_connection();
+    let conn = conn.lock().unwrap();
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM app_settings WHERE key = ?1",
+            params![SETTINGS_KEY],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match value {
+        Some(value) => match serde_json::from_str(&value) {
+            Ok(status) => Ok(Some(status)),
+            Err(e) => {
+                log::warn!("Ignoring invalid slideshow state: {}", e);
+                Ok(None)
+            }
+        },
+        None => Ok(None),
+    }
+}
+
+fn save_status(db: &Database, status: &SlideshowStatus) -> Result<()> {
+    let value = serde_json::to_string(status)?;
+    let conn = db.get_connection();
+    let conn = conn.lock().unwrap();
+    conn.execute(
+        "INSERT OR REPLACE INTO app_settings (key, value, updated_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)",
+        params![SETTINGS_KEY, value],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,389 @@
+use crate::db::models::{
+    BindingUsage, CategoryStats, FileKind, FileTypeUsage, FolderUsage, KindUsage, ResourceItem, UsageNode,
+    UsageNodeKind,
+};
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::services::resource::{map_resource, RESOURCE_COLUMNS};
+use rusqlite::Connection;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+/// 默认返回的最大文件 / 文件夹数量
+pub const DEFAULT_TOP_N: usize = 20;
+const MAX_TOP_N: usize = 1000;
+
+/// 容量树中绑定目录以下最多展开的文件夹层数，更深的文件夹计入其上层文件夹
+const MAX_FOLDER_DEPTH: usize = 8;
+
+/// 截取文件所在目录（保留末尾的分隔符），同时兼容 / 与 \ 分隔的路径
+const DIRECTORY_EXPR: &str = "rtrim(file_path, replace(replace(file_path, '/', ''), '\\', ''))";
+
+struct CategoryRow {
+    id: i64,
+    parent_id: Option<i64>,
+    name: String,
+}
+
+struct BindingRow {
+    id: i64,
+    category_id: i64,
+    directory_path: String,
+    total_bytes: i64,
+    file_count: i64,
+}
+
+/// 绑定目录下的子文件夹（含其所有子孙文件）
+struct FolderRow {
+    binding_id: i64,
+    category_id: i64,
+    total_bytes: i64,
+    file_count: i64,
+}
+
+/// 容量统计服务
+pub struct StatsService {
+    db: Arc<Database>,
+}
+
+impl StatsService {
+    pub fn new(db: Arc<Database>) -> Self {
+        StatsService { db }
+    }
+
+    /// 统计分类（未指定时为全部分类）及其子分类的磁盘占用
+    /// excluded_category_ids 中的分类及其资源不参与统计
+    pub fn get_category_stats(
+        &self,
+        category_id: Option<i64>,
+        top_n: usize,
+        excluded_category_ids: &[i64],
+    ) -> Result<CategoryStats> {
+        let top_n = top_n.clamp(1, MAX_TOP_N);
+        let excluded: HashSet<i64> = excluded_category_ids.iter().copied().collect();
+
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+
+        let categories: Vec<CategoryRow> = {
+            let mut stmt = conn.prepare("SELECT id, parent_id, name FROM categories ORDER BY sort_order, id")?;
+            let rows = stmt.query_map([], |row| {
+                Ok(CategoryRow {
+                    id: row.get(0)?,
+                    parent_id: row.get(1)?,
+                    name: row.get(2)?,
+                })
+            })?;
+            rows.collect::<std::result::Result<Vec<_>, _>>()?
+        };
+        let mut children: HashMap<Option<i64>, Vec<&CategoryRow>> = HashMap::new();
+        for category in categories.iter().filter(|c| !excluded.contains(&c.id)) {
+            children.entry(category.parent_id).or_default().push(category);
+        }
+
+        let roots: Vec<&CategoryRow> = match category_id {
+            Some(id) => vec![categories
+                .iter()
+                .find(|c| c.id == id && !excluded.contains(&c.id))
+                .ok_or(AppError::CategoryNotFound)?],
+            None => children.get(&None).cloned().unwrap_or_default(),
+        };
+
+        // 统计范围内的所有分类
+        let mut scope = Vec::new();
+        let mut visited = HashSet::new();
+        let mut stack: Vec<i64> = roots.iter().map(|c| c.id).collect();
+        while let Some(id) = stack.pop() {
+            if visited.insert(id) {
+                scope.push(id);
+                stack.extend(children.get(&Some(id)).into_iter().flatten().map(|c| c.id));
+            }
+        }
+        let scope_params: Vec<&dyn rusqlite::ToSql> = scope.iter().map(|id| id as &dyn rusqlite::ToSql).collect();
+        let in_scope = format!("category_id IN ({})", vec!["?"; scope.len().max(1)].join(", "));
+        let scope_params: &[&dyn rusqlite::ToSql] = if scope.is_empty() { &[&-1i64] } else { &scope_params };
+
+        let mut bindings: HashMap<i64, Vec<BindingRow>> = HashMap::new();
+        {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT id, category_id, directory_path FROM directory_bindings WHERE {} ORDER BY id",
+                in_scope
+            ))?;
+            let rows = stmt.query_map(scope_params, |row| {
+                Ok(BindingRow {
+                    id: row.get(0)?,
+                    category_id: row.get(1)?,
+                    directory_path: row.get(2)?,
+                    total_bytes: 0,
+                    file_count: 0,
+                })
+            })?;
+            for binding in rows {
+                let binding = binding?;
+                bindings.entry(binding.category_id).or_default().push(binding);
+            }
+        }
+
+        // 按目录聚合，每个目录只返回一行，再归属到路径最长的绑定目录并累加到其下的每一级文件夹
+        let mut own: HashMap<i64, (i64, i64)> = HashMap::new();
+        // 同一目录可能被不同分类绑定，按绑定目录区分
+        let mut folders: HashMap<(i64, String), FolderRow> = HashMap::new();
+        {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT category_id, {dir} AS directory, SUM(file_size), COUNT(*)
+                 FROM resources WHERE {in_scope}
+                 GROUP BY category_id, directory",
+                dir = DIRECTORY_EXPR,
+                in_scope = in_scope
+            ))?;
+            let rows = stmt.query_map(scope_params, |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, i64>(3)?,
+                ))
+            })?;
+            for row in rows {
+                let (category_id, directory, size, count) = row?;
+                let entry = own.entry(category_id).or_default();
+                entry.0 += size;
+                entry.1 += count;
+
+                let directory = trim_separator(&directory);
+                let binding = bindings.get_mut(&category_id).and_then(|list| {
+                    list.iter_mut()
+                        .filter(|b| Path::new(directory).starts_with(&b.directory_path))
+                        .max_by_key(|b| b.directory_path.len())
+                });
+                let Some(binding) = binding else {
+                    continue;
+                };
+                binding.total_bytes += size;
+                binding.file_count += count;
+
+                let root = Path::new(&binding.directory_path);
+                let mut folder = Some(Path::new(directory));
+                while let Some(dir) = folder {
+                    if dir == root || !dir.starts_with(root) {
+                        break;
+                    }
+                    let entry = folders.entry((binding.id, dir.to_string_lossy().to_string())).or_insert(FolderRow {
+                        binding_id: binding.id,
+                        category_id,
+                        total_bytes: 0,
+                        file_count: 0,
+                    });
+                    entry.total_bytes += size;
+                    entry.file_count += count;
+                    folder = dir.parent();
+                }
+            }
+        }
+
+        let mut file_types: Vec<FileTypeUsage> = {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT LOWER(file_type) AS file_type, SUM(file_size), COUNT(*)
+                 FROM resources WHERE {} GROUP BY LOWER(file_type)",
+                in_scope
+            ))?;
+            let rows = stmt.query_map(scope_params, |row| {
+                Ok(FileTypeUsage {
+                    file_type: row.get(0)?,
+                    total_bytes: row.get(1)?,
+                    file_count: row.get(2)?,
+                })
+            })?;
+            rows.collect::<std::result::Result<Vec<_>, _>>()?
+        };
+        file_types.sort_by(|a, b| b.total_bytes.cmp(&a.total_bytes).then(a.file_type.cmp(&b.file_type)));
+
+        // kind 为空（尚未识别）的资源与 other 合并
+        let mut kind_totals: HashMap<FileKind, (i64, i64)> = HashMap::new();
+        {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT kind, SUM(file_size), COUNT(*) FROM resources WHERE {} GROUP BY kind",
+                in_scope
+            ))?;
+            let rows = stmt.query_map(scope_params, |row| {
+                Ok((
+                    row.get::<_, Option<String>>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })?;
+            for row in rows {
+                let (kind, size, count) = row?;
+                let kind = kind.map(|k| FileKind::parse(&k)).unwrap_or_default();
+                let entry = kind_totals.entry(kind).or_default();
+                entry.0 += size;
+                entry.1 += count;
+            }
+        }
+        let mut kinds: Vec<KindUsage> = kind_totals
+            .into_iter()
+            .map(|(kind, (total_bytes, file_count))| KindUsage {
+                kind,
+                total_bytes,
+                file_count,
+            })
+            .collect();
+        kinds.sort_by(|a, b| b.total_bytes.cmp(&a.total_bytes).then(a.kind.as_str().cmp(b.kind.as_str())));
+
+        let largest_files = largest_files(&conn, &in_scope, scope_params, top_n)?;
+
+        // 子文件夹按所在目录分组，用于构建绑定目录以下的文件夹层级
+        let tree: Vec<UsageNode> = {
+            let mut subfolders: HashMap<&Path, Vec<(&Path, &FolderRow)>> = HashMap::new();
+            for ((_, path), folder) in &folders {
+                let path = Path::new(path.as_str());
+                if let Some(parent) = path.parent() {
+                    subfolders.entry(parent).or_default().push((path, folder));
+                }
+            }
+            roots
+                .iter()
+                .map(|root| build_node(root, &children, &own, &bindings, &subfolders))
+                .collect()
+        };
+
+        let mut largest_folders: Vec<FolderUsage> = folders
+            .into_iter()
+            .map(|((_, path), folder)| FolderUsage {
+                path,
+                category_id: folder.category_id,
+                total_bytes: folder.total_bytes,
+                file_count: folder.file_count,
+            })
+            .collect();
+        largest_folders.sort_by(|a, b| b.total_bytes.cmp(&a.total_bytes).then(a.path.cmp(&b.path)));
+        largest_folders.truncate(top_n);
+
+        let mut bindings: Vec<BindingUsage> = bindings
+            .into_values()
+            .flatten()
+            .map(|b| BindingUsage {
+                binding_id: b.id,
+                category_id: b.category_id,
+                directory_path: b.directory_path,
+                total_bytes: b.total_bytes,
+                file_count: b.file_count,
+            })
+            .collect();
+        bindings.sort_by_key(|b| b.binding_id);
+
+        Ok(CategoryStats {
+            total_bytes: tree.iter().map(|n| n.total_bytes).sum(),
+            file_count: tree.iter().map(|n| n.file_count).sum(),
+            tree,
+            bindings,
+            file_types,
+            kinds,
+            largest_files,
+            largest_folders,
+        })
+    }
+}
+
+fn largest_files(
+    conn: &Connection,
+    in_scope: &str,
+    scope_params: &[&dyn rusqlite::ToSql],
+    top_n: usize,
+) -> Result<Vec<ResourceItem>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM resources r WHERE r.{} ORDER BY r.file_size DESC, r.id LIMIT {}",
+        RESOURCE_COLUMNS, in_scope, top_n
+    ))?;
+    let files = stmt
+        .query_map(scope_params, map_resource)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(files)
+}
+
+/// 递归构建分类容量节点，子节点按占用空间从大到小排列
+fn build_node(
+    category: &CategoryRow,
+    children: &HashMap<Option<i64>, Vec<&CategoryRow>>,
+    own: &HashMap<i64, (i64, i64)>,
+    bindings: &HashMap<i64, Vec<BindingRow>>,
+    subfolders: &HashMap<&Path, Vec<(&Path, &FolderRow)>>,
+) -> UsageNode {
+    let (mut total_bytes, mut file_count) = own.get(&category.id).copied().unwrap_or((0, 0));
+
+    let mut nodes: Vec<UsageNode> = children
+        .get(&Some(category.id))
+        .into_iter()
+        .flatten()
+        .map(|child| build_node(child, children, own, bindings, subfolders))
+        .collect();
+    for node in &nodes {
+        total_bytes += node.total_bytes;
+        file_count += node.file_count;
+    }
+
+    nodes.extend(bindings.get(&category.id).into_iter().flatten().map(|b| UsageNode {
+        id: b.id,
+        name: b.directory_path.clone(),
+        kind: UsageNodeKind::Binding,
+        path: Some(b.directory_path.clone()),
+        total_bytes: b.total_bytes,
+        file_count: b.file_count,
+        children: folder_nodes(Path::new(&b.directory_path), b.id, subfolders, 1),
+    }));
+    nodes.sort_by_key(|n| Reverse(n.total_bytes));
+
+    UsageNode {
+        id: category.id,
+        name: category.name.clone(),
+        kind: UsageNodeKind::Category,
+        path: None,
+        total_bytes,
+        file_count,
+        children: nodes,
+    }
+}
+
+/// 递归构建目录下的文件夹节点，节点 id 为所属绑定目录的 ID
+fn folder_nodes(
+    directory: &Path,
+    binding_id: i64,
+    subfolders: &HashMap<&Path, Vec<(&Path, &FolderRow)>>,
+    depth: usize,
+) -> Vec<UsageNode> {
+    if depth > MAX_FOLDER_DEPTH {
+        return Vec::new();
+    }
+    let mut nodes: Vec<UsageNode> = subfolders
+        .get(directory)
+        .into_iter()
+        .flatten()
+        .filter(|(_, folder)| folder.binding_id == binding_id)
+        .map(|(path, folder)| UsageNode {
+            id: binding_id,
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            kind: UsageNodeKind::Folder,
+            path: Some(path.to_string_lossy().to_string()),
+            total_bytes: folder.total_bytes,
+            file_count: folder.file_count,
+            children: folder_nodes(path, binding_id, subfolders, depth + 1),
+        })
+        .collect();
+    nodes.sort_by(|a, b| b.total_bytes.cmp(&a.total_bytes).then(a.name.cmp(&b.name)));
+    nodes
+}
+
+/// 去掉目录末尾的分隔符，根目录保持不变
+fn trim_separator(directory: &str) -> &str {
+    let trimmed = directory.trim_end_matches(['/', '\\']);
+    if trimmed.is_empty() || trimmed.ends_with(':') {
+        directory
+    } else {
+        trimmed
+    }
+}
@@ -0,0 +1,360 @@
+use crate::error::{AppError, Result};
+use base64::Engine;
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageDecoder, ImageReader, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 可生成缩略图的图片扩展名，与壁纸导入允许的格式一致
+pub const THUMBNAIL_EXTENSIONS: [&str; 7] = ["png", "jpg", "jpeg", "gif", "bmp", "webp", "svg"];
+
+/// 缩略图边长上限，避免任意尺寸的请求撑大缓存
+const MAX_THUMBNAIL_SIZE: u32 = 1024;
+/// 缓存目录默认容量上限
+const DEFAULT_MAX_CACHE_BYTES: u64 = 256 * 1024 * 1024;
+/// 超出上限后淘汰到容量的该比例以下，避免每次写入都触发淘汰
+const EVICT_TARGET_RATIO: f64 = 0.8;
+const JPEG_QUALITY: u8 = 85;
+
+/// 缩略图的返回形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThumbnailOutput {
+    /// 缓存文件的绝对路径，前端通过 convertFileSrc 加载
+    Path,
+    /// base64 编码的 data URL，可直接用作 img 的 src
+    #[default]
+    DataUrl,
+}
+
+/// 缩略图缓存占用情况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThumbnailCacheStats {
+    pub file_count: u64,
+    pub total_bytes: u64,
+    pub max_bytes: u64,
+}
+
+/// 缩略图缓存
+/// 缓存文件位于 <缓存目录>/<源文件路径哈希>/<修改时间>-<大小>-<宽>x<高>.<jpg|png>，
+/// 源文件被修改后键随之变化，旧的缩略图在下次生成时清理；总大小超出上限时按最近访问时间淘汰
+pub struct ThumbnailCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    // 缓存目录当前占用的字节数，首次写入时统计
+    usage: Mutex<Option<u64>>,
+}
+
+impl ThumbnailCache {
+    pub fn new(dir: PathBuf) -> Self {
+        ThumbnailCache {
+            dir,
+            max_bytes: DEFAULT_MAX_CACHE_BYTES,
+            usage: Mutex::new(None),
+        }
+    }
+
+    /// 获取图片的缩略图，缩放到 width × height 以内并保持宽高比，不会放大位图
+    pub fn get(&self, source: &Path, width: u32, height: u32, output: ThumbnailOutput) -> Result<String> {
+        let path = self.get_or_create(source, width, height)?;
+        match output {
+            ThumbnailOutput::Path => Ok(path.to_string_lossy().to_string()),
+            ThumbnailOutput::DataUrl => to_data_url(&path),
+        }
+    }
+
+    /// 返回缓存中的缩略图路径，不存在时生成
+    pub fn get_or_create(&self, source: &Path, width: u32, height: u32) -> Result<PathBuf> {
+        if width == 0 || height == 0 {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid thumbnail size: {}x{}",
+                width, height
+            )));
+        }
+        let width = width.min(MAX_THUMBNAIL_SIZE);
+        let height = height.min(MAX_THUMBNAIL_SIZE);
+
+        let extension = source
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .unwrap_or_default();
+        if !THUMBNAIL_EXTENSIONS.contains(&extension.as_str()) {
+            return Err(AppError::InvalidInput(format!(
+                "Unsupported image format: {}",
+                source.display()
+            )));
+        }
+
+        let metadata = fs::metadata(source).map_err(|_| AppError::PathNotFound(source.display().to_string()))?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_nanos())
+            .unwrap_or(0);
+        let version = format!("{}-{}", modified, metadata.len());
+        let entry_dir = self.dir.join(source_key(source));
+        let stem = format!("{}-{}x{}", version, width, height);
+
+        for ext in ["jpg", "png"] {
+            let cached = entry_dir.join(format!("{}.{}", stem, ext));
+            if cached.is_file() {
+                touch(&cached);
+                return Ok(cached);
+            }
+        }
+
+        let mut thumbnail = load_image(source, width, height)?;
+        if thumbnail.width() > width || thumbnail.height() > height {
+            thumbnail = thumbnail.thumbnail(width, height);
+        }
+
+        fs::create_dir_all(&entry_dir)?;
+        let freed = remove_stale(&entry_dir, &version);
+        let (path, written) = write_thumbnail(&entry_dir, &stem, &thumbnail)?;
+        self.record_usage(written, freed);
+        Ok(path)
+    }
+
+    /// 删除某个源文件的全部缩略图
+    pub fn remove(&self, source: &Path) -> Result<()> {
+        let entry_dir = self.dir.join(source_key(source));
+        if !entry_dir.exists() {
+            return Ok(());
+        }
+        let freed = dir_size(&entry_dir);
+        fs::remove_dir_all(&entry_dir)?;
+        self.record_usage(0, freed);
+        Ok(())
+    }
+
+    /// 删除多个源文件的缩略图，用于记录被删除后清理缓存，失败时只记录日志
+    pub fn remove_sources(&self, sources: &[String]) {
+        for source in sources {
+            if let Err(e) = self.remove(Path::new(source)) {
+                log::warn!("Failed to remove thumbnails of {}: {}", source, e);
+            }
+        }
+    }
+
+    /// 清空缓存，返回释放的字节数
+    pub fn clear(&self) -> Result<u64> {
+        let mut usage = self.usage.lock().unwrap();
+        let freed = dir_size(&self.dir);
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir)?;
+        }
+        *usage = Some(0);
+        Ok(freed)
+    }
+
+    pub fn stats(&self) -> ThumbnailCacheStats {
+        let entries = cache_entries(&self.dir);
+        let total_bytes = entries.iter().map(|(_, size, _)| size).sum();
+        *self.usage.lock().unwrap() = Some(total_bytes);
+
+        ThumbnailCacheStats {
+            file_count: entries.len() as u64,
+            total_bytes,
+            max_bytes: self.max_bytes,
+        }
+    }
+
+    /// 记录写入与释放的字节数，超出上限时淘汰最久未访问的缩略图
+    fn record_usage(&self, written: u64, freed: u64) {
+        let mut usage = self.usage.lock().unwrap();
+        let current = match *usage {
+            Some(current) => (current + written).saturating_sub(freed),
+            // 首次统计时新写入的文件已包含在目录大小中
+            None => dir_size(&self.dir),
+        };
+        *usage = Some(current);
+        if current <= self.max_bytes {
+            return;
+        }
+
+        let target = (self.max_bytes as f64 * EVICT_TARGET_RATIO) as u64;
+        let mut entries = cache_entries(&self.dir);
+        let mut remaining: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        entries.sort_by_key(|(_, _, accessed)| *accessed);
+
+        let mut evicted = 0;
+        for (path, size, _) in entries {
+            if remaining <= target {
+                break;
+            }
+            if fs::remove_file(&path).is_ok() {
+                remaining = remaining.saturating_sub(size);
+                evicted += 1;
+                if let Some(parent) = path.parent() {
+                    // 目录非空时删除失败，忽略即可
+                    let _ = fs::remove_dir(parent);
+                }
+            }
+        }
+        log::info!(
+            "Evicted {} thumbnails, cache size is now {} bytes",
+            evicted,
+            remaining
+        );
+        *usage = Some(remaining);
+    }
+}
+
+/// 以源文件路径的哈希作为缓存子目录名
+fn source_key(source: &Path) -> String {
+    let hash = blake3::hash(source.to_string_lossy().as_bytes());
+    hash.to_hex()[..32].to_string()
+}
+
+/// 解码图片，位图按原始尺寸解码并按 EXIF 方向旋转，矢量图渲染到 width × height 以内
+pub(crate) fn load_image(source: &Path, width: u32, height: u32) -> Result<DynamicImage> {
+    let is_svg = source
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("svg"))
+        .unwrap_or(false);
+    if is_svg {
+        render_svg(source, width, height)
+    } else {
+        decode_raster(source)
+    }
+}
+
+fn decode_raster(source: &Path) -> Result<DynamicImage> {
+    let decode_error = |e: image::ImageError| {
+        AppError::Internal(format!("Failed to decode image {}: {}", source.display(), e))
+    };
+    let mut decoder = ImageReader::open(source)?
+        .with_guessed_format()?
+        .into_decoder()
+        .map_err(decode_error)?;
+    // 照片按 EXIF 方向旋转为显示方向
+    let orientation = decoder.orientation().map_err(decode_error)?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(decode_error)?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+/// 矢量图按目标尺寸直接渲染，可以放大
+fn render_svg(source: &Path, width: u32, height: u32) -> Result<DynamicImage> {
+    use resvg::{tiny_skia, usvg};
+
+    let data = fs::read(source)?;
+    let tree = usvg::Tree::from_data(&data, &usvg::Options::default())
+        .map_err(|e| AppError::Internal(format!("Failed to parse SVG {}: {}", source.display(), e)))?;
+    let size = tree.size();
+    let scale = (width as f32 / size.width()).min(height as f32 / size.height());
+    let pixel_width = ((size.width() * scale).round() as u32).max(1);
+    let pixel_height = ((size.height() * scale).round() as u32).max(1);
+
+    let mut pixmap = tiny_skia::Pixmap::new(pixel_width, pixel_height)
+        .ok_or_else(|| AppError::Internal(format!("Invalid SVG size: {}", source.display())))?;
+    resvg::render(&tree, tiny_skia::Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+
+    // tiny-skia 使用预乘 alpha，需要还原为普通 RGBA
+    let mut image = RgbaImage::new(pixel_width, pixel_height);
+    for (pixel, color) in image.pixels_mut().zip(pixmap.pixels()) {
+        let color = color.demultiply();
+        pixel.0 = [color.red(), color.green(), color.blue(), color.alpha()];
+    }
+    Ok(DynamicImage::ImageRgba8(image))
+}
+
+/// 不透明图片保存为 JPEG，带透明通道的保存为 PNG，返回文件路径与大小
+/// 先写入临时文件再重命名，并发生成同一缩略图时不会读到写了一半的文件
+fn write_thumbnail(entry_dir: &Path, stem: &str, image: &DynamicImage) -> Result<(PathBuf, u64)> {
+    let ext = if image.color().has_alpha() { "png" } else { "jpg" };
+    let path = entry_dir.join(format!("{}.{}", stem, ext));
+    let temp = entry_dir.join(format!("{}.{}.tmp", stem, uuid::Uuid::new_v4()));
+
+    let encoded = (|| -> Result<()> {
+        let mut writer = BufWriter::new(File::create(&temp)?);
+        let encode_error = |e: image::ImageError| AppError::Internal(format!("Failed to encode thumbnail: {}", e));
+        if ext == "png" {
+            image
+                .to_rgba8()
+                .write_to(&mut writer, image::ImageFormat::Png)
+                .map_err(encode_error)?;
+        } else {
+            JpegEncoder::new_with_quality(&mut writer, JPEG_QUALITY)
+                .encode_image(&image.to_rgb8())
+                .map_err(encode_error)?;
+        }
+        writer.into_inner().map_err(|e| e.into_error())?;
+        Ok(())
+    })();
+    if let Err(e) = encoded.and_then(|_| fs::rename(&temp, &path).map_err(AppError::from)) {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+
+    let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    Ok((path, size))
+}
+
+/// 删除源文件旧版本的缩略图，返回释放的字节数
+fn remove_stale(entry_dir: &Path, version: &str) -> u64 {
+    let prefix = format!("{}-", version);
+    let mut freed = 0;
+    for (path, size, _) in cache_entries(entry_dir) {
+        let current = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.starts_with(&prefix))
+            .unwrap_or(false);
+        if !current && fs::remove_file(&path).is_ok() {
+            freed += size;
+        }
+    }
+    freed
+}
+
+/// 命中缓存时刷新修改时间，作为淘汰时的最近访问时间
+fn touch(path: &Path) {
+    if let Ok(file) = File::options().write(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+fn to_data_url(path: &Path) -> Result<String> {
+    let mime = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("png") => "image/png",
+        _ => "image/jpeg",
+    };
+    let data = fs::read(path)?;
+    Ok(format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(data)
+    ))
+}
+
+/// 递归列出目录下的缓存文件及其大小、修改时间
+fn cache_entries(dir: &Path) -> Vec<(PathBuf, u64, SystemTime)> {
+    let mut entries = Vec::new();
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return entries;
+    };
+    for entry in read_dir.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            entries.extend(cache_entries(&entry.path()));
+        } else {
+            let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+            entries.push((entry.path(), metadata.len(), modified));
+        }
+    }
+    entries
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    cache_entries(dir).iter().map(|(_, size, _)| size).sum()
+}
@@ -0,0 +1,84 @@
+use crate::error::{AppError, Result};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+/// app_settings 中存放密钥派生盐值的键
+pub const ENCRYPTION_SALT_KEY: &str = "encryption_salt";
+
+/// 密文前缀，用于区分已加密字段与历史遗留的明文字段
+const SEALED_PREFIX: &str = "enc:v1:";
+const NONCE_LEN: usize = 12;
+
+/// 由全局密码派生出的 AES-256-GCM 密钥，只保存在内存中
+#[derive(Clone)]
+pub struct VaultKey([u8; 32]);
+
+impl VaultKey {
+    /// 使用 Argon2id 从密码和盐值派生密钥
+    pub fn derive(password: &str, salt: &[u8]) -> Result<Self> {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(password.as_bytes(), salt, &mut key)
+            .map_err(|e| AppError::Internal(format!("Failed to derive encryption key: {}", e)))?;
+        Ok(VaultKey(key))
+    }
+
+    /// 加密字符串，输出为带前缀的 base64(nonce || ciphertext)
+    pub fn seal(&self, plaintext: &str) -> Result<String> {
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.0));
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| AppError::Internal("Failed to encrypt value".to_string()))?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", SEALED_PREFIX, BASE64.encode(payload)))
+    }
+
+    /// 解密 seal 生成的字符串，未加密的旧数据原样返回
+    pub fn open(&self, value: &str) -> Result<String> {
+        let encoded = match value.strip_prefix(SEALED_PREFIX) {
+            Some(encoded) => encoded,
+            None => return Ok(value.to_string()),
+        };
+
+        let payload = BASE64
+            .decode(encoded)
+            .map_err(|e| AppError::Internal(format!("Malformed encrypted value: {}", e)))?;
+        if payload.len() < NONCE_LEN {
+            return Err(AppError::Internal("Malformed encrypted value".to_string()));
+        }
+
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.0));
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| AppError::Internal("Failed to decrypt value".to_string()))?;
+
+        String::from_utf8(plaintext).map_err(|e| AppError::Internal(e.to_string()))
+    }
+}
+
+/// 字段是否已经加密
+pub fn is_sealed(value: &str) -> bool {
+    value.starts_with(SEALED_PREFIX)
+}
+
+/// 生成新的密钥派生盐值（base64 编码后存储）
+pub fn generate_salt() -> String {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    BASE64.encode(salt)
+}
+
+/// 解码存储的盐值
+pub fn decode_salt(salt: &str) -> Result<Vec<u8>> {
+    BASE64
+        .decode(salt)
+        .map_err(|e| AppError::Internal(format!("Malformed encryption salt: {}", e)))
+}
@@ -1,10 +1,22 @@
 use crate::db::Database;
+use crate::db::models::{FileKind, SortDirection};
 use crate::error::AppError;
+use crate::services::media_probe;
+use crate::services::resource::escape_like;
+use crate::services::thumbnail::{ThumbnailCache, ThumbnailOutput};
+use crate::services::wallpaper_compose::{compose_span, MonitorLayer};
+use crate::services::wallpaper_dedup::{
+    hamming_distance, is_distinctive, load_hashed_wallpapers, perceptual_hash, pixels, similarity_of, NearDuplicateAction,
+    WallpaperDedupService,
+};
+use crate::services::wallpaper_source::WallpaperSourceRegistry;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use tauri::{AppHandle, Manager, Runtime};
 use uuid::Uuid;
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension};
 
 /**
  * 壁纸服务结构体
@@ -40,6 +52,22 @@ pub struct LocalWallpaper {
     pub height: Option<i32>,
     pub modified_at: String,
     pub created_at: String,
+    /// 由壁纸文件夹同步导入时为所属文件夹ID
+    #[serde(default)]
+    pub folder_id: Option<i64>,
+    /// 文件已被移动或删除
+    #[serde(default)]
+    pub missing: bool,
+    #[serde(default)]
+    pub favorite: bool,
+    /// 评分 1-5，0 表示未评分
+    #[serde(default)]
+    pub rating: i32,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// dHash 感知哈希，仅供后端去重使用
+    #[serde(skip)]
+    pub perceptual_hash: Option<u64>,
 }
 
 /**
@@ -97,13 +125,19 @@ pub struct SetWallpaperResult {
 
 /**
  * 显示器信息
+ * @description 位置与尺寸均为物理像素，位置相对于主显示器左上角
  */
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct MonitorInfo {
+    /// 系统提供的显示器名称（如 Windows 的 \\.\DISPLAY1、Linux 的 DP-1），重启后保持不变
     pub id: String,
     pub name: String,
+    pub x: i32,
+    pub y: i32,
     pub width: i32,
     pub height: i32,
+    pub scale_factor: f64,
+    pub is_primary: bool,
 }
 
 /**
@@ -111,73 +145,288 @@ pub struct MonitorInfo {
  */
 #[derive(Serialize, Deserialize)]
 pub struct CurrentWallpaperInfo {
+    /// 设置到所有显示器的壁纸；按显示器分别设置时为拼接后的图片
     pub file_path: String,
     pub fit_mode: String,
+    /// 按显示器单独设置的壁纸
+    pub monitors: Vec<MonitorWallpaper>,
+}
+
+/**
+ * 单台显示器的壁纸设置
+ */
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MonitorWallpaper {
+    pub monitor_id: String,
+    pub file_path: String,
+    pub fit_mode: WallpaperFitMode,
+}
+
+/**
+ * 壁纸适配模式
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WallpaperFitMode {
+    /// 等比缩放铺满屏幕，超出部分裁掉
+    #[default]
+    Fill,
+    /// 等比缩放完整显示，空白处留黑边
+    Fit,
+    Stretch,
+    Center,
+    Tile,
+    /// 一张图片横跨所有显示器
+    Span,
+}
+
+impl WallpaperFitMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WallpaperFitMode::Fill => "fill",
+            WallpaperFitMode::Fit => "fit",
+            WallpaperFitMode::Stretch => "stretch",
+            WallpaperFitMode::Center => "center",
+            WallpaperFitMode::Tile => "tile",
+            WallpaperFitMode::Span => "span",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, AppError> {
+        match value {
+            "fill" => Ok(WallpaperFitMode::Fill),
+            "fit" => Ok(WallpaperFitMode::Fit),
+            "stretch" => Ok(WallpaperFitMode::Stretch),
+            "center" => Ok(WallpaperFitMode::Center),
+            "tile" => Ok(WallpaperFitMode::Tile),
+            "span" => Ok(WallpaperFitMode::Span),
+            _ => Err(AppError::InvalidInput(format!("不支持的适配模式: {}", value))),
+        }
+    }
+
+    fn to_system_mode(self) -> wallpaper::Mode {
+        match self {
+            WallpaperFitMode::Fill => wallpaper::Mode::Crop,
+            WallpaperFitMode::Fit => wallpaper::Mode::Fit,
+            WallpaperFitMode::Stretch => wallpaper::Mode::Stretch,
+            WallpaperFitMode::Center => wallpaper::Mode::Center,
+            WallpaperFitMode::Tile => wallpaper::Mode::Tile,
+            WallpaperFitMode::Span => wallpaper::Mode::Span,
+        }
+    }
+}
+
+/**
+ * 本地壁纸筛选条件
+ * @description 宽高未知的壁纸不会匹配任何尺寸条件
+ */
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct WallpaperFilter {
+    pub min_width: Option<i32>,
+    pub min_height: Option<i32>,
+    /// 宽高比（宽/高），如 16:9 约为 1.778
+    pub aspect_ratio: Option<f64>,
+    /// 宽高比允许的相对误差，默认 0.02
+    pub aspect_tolerance: Option<f64>,
+    /// 只返回适配该分辨率的壁纸：宽高均不小于该分辨率且宽高比一致，如 3440x1440 的显示器
+    pub fits_width: Option<i32>,
+    pub fits_height: Option<i32>,
+    /// 只返回该来源文件夹中的壁纸
+    pub folder_id: Option<i64>,
+    /// 按文件是否已丢失筛选，为空时不限
+    pub missing: Option<bool>,
+    /// 名称或文件名中包含的关键字
+    pub keyword: Option<String>,
+    /// 同时带有这些标签的壁纸
+    #[serde(default)]
+    pub tag_ids: Vec<i64>,
+    /// 只返回该合集中的壁纸
+    pub collection_id: Option<i64>,
+    pub favorite: Option<bool>,
+    /// 评分不低于该值的壁纸
+    pub min_rating: Option<i32>,
+}
+
+/**
+ * 本地壁纸排序字段
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WallpaperSortField {
+    /// 导入时间
+    #[default]
+    CreatedAt,
+    Name,
+    FileSize,
+    /// 像素数（宽 × 高）
+    Resolution,
+    Rating,
+    ModifiedAt,
+}
+
+/**
+ * 本地壁纸排序方式
+ * @description 未指定方向时按名称升序，其余字段降序
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+#[serde(default)]
+pub struct WallpaperSort {
+    pub field: WallpaperSortField,
+    pub direction: Option<SortDirection>,
+}
+
+/**
+ * 本地壁纸分页响应
+ */
+#[derive(Serialize, Deserialize)]
+pub struct LocalWallpaperPage {
+    pub wallpapers: Vec<LocalWallpaper>,
+    /// 符合筛选条件的总数
+    pub total: i64,
+    pub page: u32,
+    pub page_size: u32,
+    pub has_more: bool,
+}
+
+/**
+ * 壁纸导入结果
+ */
+#[derive(Serialize, Deserialize)]
+pub struct WallpaperImportResult {
+    pub imported: Vec<LocalWallpaper>,
+    /// 与已有壁纸近似重复而被拒绝或合并的图片
+    pub duplicates: Vec<WallpaperImportDuplicate>,
+}
+
+/**
+ * 导入时发现的近似重复图片
+ */
+#[derive(Serialize, Deserialize)]
+pub struct WallpaperImportDuplicate {
+    pub file_path: String,
+    /// 已有的壁纸，合并时为合并后的记录
+    pub existing: LocalWallpaper,
+    pub similarity: f64,
+    /// refuse 表示未导入；merge 表示已合并到已有壁纸，新图片分辨率更高时已有壁纸改为指向新图片
+    pub action: NearDuplicateAction,
+}
+
+/**
+ * 壁纸宽高补全结果
+ */
+#[derive(Serialize, Deserialize)]
+pub struct WallpaperBackfillResult {
+    pub updated: i32,
+    /// 文件已不存在的记录数
+    pub missing: i32,
+    /// 无法解析宽高的记录数
+    pub failed: i32,
 }
 
 impl WallpaperService {
     /**
-     * 获取所有本地壁纸
+     * 分页获取本地壁纸
+     * @param filter 筛选条件
+     * @param sort 排序方式
+     * @param page 页码，从1开始
+     * @param page_size 每页数量
      */
-    pub fn get_local_wallpapers(&self) -> Result<String, AppError> {
+    pub fn get_local_wallpapers(
+        &self,
+        filter: &WallpaperFilter,
+        sort: WallpaperSort,
+        page: Option<u32>,
+        page_size: Option<u32>,
+    ) -> Result<LocalWallpaperPage, AppError> {
+        let page = page.unwrap_or(1).max(1);
+        let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+        let (where_clause, values) = build_filter_clause(filter);
+        let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+
+        let (sort_expr, default_direction) = match sort.field {
+            WallpaperSortField::CreatedAt => ("created_at", SortDirection::Desc),
+            WallpaperSortField::Name => ("name COLLATE NOCASE", SortDirection::Asc),
+            WallpaperSortField::FileSize => ("file_size", SortDirection::Desc),
+            WallpaperSortField::Resolution => ("COALESCE(width, 0) * COALESCE(height, 0)", SortDirection::Desc),
+            WallpaperSortField::Rating => ("rating", SortDirection::Desc),
+            WallpaperSortField::ModifiedAt => ("modified_at", SortDirection::Desc),
+        };
+        let order = match sort.direction.unwrap_or(default_direction) {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        };
+
         let conn = self.db.get_connection();
         let conn = conn.lock().unwrap();
-        
-        // 先检查数据库中已有多少条记录
-        let mut count_stmt = conn.prepare("SELECT COUNT(*) FROM wallpapers")?;
-        let count: i64 = count_stmt.query_row([], |row| row.get(0))?;
-        log::info!("[Rust] 数据库中 wallpapers 表已有记录数: {}", count);
-        
-        let mut stmt = conn.prepare(
-            "SELECT id, name, file_path, file_name, file_type, file_size, width, height, modified_at, created_at 
-             FROM wallpapers 
-             ORDER BY created_at DESC"
+
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM wallpapers{}", where_clause),
+            params.as_slice(),
+            |row| row.get(0),
         )?;
-        
-        let wallpaper_iter = stmt.query_map([], |row| {
-            Ok(LocalWallpaper {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                file_path: row.get(2)?,
-                file_name: row.get(3)?,
-                file_type: row.get(4)?,
-                file_size: row.get(5)?,
-                width: row.get(6)?,
-                height: row.get(7)?,
-                modified_at: row.get(8)?,
-                created_at: row.get(9)?,
-            })
-        })?;
-        
-        let mut wallpapers: Vec<LocalWallpaper> = vec![];
-        for wallpaper in wallpaper_iter {
-            wallpapers.push(wallpaper?);
-        }
-        
-        log::info!("[Rust] get_local_wallpapers 返回记录数: {}", wallpapers.len());
-        serde_json::to_string(&wallpapers).map_err(|e| AppError::Internal(e.to_string()))
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, name, file_path, file_name, file_type, file_size, width, height, modified_at, created_at, folder_id, missing, favorite, rating
+             FROM wallpapers{}
+             ORDER BY {} {}, id {}
+             LIMIT {} OFFSET {}",
+            where_clause,
+            sort_expr,
+            order,
+            order,
+            page_size,
+            (page as i64 - 1) * page_size as i64
+        ))?;
+        let mut wallpapers = stmt
+            .query_map(params.as_slice(), |row| {
+                Ok(LocalWallpaper {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    file_path: row.get(2)?,
+                    file_name: row.get(3)?,
+                    file_type: row.get(4)?,
+                    file_size: row.get(5)?,
+                    width: row.get(6)?,
+                    height: row.get(7)?,
+                    modified_at: row.get(8)?,
+                    created_at: row.get(9)?,
+                    folder_id: row.get(10)?,
+                    missing: row.get(11)?,
+                    favorite: row.get(12)?,
+                    rating: row.get(13)?,
+                    tags: vec![],
+                    perceptual_hash: None,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        // 只为当前页的壁纸查询标签
+        attach_tags(&conn, wallpapers.iter_mut().collect())?;
+
+        log::info!("[Rust] get_local_wallpapers 返回记录数: {} / {}", wallpapers.len(), total);
+        Ok(LocalWallpaperPage {
+            has_more: (page as i64) * (page_size as i64) < total,
+            wallpapers,
+            total,
+            page,
+            page_size,
+        })
     }
 
     /**
      * 导入壁纸文件
      */
-    pub fn import_wallpapers(&self, file_paths: Vec<String>) -> Result<String, AppError> {
+    pub fn import_wallpapers(&self, file_paths: Vec<String>) -> Result<WallpaperImportResult, AppError> {
         log::info!("[Rust] 开始导入，收到文件数量: {}", file_paths.len());
         log::info!("[Rust] 收到的文件路径: {:?}", file_paths);
-        
-        let conn = self.db.get_connection();
-        let conn = conn.lock().unwrap();
-        let mut imported: Vec<LocalWallpaper> = vec![];
-        
-        for (index, path) in file_paths.iter().enumerate() {
-            log::info!("[Rust] 处理第 {} 个文件: {}", index + 1, path);
-            
-            let path = Path::new(&path);
+
+        let mut paths = vec![];
+        for path in file_paths {
+            let path = PathBuf::from(path);
             if !path.exists() || !path.is_file() {
                 log::info!("[Rust] 文件不存在或不是文件，跳过: {}", path.display());
                 continue;
             }
-
             if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
                 let image_exts = ["png", "jpg", "jpeg", "gif", "bmp", "webp", "svg"];
                 if !image_exts.iter().any(|e| e.eq_ignore_ascii_case(ext)) {
@@ -185,73 +434,18 @@ impl WallpaperService {
                     continue;
                 }
             }
-
-            let file_name = path.file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("未知")
-                .to_string();
-            
-            let path_str = path.to_string_lossy().to_string();
-            
-            log::info!("[Rust] 检查文件是否已存在: {}", path_str);
-            
-            let mut check_stmt = conn.prepare("SELECT COUNT(*) FROM wallpapers WHERE file_path = ?1")?;
-            let count: i64 = check_stmt.query_row([&path_str], |row| row.get(0))?;
-            
-            if count > 0 {
-                log::info!("[Rust] 文件已存在于数据库中，跳过: {}", path_str);
-                continue;
-            }
-            
-            let id = Uuid::new_v4().to_string();
-            let now = chrono::Utc::now().to_rfc3339();
-            
-            let wallpaper = LocalWallpaper {
-                id: id.clone(),
-                name: file_name.clone(),
-                file_path: path_str.clone(),
-                file_name: file_name.clone(),
-                file_type: path.extension()
-                    .and_then(|e| e.to_str())
-                    .unwrap_or("unknown")
-                    .to_string(),
-                file_size: path.metadata()
-                    .map(|m| m.len() as i64)
-                    .unwrap_or(0),
-                width: None,
-                height: None,
-                modified_at: now.clone(),
-                created_at: now.clone(),
-            };
-
-            conn.execute(
-                "INSERT INTO wallpapers (id, name, file_path, file_name, file_type, file_size, width, height, modified_at, created_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-                params![
-                    wallpaper.id,
-                    wallpaper.name,
-                    wallpaper.file_path,
-                    wallpaper.file_name,
-                    wallpaper.file_type,
-                    wallpaper.file_size,
-                    wallpaper.width,
-                    wallpaper.height,
-                    wallpaper.modified_at,
-                    wallpaper.created_at
-                ],
-            )?;
-
-            imported.push(wallpaper);
+            paths.push(path);
         }
 
-        log::info!("[Rust] 导入完成，共处理: {} 个文件", imported.len());
-        serde_json::to_string(&imported).map_err(|e| AppError::Internal(e.to_string()))
+        let result = self.import_files(paths)?;
+        log::info!("[Rust] 导入完成，共处理: {} 个文件", result.imported.len());
+        Ok(result)
     }
 
     /**
      * 从目录导入壁纸
      */
-    pub fn import_wallpapers_from_directory(&self, directory_path: String) -> Result<String, AppError> {
+    pub fn import_wallpapers_from_directory(&self, directory_path: String) -> Result<WallpaperImportResult, AppError> {
         log::info!("[Rust] 开始从目录导入，目录路径: {}", directory_path);
         
         let dir_path = Path::new(&directory_path);
@@ -260,14 +454,11 @@ impl WallpaperService {
             return Err(AppError::Internal(format!("目录不存在: {}", directory_path)));
         }
 
-        let conn = self.db.get_connection();
-        let conn = conn.lock().unwrap();
-        let mut imported: Vec<LocalWallpaper> = vec![];
         let image_exts = ["png", "jpg", "jpeg", "gif", "bmp", "webp", "svg"];
-
         let entries = std::fs::read_dir(dir_path)
             .map_err(|e| AppError::Internal(format!("读取目录失败: {}", e)))?;
 
+        let mut paths = vec![];
         for entry in entries {
             let entry = entry.map_err(|e| AppError::Internal(format!("读取文件失败: {}", e)))?;
             let path = entry.path();
@@ -285,48 +476,145 @@ impl WallpaperService {
                 log::info!("[Rust] 跳过无扩展名的文件: {}", path.display());
                 continue;
             }
+            paths.push(path);
+        }
+
+        let result = self.import_files(paths)?;
+        log::info!("[Rust] 目录导入完成，共导入: {} 张壁纸", result.imported.len());
+        Ok(result)
+    }
+
+    /**
+     * 导入已筛选过扩展名的图片文件
+     * @description 路径已导入的文件直接跳过；按去重设置拒绝或合并与已有壁纸近似重复的图片，同一批次内的图片之间同样会比较。
+     * 文件解析与哈希计算在数据库锁之外完成
+     */
+    fn import_files(&self, paths: Vec<PathBuf>) -> Result<WallpaperImportResult, AppError> {
+        let dedup = WallpaperDedupService::new(self.db.clone());
+        let settings = dedup.settings()?;
+        if settings.action != NearDuplicateAction::Allow {
+            dedup.fill_missing_hashes()?;
+        }
+
+        let paths: Vec<PathBuf> = {
+            let conn = self.db.get_connection();
+            let conn = conn.lock().unwrap();
+            let mut check_stmt = conn.prepare("SELECT 1 FROM wallpapers WHERE file_path = ?1")?;
+            let mut seen = HashSet::new();
+            let mut pending = vec![];
+            for path in paths {
+                // 同一路径在本批次中出现多次时只导入一次
+                if !seen.insert(path.clone()) {
+                    continue;
+                }
+                if check_stmt.exists([path.to_string_lossy()])? {
+                    log::info!("[Rust] 文件已存在于数据库中，跳过: {}", path.display());
+                } else {
+                    pending.push(path);
+                }
+            }
+            pending
+        };
+
+        let wallpapers: Vec<LocalWallpaper> = paths
+            .iter()
+            .map(|path| {
+                let file_name = path.file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("未知")
+                    .to_string();
+                read_wallpaper_file(path, &file_name, &path.to_string_lossy())
+            })
+            .collect();
+
+        let conn = self.db.get_connection();
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut known = if settings.action == NearDuplicateAction::Allow {
+            vec![]
+        } else {
+            load_hashed_wallpapers(&tx)?
+        };
+        let mut result = WallpaperImportResult {
+            imported: vec![],
+            duplicates: vec![],
+        };
+
+        for wallpaper in wallpapers {
+            // 解析文件期间可能已由文件夹同步导入，或被前面合并的近似重复项占用
+            if tx
+                .prepare_cached("SELECT 1 FROM wallpapers WHERE file_path = ?1")?
+                .exists([&wallpaper.file_path])?
+            {
+                continue;
+            }
 
-            let file_name = path.file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("未知")
-                .to_string();
-            
-            let path_str = path.to_string_lossy().to_string();
-            
-            log::info!("[Rust] 检查文件是否已存在: {}", path_str);
-            
-            let mut check_stmt = conn.prepare("SELECT COUNT(*) FROM wallpapers WHERE file_path = ?1")?;
-            let count: i64 = check_stmt.query_row([&path_str], |row| row.get(0))?;
-            
-            if count > 0 {
-                log::info!("[Rust] 文件已存在于数据库中，跳过: {}", path_str);
+            let matched = wallpaper
+                .perceptual_hash
+                .filter(|hash| is_distinctive(*hash, settings.max_distance()))
+                .and_then(|hash| {
+                    known
+                        .iter()
+                        .enumerate()
+                        .map(|(index, (_, existing))| (index, hamming_distance(hash, *existing)))
+                        .filter(|(_, distance)| *distance <= settings.max_distance())
+                        .min_by_key(|(_, distance)| *distance)
+                });
+            if let Some((index, distance)) = matched {
+                let existing = &mut known[index].0;
+                // 文件夹同步的壁纸与文件夹中的文件一一对应，不改变其指向
+                if settings.action == NearDuplicateAction::Merge
+                    && existing.folder_id.is_none()
+                    && pixels(&wallpaper) > pixels(existing)
+                {
+                    tx.execute(
+                        "UPDATE wallpapers SET name = ?1, file_path = ?2, file_name = ?3, file_type = ?4, file_size = ?5,
+                         width = ?6, height = ?7, modified_at = ?8, perceptual_hash = ?9, hash_failed = ?9 IS NULL, missing = 0
+                         WHERE id = ?10",
+                        params![
+                            wallpaper.name,
+                            wallpaper.file_path,
+                            wallpaper.file_name,
+                            wallpaper.file_type,
+                            wallpaper.file_size,
+                            wallpaper.width,
+                            wallpaper.height,
+                            wallpaper.modified_at,
+                            wallpaper.perceptual_hash.map(|h| h as i64),
+                            existing.id
+                        ],
+                    )?;
+                    // 只替换文件相关字段，收藏、评分、标签与导入时间保持不变
+                    existing.name = wallpaper.name.clone();
+                    existing.file_path = wallpaper.file_path.clone();
+                    existing.file_name = wallpaper.file_name.clone();
+                    existing.file_type = wallpaper.file_type.clone();
+                    existing.file_size = wallpaper.file_size;
+                    existing.width = wallpaper.width;
+                    existing.height = wallpaper.height;
+                    existing.modified_at = wallpaper.modified_at.clone();
+                    existing.perceptual_hash = wallpaper.perceptual_hash;
+                    existing.missing = false;
+                    known[index].1 = wallpaper.perceptual_hash.unwrap_or_default();
+                }
+                log::info!(
+                    "[Rust] 与已有壁纸近似重复（{:?}）: {} -> {}",
+                    settings.action,
+                    wallpaper.file_path,
+                    known[index].0.file_path
+                );
+                result.duplicates.push(WallpaperImportDuplicate {
+                    file_path: wallpaper.file_path,
+                    existing: known[index].0.clone(),
+                    similarity: similarity_of(distance),
+                    action: settings.action,
+                });
                 continue;
             }
-            
-            let id = Uuid::new_v4().to_string();
-            let now = chrono::Utc::now().to_rfc3339();
-            
-            let wallpaper = LocalWallpaper {
-                id: id.clone(),
-                name: file_name.clone(),
-                file_path: path_str.clone(),
-                file_name: file_name.clone(),
-                file_type: path.extension()
-                    .and_then(|e| e.to_str())
-                    .unwrap_or("unknown")
-                    .to_string(),
-                file_size: path.metadata()
-                    .map(|m| m.len() as i64)
-                    .unwrap_or(0),
-                width: None,
-                height: None,
-                modified_at: now.clone(),
-                created_at: now.clone(),
-            };
-
-            conn.execute(
-                "INSERT INTO wallpapers (id, name, file_path, file_name, file_type, file_size, width, height, modified_at, created_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+
+            tx.execute(
+                "INSERT INTO wallpapers (id, name, file_path, file_name, file_type, file_size, width, height, modified_at, created_at, perceptual_hash, hash_failed)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?11 IS NULL)",
                 params![
                     wallpaper.id,
                     wallpaper.name,
@@ -337,47 +625,123 @@ impl WallpaperService {
                     wallpaper.width,
                     wallpaper.height,
                     wallpaper.modified_at,
-                    wallpaper.created_at
+                    wallpaper.created_at,
+                    wallpaper.perceptual_hash.map(|h| h as i64)
                 ],
             )?;
-
-            imported.push(wallpaper);
+            if let (Some(hash), true) = (wallpaper.perceptual_hash, settings.action != NearDuplicateAction::Allow) {
+                known.push((wallpaper.clone(), hash));
+            }
+            result.imported.push(wallpaper);
         }
-
-        log::info!("[Rust] 目录导入完成，共导入: {} 张壁纸", imported.len());
-        serde_json::to_string(&imported).map_err(|e| AppError::Internal(e.to_string()))
+        // 前端会用重复项中的已有壁纸替换列表中的记录，需带上标签
+        attach_tags(&tx, result.duplicates.iter_mut().map(|d| &mut d.existing).collect())?;
+        tx.commit()?;
+        Ok(result)
     }
 
     /**
      * 删除本地壁纸
      */
-    pub fn delete_local_wallpaper(&self, wallpaper_id: String) -> Result<(), AppError> {
-        let conn = self.db.get_connection();
-        let conn = conn.lock().unwrap();
-        
-        conn.execute(
-            "DELETE FROM wallpapers WHERE id = ?1",
-            params![wallpaper_id],
-        )?;
-        
-        Ok(())
+    pub fn delete_local_wallpaper(&self, thumbnails: &ThumbnailCache, wallpaper_id: String) -> Result<(), AppError> {
+        self.delete_local_wallpapers(thumbnails, vec![wallpaper_id])
     }
 
     /**
      * 批量删除本地壁纸
      */
-    pub fn delete_local_wallpapers(&self, wallpaper_ids: Vec<String>) -> Result<(), AppError> {
+    pub fn delete_local_wallpapers(&self, thumbnails: &ThumbnailCache, wallpaper_ids: Vec<String>) -> Result<(), AppError> {
+        let mut removed_paths = vec![];
+        {
+            let conn = self.db.get_connection();
+            let conn = conn.lock().unwrap();
+
+            for id in wallpaper_ids {
+                let file_path: Option<String> = conn
+                    .query_row("SELECT file_path FROM wallpapers WHERE id = ?1", params![id], |row| row.get(0))
+                    .optional()?;
+                conn.execute(
+                    "DELETE FROM wallpapers WHERE id = ?1",
+                    params![id],
+                )?;
+                removed_paths.extend(file_path);
+            }
+        }
+
+        // 记录删除后其缩略图不会再被访问，直接清理
+        thumbnails.remove_sources(&removed_paths);
+        Ok(())
+    }
+
+    /**
+     * 补全已导入壁纸的宽高
+     * @description 早期导入的壁纸没有记录宽高，修改时间也是导入时间，此处一并按文件实际信息更新
+     */
+    pub fn backfill_wallpaper_dimensions(&self) -> Result<String, AppError> {
+        let rows: Vec<(String, String)> = {
+            let conn = self.db.get_connection();
+            let conn = conn.lock().unwrap();
+            let mut stmt = conn.prepare(
+                "SELECT id, file_path FROM wallpapers WHERE width IS NULL OR height IS NULL",
+            )?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            rows
+        };
+        log::info!("[Rust] 待补全宽高的壁纸数: {}", rows.len());
+
+        // 在数据库锁之外读取文件
+        let mut result = WallpaperBackfillResult {
+            updated: 0,
+            missing: 0,
+            failed: 0,
+        };
+        let mut updates = vec![];
+        for (id, file_path) in rows {
+            let path = Path::new(&file_path);
+            if !path.is_file() {
+                result.missing += 1;
+                continue;
+            }
+            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("未知");
+            let wallpaper = read_wallpaper_file(path, file_name, &file_path);
+            if wallpaper.width.is_none() || wallpaper.height.is_none() {
+                log::info!("[Rust] 无法解析图片宽高: {}", file_path);
+                result.failed += 1;
+                continue;
+            }
+            updates.push((id, wallpaper));
+        }
+
         let conn = self.db.get_connection();
-        let conn = conn.lock().unwrap();
-        
-        for id in wallpaper_ids {
-            conn.execute(
-                "DELETE FROM wallpapers WHERE id = ?1",
-                params![id],
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "UPDATE wallpapers SET width = ?1, height = ?2, file_size = ?3, modified_at = ?4, perceptual_hash = ?5,
+                 hash_failed = ?5 IS NULL WHERE id = ?6",
             )?;
+            for (id, wallpaper) in &updates {
+                result.updated += stmt.execute(params![
+                    wallpaper.width,
+                    wallpaper.height,
+                    wallpaper.file_size,
+                    wallpaper.modified_at,
+                    wallpaper.perceptual_hash.map(|h| h as i64),
+                    id
+                ])? as i32;
+            }
         }
-        
-        Ok(())
+        tx.commit()?;
+
+        log::info!(
+            "[Rust] 壁纸宽高补全完成，更新: {}，文件缺失: {}，解析失败: {}",
+            result.updated,
+            result.missing,
+            result.failed
+        );
+        serde_json::to_string(&result).map_err(|e| AppError::Internal(e.to_string()))
     }
 
     /**
@@ -385,117 +749,71 @@ impl WallpaperService {
      */
     pub fn get_wallpaper_thumbnail(
         &self,
-        _wallpaper_id: String,
-        _width: u32,
-        _height: u32,
+        thumbnails: &ThumbnailCache,
+        wallpaper_id: String,
+        width: u32,
+        height: u32,
+        output: ThumbnailOutput,
     ) -> Result<String, AppError> {
-        Ok(String::new())
+        let file_path: String = {
+            let conn = self.db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.query_row(
+                "SELECT file_path FROM wallpapers WHERE id = ?1",
+                params![wallpaper_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::Internal(format!("未找到壁纸: {}", e)))?
+        };
+
+        thumbnails.get(Path::new(&file_path), width, height, output)
     }
 
     /**
      * 获取壁纸站分类列表
+     * @param sources 壁纸源注册表，使用当前选中的壁纸源，未配置时返回空列表
      */
-    pub fn get_wallpaper_categories(&self) -> Result<String, AppError> {
-        let categories = vec![
-            WallpaperCategory {
-                id: "all".to_string(),
-                name: "全部".to_string(),
-                icon: None,
-                color: None,
-                count: None,
-            },
-            WallpaperCategory {
-                id: "hot".to_string(),
-                name: "热门".to_string(),
-                icon: None,
-                color: None,
-                count: Some(100),
-            },
-            WallpaperCategory {
-                id: "new".to_string(),
-                name: "最新".to_string(),
-                icon: None,
-                color: None,
-                count: Some(50),
-            },
-            WallpaperCategory {
-                id: "scenery".to_string(),
-                name: "风景".to_string(),
-                icon: None,
-                color: None,
-                count: Some(200),
-            },
-            WallpaperCategory {
-                id: "anime".to_string(),
-                name: "动漫".to_string(),
-                icon: None,
-                color: None,
-                count: Some(150),
-            },
-            WallpaperCategory {
-                id: "abstract".to_string(),
-                name: "抽象".to_string(),
-                icon: None,
-                color: None,
-                count: Some(80),
-            },
-        ];
+    pub fn get_wallpaper_categories(&self, sources: &WallpaperSourceRegistry) -> Result<String, AppError> {
+        let categories = match sources.active() {
+            Some(source) => source.list_categories()?,
+            None => vec![],
+        };
         serde_json::to_string(&categories).map_err(|e| AppError::Internal(e.to_string()))
     }
 
     /**
      * 获取壁纸站壁纸列表
+     * @param sources 壁纸源注册表
+     * @param category_id 分类ID，为空或 "all" 时不按分类筛选
      */
     pub fn get_wallpaper_station_wallpapers(
         &self,
-        _category_id: Option<String>,
+        sources: &WallpaperSourceRegistry,
+        category_id: Option<String>,
         page: u32,
         page_size: u32,
     ) -> Result<String, AppError> {
-        let response = WallpaperStationResponse {
-            wallpapers: vec![],
-            total: 0,
-            page,
-            page_size,
-            has_more: false,
+        let response = match sources.active() {
+            Some(source) => source.browse(category_id.as_deref(), page, page_size)?,
+            None => empty_station_response(page, page_size),
         };
         serde_json::to_string(&response).map_err(|e| AppError::Internal(e.to_string()))
     }
 
-    /**
-     * 下载壁纸到本地
-     */
-    pub fn download_wallpaper(&self, wallpaper_id: String) -> Result<String, AppError> {
-        let wallpaper = LocalWallpaper {
-            id: wallpaper_id.clone(),
-            name: "下载的壁纸".to_string(),
-            file_path: String::new(),
-            file_name: "wallpaper.png".to_string(),
-            file_type: "png".to_string(),
-            file_size: 0,
-            width: None,
-            height: None,
-            modified_at: chrono::Utc::now().to_rfc3339(),
-            created_at: chrono::Utc::now().to_rfc3339(),
-        };
-        serde_json::to_string(&wallpaper).map_err(|e| AppError::Internal(e.to_string()))
-    }
-
     /**
      * 搜索壁纸
+     * @param sources 壁纸源注册表
      */
     pub fn search_wallpapers(
         &self,
-        _keyword: String,
+        sources: &WallpaperSourceRegistry,
+        keyword: String,
         page: u32,
         page_size: u32,
     ) -> Result<String, AppError> {
-        let response = WallpaperStationResponse {
-            wallpapers: vec![],
-            total: 0,
-            page,
-            page_size,
-            has_more: false,
+        let response = match sources.active() {
+            Some(source) => source.search(&keyword, page, page_size)?,
+            None => empty_station_response(page, page_size),
         };
         serde_json::to_string(&response).map_err(|e| AppError::Internal(e.to_string()))
     }
@@ -503,71 +821,515 @@ impl WallpaperService {
     /**
      * 设置系统壁纸
      */
-    pub fn set_wallpaper(
+    pub fn set_wallpaper<R: Runtime>(
         &self,
+        app: &AppHandle<R>,
         wallpaper_id: String,
-        _fit_mode: String,
-        _monitor_id: Option<String>,
+        fit_mode: String,
+        monitor_id: Option<String>,
     ) -> Result<String, AppError> {
-        let conn = self.db.get_connection();
-        let conn = conn.lock().unwrap();
-        let mut stmt = conn.prepare("SELECT file_path FROM wallpapers WHERE id = ?1")?;
-        let file_path: String = stmt.query_row([&wallpaper_id], |row| row.get(0))
-            .map_err(|e| AppError::Internal(format!("未找到壁纸: {}", e)))?;
+        let file_path: String = {
+            let conn = self.db.get_connection();
+            let conn = conn.lock().unwrap();
+            let mut stmt = conn.prepare("SELECT file_path FROM wallpapers WHERE id = ?1")?;
+            let file_path = stmt.query_row([&wallpaper_id], |row| row.get(0))
+                .map_err(|e| AppError::Internal(format!("未找到壁纸: {}", e)))?;
+            file_path
+        };
 
         log::info!("[Rust] 设置壁纸，路径: {}", file_path);
-        wallpaper::set_from_path(&file_path).map_err(|e| AppError::Internal(format!("设置壁纸失败: {}", e)))?;
-
-        let result = SetWallpaperResult {
-            success: true,
-            message: Some("壁纸设置成功".to_string()),
-            error_code: None,
-        };
+        let fit_mode = WallpaperFitMode::parse(&fit_mode)?;
+        let result = self.apply_wallpaper(app, &file_path, fit_mode, monitor_id.as_deref())?;
         serde_json::to_string(&result).map_err(|e| AppError::Internal(e.to_string()))
     }
 
     /**
      * 设置本地文件为壁纸
      */
-    pub fn set_local_wallpaper(
+    pub fn set_local_wallpaper<R: Runtime>(
         &self,
+        app: &AppHandle<R>,
         file_path: String,
-        _fit_mode: String,
-        _monitor_id: Option<String>,
+        fit_mode: String,
+        monitor_id: Option<String>,
     ) -> Result<String, AppError> {
         log::info!("[Rust] 设置本地壁纸，路径: {}", file_path);
-        wallpaper::set_from_path(&file_path).map_err(|e| AppError::Internal(format!("设置壁纸失败: {}", e)))?;
+        let fit_mode = WallpaperFitMode::parse(&fit_mode)?;
+        let result = self.apply_wallpaper(app, &file_path, fit_mode, monitor_id.as_deref())?;
+        serde_json::to_string(&result).map_err(|e| AppError::Internal(e.to_string()))
+    }
+
+    /**
+     * 应用壁纸并记录设置
+     * @description 未指定显示器时设置到所有显示器；指定显示器且存在多台显示器时，
+     * 把各显示器的壁纸拼接成一张图片后以跨屏模式设置，系统不支持按显示器设置壁纸时同样有效
+     */
+    pub fn apply_wallpaper<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        file_path: &str,
+        fit_mode: WallpaperFitMode,
+        monitor_id: Option<&str>,
+    ) -> Result<SetWallpaperResult, AppError> {
+        if !Path::new(file_path).is_file() {
+            return Err(AppError::PathNotFound(file_path.to_string()));
+        }
 
-        let result = SetWallpaperResult {
-            success: true,
-            message: Some("壁纸设置成功".to_string()),
-            error_code: None,
+        let monitors = match monitor_id {
+            Some(_) => list_monitors(app)?,
+            None => vec![],
         };
-        serde_json::to_string(&result).map_err(|e| AppError::Internal(e.to_string()))
+        let target = match monitor_id {
+            Some(id) => {
+                let monitor = monitors.iter().find(|m| m.id == id)
+                    .ok_or_else(|| AppError::InvalidInput(format!("未找到显示器: {}", id)))?;
+                // 只有一台显示器时按整体设置处理
+                (monitors.len() > 1).then(|| monitor.id.clone())
+            }
+            None => None,
+        };
+
+        let Some(target) = target else {
+            wallpaper::set_from_path(file_path)
+                .map_err(|e| AppError::Internal(format!("设置壁纸失败: {}", e)))?;
+            let mode_applied = set_system_fit_mode(fit_mode);
+            self.save_assignments(ALL_MONITORS, file_path, fit_mode, file_path)?;
+            return Ok(set_result(mode_applied));
+        };
+
+        // 拼接各显示器的壁纸：本次指定的显示器使用新壁纸，其余沿用已记录的设置
+        let mut assignments = self.load_assignments()?;
+        assignments.retain(|a| a.monitor_id != target);
+        assignments.push(MonitorWallpaper {
+            monitor_id: target.clone(),
+            file_path: file_path.to_string(),
+            fit_mode,
+        });
+        let layers: Vec<MonitorLayer> = monitors
+            .iter()
+            .filter_map(|monitor| {
+                assignments.iter().find(|a| a.monitor_id == monitor.id).map(|a| MonitorLayer {
+                    monitor,
+                    file_path: PathBuf::from(&a.file_path),
+                    fit_mode: a.fit_mode,
+                })
+            })
+            .collect();
+        let background = self.background_wallpaper(app, &assignments)?;
+        let canvas = compose_span(
+            &monitors,
+            background.as_ref().map(|(path, mode)| (Path::new(path), *mode)),
+            &layers,
+        )?;
+
+        let composite_dir = composite_dir(app)?;
+        std::fs::create_dir_all(&composite_dir)?;
+        // 每次使用新文件名，部分桌面环境会按路径缓存壁纸
+        let composite_path = composite_dir.join(format!("span-{}.jpg", Uuid::new_v4()));
+        image::DynamicImage::ImageRgb8(canvas)
+            .save(&composite_path)
+            .map_err(|e| AppError::Internal(format!("保存拼接壁纸失败: {}", e)))?;
+        let composite_str = composite_path.to_string_lossy().to_string();
+
+        wallpaper::set_from_path(&composite_str)
+            .map_err(|e| AppError::Internal(format!("设置壁纸失败: {}", e)))?;
+        let mode_applied = set_system_fit_mode(WallpaperFitMode::Span);
+        self.save_assignments(&target, file_path, fit_mode, &composite_str)?;
+
+        // 清理之前生成的拼接图片
+        if let Ok(entries) = std::fs::read_dir(&composite_dir) {
+            for entry in entries.flatten() {
+                if entry.path() != composite_path {
+                    let _ = std::fs::remove_file(entry.path());
+                }
+            }
+        }
+        log::info!("[Rust] 已为显示器 {} 设置壁纸，拼接图片: {}", target, composite_str);
+        Ok(set_result(mode_applied))
+    }
+
+    /**
+     * 拼接时作为背景的壁纸
+     * @description 优先使用为所有显示器设置的壁纸，没有时沿用当前的系统壁纸（不含之前生成的拼接图片）
+     */
+    fn background_wallpaper<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        assignments: &[MonitorWallpaper],
+    ) -> Result<Option<(String, WallpaperFitMode)>, AppError> {
+        if let Some(all) = assignments.iter().find(|a| a.monitor_id == ALL_MONITORS) {
+            if Path::new(&all.file_path).is_file() {
+                return Ok(Some((all.file_path.clone(), all.fit_mode)));
+            }
+        }
+        let composite_dir = composite_dir(app)?;
+        Ok(wallpaper::get()
+            .ok()
+            .filter(|path| !path.is_empty() && Path::new(path).is_file())
+            .filter(|path| !Path::new(path).starts_with(&composite_dir))
+            .map(|path| (path, WallpaperFitMode::Fill)))
+    }
+
+    /**
+     * 读取已记录的各显示器壁纸设置
+     */
+    fn load_assignments(&self) -> Result<Vec<MonitorWallpaper>, AppError> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT monitor_id, file_path, fit_mode FROM wallpaper_assignments ORDER BY updated_at",
+        )?;
+        let assignments = stmt
+            .query_map([], |row| {
+                Ok(MonitorWallpaper {
+                    monitor_id: row.get(0)?,
+                    file_path: row.get(1)?,
+                    fit_mode: WallpaperFitMode::parse(&row.get::<_, String>(2)?).unwrap_or_default(),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(assignments)
+    }
+
+    /**
+     * 记录壁纸设置及实际设置到系统的图片路径
+     * @description 设置到所有显示器时清除各显示器单独的设置
+     */
+    fn save_assignments(
+        &self,
+        monitor_id: &str,
+        file_path: &str,
+        fit_mode: WallpaperFitMode,
+        applied_path: &str,
+    ) -> Result<(), AppError> {
+        let conn = self.db.get_connection();
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        if monitor_id == ALL_MONITORS {
+            tx.execute("DELETE FROM wallpaper_assignments", [])?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO wallpaper_assignments (monitor_id, file_path, fit_mode, updated_at)
+             VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP)",
+            params![monitor_id, file_path, fit_mode.as_str()],
+        )?;
+        tx.execute(
+            "INSERT OR REPLACE INTO app_settings (key, value, updated_at) VALUES ('applied_wallpaper_path', ?1, CURRENT_TIMESTAMP)",
+            params![applied_path],
+        )?;
+        tx.commit()?;
+        Ok(())
     }
 
     /**
      * 获取当前壁纸信息
+     * @description 系统壁纸仍是本应用设置的图片时返回记录的适配模式与各显示器设置；
+     * 壁纸已在应用外被修改时只返回系统壁纸路径，适配模式按系统默认的填充处理
      */
     pub fn get_current_wallpaper(&self) -> Result<String, AppError> {
         let path = wallpaper::get().unwrap_or_default();
-        let info = CurrentWallpaperInfo {
-            file_path: path,
-            fit_mode: "fill".to_string(),
+        let applied_path: Option<String> = {
+            let conn = self.db.get_connection();
+            let conn = conn.lock().unwrap();
+            conn.query_row(
+                "SELECT value FROM app_settings WHERE key = 'applied_wallpaper_path'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?
+        };
+
+        let mut info = CurrentWallpaperInfo {
+            file_path: path.clone(),
+            fit_mode: WallpaperFitMode::Fill.as_str().to_string(),
+            monitors: vec![],
         };
+        let applied_by_app = applied_path.is_some_and(|applied| !path.is_empty() && Path::new(&applied) == Path::new(&path));
+        if applied_by_app {
+            let (all, per_monitor): (Vec<_>, Vec<_>) = self
+                .load_assignments()?
+                .into_iter()
+                .partition(|a| a.monitor_id == ALL_MONITORS);
+            if !per_monitor.is_empty() {
+                info.fit_mode = WallpaperFitMode::Span.as_str().to_string();
+                info.monitors = per_monitor;
+            } else if let Some(all) = all.into_iter().next() {
+                info.file_path = all.file_path;
+                info.fit_mode = all.fit_mode.as_str().to_string();
+            }
+        }
         serde_json::to_string(&info).map_err(|e| AppError::Internal(e.to_string()))
     }
 
     /**
      * 获取显示器列表
      */
-    pub fn get_monitors(&self) -> Result<String, AppError> {
-        let monitors = vec![MonitorInfo {
-            id: "primary".to_string(),
-            name: "主显示器".to_string(),
-            width: 1920,
-            height: 1080,
-        }];
+    pub fn get_monitors<R: Runtime>(&self, app: &AppHandle<R>) -> Result<String, AppError> {
+        let monitors = list_monitors(app)?;
+        log::info!("[Rust] 检测到显示器数量: {}", monitors.len());
         serde_json::to_string(&monitors).map_err(|e| AppError::Internal(e.to_string()))
     }
 }
+
+/// wallpaper_assignments 中表示所有显示器的 monitor_id
+const ALL_MONITORS: &str = "*";
+
+/**
+ * 拼接壁纸的存放目录
+ */
+fn composite_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, AppError> {
+    let app_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Internal(format!("获取应用数据目录失败: {}", e)))?;
+    Ok(app_dir.join("wallpapers").join("composite"))
+}
+
+/**
+ * 设置系统的壁纸适配模式
+ * @description 部分桌面环境不支持设置适配模式，此时壁纸仍会生效，只记录日志
+ */
+fn set_system_fit_mode(fit_mode: WallpaperFitMode) -> bool {
+    match wallpaper::set_mode(fit_mode.to_system_mode()) {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("[Rust] 设置壁纸适配模式 {} 失败: {}", fit_mode.as_str(), e);
+            false
+        }
+    }
+}
+
+fn set_result(mode_applied: bool) -> SetWallpaperResult {
+    SetWallpaperResult {
+        success: true,
+        message: Some(if mode_applied {
+            "壁纸设置成功".to_string()
+        } else {
+            "壁纸设置成功，但当前桌面环境不支持该适配模式".to_string()
+        }),
+        error_code: (!mode_applied).then(|| "FIT_MODE_UNSUPPORTED".to_string()),
+    }
+}
+
+/// 未配置壁纸源时返回的空分页
+fn empty_station_response(page: u32, page_size: u32) -> WallpaperStationResponse {
+    WallpaperStationResponse {
+        wallpapers: vec![],
+        total: 0,
+        page,
+        page_size,
+        has_more: false,
+    }
+}
+
+/// 宽高比默认允许的相对误差，可以区分 16:9 与 16:10，同时容忍 3440x1440 与 2560x1080 这类近似比例
+const DEFAULT_ASPECT_TOLERANCE: f64 = 0.02;
+
+/// 本地壁纸列表默认每页条数
+const DEFAULT_PAGE_SIZE: u32 = 60;
+/// 本地壁纸列表单页最大条数
+const MAX_PAGE_SIZE: u32 = 500;
+
+/**
+ * 枚举当前连接的显示器
+ * @description 主显示器排在最前，其余按位置从左到右、从上到下排列
+ */
+pub fn list_monitors<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<MonitorInfo>, AppError> {
+    let mut available = app
+        .available_monitors()
+        .map_err(|e| AppError::SystemError(format!("获取显示器列表失败: {}", e)))?;
+    let primary = app.primary_monitor().ok().flatten();
+    // 部分 Wayland 合成器不支持枚举显示器，此时至少返回主显示器
+    if available.is_empty() {
+        available.extend(primary.clone());
+    }
+
+    let mut monitors: Vec<MonitorInfo> = vec![];
+    for monitor in available {
+        let position = monitor.position();
+        let size = monitor.size();
+        let is_primary = primary.as_ref().is_some_and(|p| {
+            p.name() == monitor.name() && p.position().x == position.x && p.position().y == position.y
+        });
+        let base_id = monitor
+            .name()
+            .filter(|name| !name.is_empty())
+            .cloned()
+            .unwrap_or_else(|| format!("monitor@{},{}", position.x, position.y));
+        // 名称重复时（如同型号显示器未提供名称）追加序号保证唯一
+        let mut id = base_id.clone();
+        let mut suffix = 2;
+        while monitors.iter().any(|m| m.id == id) {
+            id = format!("{}#{}", base_id, suffix);
+            suffix += 1;
+        }
+
+        monitors.push(MonitorInfo {
+            id,
+            name: String::new(),
+            x: position.x,
+            y: position.y,
+            width: size.width as i32,
+            height: size.height as i32,
+            scale_factor: monitor.scale_factor(),
+            is_primary,
+        });
+    }
+
+    monitors.sort_by_key(|m| (!m.is_primary, m.x, m.y));
+    for (index, monitor) in monitors.iter_mut().enumerate() {
+        monitor.name = if monitor.is_primary {
+            format!("主显示器 ({}x{})", monitor.width, monitor.height)
+        } else {
+            format!("显示器 {} ({}x{})", index + 1, monitor.width, monitor.height)
+        };
+    }
+    Ok(monitors)
+}
+
+/**
+ * 读取壁纸文件信息
+ * @description 解析图片头获取实际宽高（按 EXIF 方向换算为显示尺寸），修改时间取文件本身的修改时间
+ */
+pub(crate) fn read_wallpaper_file(path: &Path, file_name: &str, path_str: &str) -> LocalWallpaper {
+    let metadata = path.metadata().ok();
+    let info = media_probe::probe(path, FileKind::Image);
+    let now = chrono::Utc::now().to_rfc3339();
+
+    LocalWallpaper {
+        id: Uuid::new_v4().to_string(),
+        name: file_name.to_string(),
+        file_path: path_str.to_string(),
+        file_name: file_name.to_string(),
+        file_type: path.extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("unknown")
+            .to_string(),
+        file_size: metadata.as_ref()
+            .map(|m| m.len() as i64)
+            .unwrap_or(0),
+        width: info.width.and_then(|w| i32::try_from(w).ok()),
+        height: info.height.and_then(|h| i32::try_from(h).ok()),
+        modified_at: metadata
+            .and_then(|m| m.modified().ok())
+            .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339())
+            .unwrap_or_else(|| now.clone()),
+        created_at: now,
+        folder_id: None,
+        missing: false,
+        favorite: false,
+        rating: 0,
+        tags: vec![],
+        perceptual_hash: perceptual_hash(path),
+    }
+}
+
+/**
+ * 查询并填充壁纸的标签
+ */
+fn attach_tags(conn: &rusqlite::Connection, mut wallpapers: Vec<&mut LocalWallpaper>) -> Result<(), AppError> {
+    if wallpapers.is_empty() {
+        return Ok(());
+    }
+    let mut tag_stmt = conn.prepare(&format!(
+        "SELECT l.wallpaper_id, t.name FROM wallpaper_tag_links l
+         JOIN wallpaper_tags t ON t.id = l.tag_id
+         WHERE l.wallpaper_id IN ({})
+         ORDER BY t.name COLLATE NOCASE",
+        vec!["?"; wallpapers.len()].join(", ")
+    ))?;
+    let ids: Vec<&dyn rusqlite::ToSql> = wallpapers.iter().map(|w| &w.id as &dyn rusqlite::ToSql).collect();
+    let links = tag_stmt
+        .query_map(ids.as_slice(), |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    for (wallpaper_id, tag) in links {
+        for wallpaper in wallpapers.iter_mut().filter(|w| w.id == wallpaper_id) {
+            wallpaper.tags.push(tag.clone());
+        }
+    }
+    Ok(())
+}
+
+/**
+ * 构造筛选条件对应的 WHERE 子句及参数
+ */
+fn build_filter_clause(filter: &WallpaperFilter) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+    let mut conditions: Vec<String> = vec![];
+    let mut values: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+    let tolerance = filter.aspect_tolerance.unwrap_or(DEFAULT_ASPECT_TOLERANCE).abs();
+
+    if let Some(min_width) = filter.min_width {
+        conditions.push("width >= ?".to_string());
+        values.push(Box::new(min_width));
+    }
+    if let Some(min_height) = filter.min_height {
+        conditions.push("height >= ?".to_string());
+        values.push(Box::new(min_height));
+    }
+
+    if let Some(folder_id) = filter.folder_id {
+        conditions.push("folder_id = ?".to_string());
+        values.push(Box::new(folder_id));
+    }
+    if let Some(missing) = filter.missing {
+        conditions.push("missing = ?".to_string());
+        values.push(Box::new(missing));
+    }
+
+    if let Some(keyword) = filter.keyword.as_deref().map(str::trim).filter(|k| !k.is_empty()) {
+        conditions.push("(name LIKE ? ESCAPE '\\' OR file_name LIKE ? ESCAPE '\\')".to_string());
+        let pattern = format!("%{}%", escape_like(keyword));
+        values.push(Box::new(pattern.clone()));
+        values.push(Box::new(pattern));
+    }
+    if !filter.tag_ids.is_empty() {
+        let mut tag_ids = filter.tag_ids.clone();
+        tag_ids.sort_unstable();
+        tag_ids.dedup();
+        conditions.push(format!(
+            "id IN (SELECT wallpaper_id FROM wallpaper_tag_links WHERE tag_id IN ({}) GROUP BY wallpaper_id HAVING COUNT(*) = ?)",
+            vec!["?"; tag_ids.len()].join(", ")
+        ));
+        let count = tag_ids.len() as i64;
+        for tag_id in tag_ids {
+            values.push(Box::new(tag_id));
+        }
+        values.push(Box::new(count));
+    }
+    if let Some(collection_id) = filter.collection_id {
+        conditions.push("id IN (SELECT wallpaper_id FROM wallpaper_collection_items WHERE collection_id = ?)".to_string());
+        values.push(Box::new(collection_id));
+    }
+    if let Some(favorite) = filter.favorite {
+        conditions.push("favorite = ?".to_string());
+        values.push(Box::new(favorite));
+    }
+    if let Some(min_rating) = filter.min_rating {
+        conditions.push("rating >= ?".to_string());
+        values.push(Box::new(min_rating));
+    }
+
+    let mut aspect_ratios = vec![];
+    if let Some(ratio) = filter.aspect_ratio.filter(|r| *r > 0.0) {
+        aspect_ratios.push(ratio);
+    }
+    if let (Some(width), Some(height)) = (filter.fits_width, filter.fits_height) {
+        if width > 0 && height > 0 {
+            conditions.push("width >= ? AND height >= ?".to_string());
+            values.push(Box::new(width));
+            values.push(Box::new(height));
+            aspect_ratios.push(width as f64 / height as f64);
+        }
+    }
+    for ratio in aspect_ratios {
+        conditions.push("height > 0 AND ABS(CAST(width AS REAL) / height - ?) <= ?".to_string());
+        values.push(Box::new(ratio));
+        values.push(Box::new(ratio * tolerance));
+    }
+
+    if conditions.is_empty() {
+        (String::new(), values)
+    } else {
+        (format!(" WHERE {}", conditions.join(" AND ")), values)
+    }
+}
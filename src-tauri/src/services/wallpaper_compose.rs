@@ -0,0 +1,128 @@
+use crate::error::{AppError, Result};
+use crate::services::thumbnail::load_image;
+use crate::services::wallpaper::{MonitorInfo, WallpaperFitMode};
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, RgbImage};
+use std::path::{Path, PathBuf};
+
+/// 拼接后的画布边长上限，防止显示器坐标异常时分配过大的内存
+const MAX_CANVAS_SIZE: u32 = 32768;
+
+/// 一块显示器区域要绘制的图片
+pub struct MonitorLayer<'a> {
+    pub monitor: &'a MonitorInfo,
+    pub file_path: PathBuf,
+    pub fit_mode: WallpaperFitMode,
+}
+
+/// 把每台显示器各自的壁纸拼成一张覆盖所有显示器的图片，配合跨屏（span）模式使用
+/// background 会先铺满整张画布，没有单独指定壁纸的显示器显示背景对应的部分；
+/// 画布原点为所有显示器外接矩形的左上角，与系统跨屏显示时的坐标一致
+pub fn compose_span(
+    monitors: &[MonitorInfo],
+    background: Option<(&Path, WallpaperFitMode)>,
+    layers: &[MonitorLayer],
+) -> Result<RgbImage> {
+    let left = monitors.iter().map(|m| m.x).min().unwrap_or(0);
+    let top = monitors.iter().map(|m| m.y).min().unwrap_or(0);
+    let right = monitors.iter().map(|m| m.x + m.width).max().unwrap_or(0);
+    let bottom = monitors.iter().map(|m| m.y + m.height).max().unwrap_or(0);
+    let width = (right - left).max(0) as u32;
+    let height = (bottom - top).max(0) as u32;
+    if width == 0 || height == 0 || width > MAX_CANVAS_SIZE || height > MAX_CANVAS_SIZE {
+        return Err(AppError::Internal(format!("显示器布局无效: {}x{}", width, height)));
+    }
+
+    let mut canvas = RgbImage::new(width, height);
+    if let Some((path, fit_mode)) = background {
+        if fit_mode == WallpaperFitMode::Span {
+            let image = load_image(path, width, height)?;
+            draw(&mut canvas, &image, 0, 0, width, height, WallpaperFitMode::Fill);
+        } else {
+            // 背景按各显示器分别适配，已单独指定壁纸的显示器会被覆盖，无需绘制
+            let image = load_image(path, width, height)?;
+            for monitor in monitors {
+                if layers.iter().any(|layer| layer.monitor.id == monitor.id) {
+                    continue;
+                }
+                draw_monitor(&mut canvas, &image, monitor, left, top, fit_mode);
+            }
+        }
+    }
+
+    for layer in layers {
+        let monitor = layer.monitor;
+        let image = load_image(&layer.file_path, monitor.width as u32, monitor.height as u32)?;
+        draw_monitor(&mut canvas, &image, monitor, left, top, layer.fit_mode);
+    }
+
+    Ok(canvas)
+}
+
+fn draw_monitor(
+    canvas: &mut RgbImage,
+    image: &DynamicImage,
+    monitor: &MonitorInfo,
+    left: i32,
+    top: i32,
+    fit_mode: WallpaperFitMode,
+) {
+    draw(
+        canvas,
+        image,
+        (monitor.x - left) as i64,
+        (monitor.y - top) as i64,
+        monitor.width.max(1) as u32,
+        monitor.height.max(1) as u32,
+        fit_mode,
+    );
+}
+
+/// 按适配模式把图片绘制到画布的 (x, y, width, height) 区域内，超出区域的部分会被裁掉
+fn draw(
+    canvas: &mut RgbImage,
+    image: &DynamicImage,
+    x: i64,
+    y: i64,
+    width: u32,
+    height: u32,
+    fit_mode: WallpaperFitMode,
+) {
+    let mut region = RgbImage::new(width, height);
+    match fit_mode {
+        // 单台显示器上的跨屏模式等同于填充
+        WallpaperFitMode::Fill | WallpaperFitMode::Span => {
+            let filled = image.resize_to_fill(width, height, FilterType::Triangle);
+            imageops::replace(&mut region, &filled.to_rgb8(), 0, 0);
+        }
+        WallpaperFitMode::Fit => {
+            let fitted = image.resize(width, height, FilterType::Triangle);
+            let offset_x = (width as i64 - fitted.width() as i64) / 2;
+            let offset_y = (height as i64 - fitted.height() as i64) / 2;
+            imageops::replace(&mut region, &fitted.to_rgb8(), offset_x, offset_y);
+        }
+        WallpaperFitMode::Stretch => {
+            let stretched = image.resize_exact(width, height, FilterType::Triangle);
+            imageops::replace(&mut region, &stretched.to_rgb8(), 0, 0);
+        }
+        WallpaperFitMode::Center => {
+            let offset_x = (width as i64 - image.width() as i64) / 2;
+            let offset_y = (height as i64 - image.height() as i64) / 2;
+            imageops::replace(&mut region, &image.to_rgb8(), offset_x, offset_y);
+        }
+        WallpaperFitMode::Tile => {
+            let tile = image.to_rgb8();
+            let (tile_width, tile_height) = (tile.width().max(1) as i64, tile.height().max(1) as i64);
+            let mut offset_y = 0;
+            while offset_y < height as i64 {
+                let mut offset_x = 0;
+                while offset_x < width as i64 {
+                    imageops::replace(&mut region, &tile, offset_x, offset_y);
+                    offset_x += tile_width;
+                }
+                offset_y += tile_height;
+            }
+        }
+    }
+    imageops::replace(canvas, &region, x, y);
+}
@@ -0,0 +1,346 @@
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::services::thumbnail::load_image;
+use crate::services::wallpaper::LocalWallpaper;
+use image::imageops::FilterType;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// 去重设置存放在 app_settings 中的键
+const SETTINGS_KEY: &str = "wallpaper_dedup";
+
+/// dHash 的位数（9 × 8 灰度图相邻像素比较得到 64 位）
+const HASH_BITS: u32 = 64;
+const DEFAULT_SIMILARITY: f64 = 0.9;
+/// 相似度下限，过低时几乎所有图片都会被判为重复
+const MIN_SIMILARITY: f64 = 0.75;
+
+/// 矢量图计算哈希时的渲染尺寸
+const SVG_RENDER_SIZE: u32 = 256;
+
+/// 导入时遇到近似重复图片的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NearDuplicateAction {
+    /// 照常导入
+    Allow,
+    /// 不导入新图片
+    #[default]
+    Refuse,
+    /// 不新增记录；新图片分辨率更高时由已有记录改为指向新图片，标签、收藏等保持不变
+    Merge,
+}
+
+/// 壁纸去重设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WallpaperDedupSettings {
+    /// 感知哈希相似度（0-1），不低于该值视为近似重复
+    pub similarity: f64,
+    pub action: NearDuplicateAction,
+}
+
+impl Default for WallpaperDedupSettings {
+    fn default() -> Self {
+        Self {
+            similarity: DEFAULT_SIMILARITY,
+            action: NearDuplicateAction::default(),
+        }
+    }
+}
+
+impl WallpaperDedupSettings {
+    /// 相似度对应的最大汉明距离
+    pub fn max_distance(&self) -> u32 {
+        max_distance(self.similarity)
+    }
+}
+
+/// 近似重复的一组壁纸，按分辨率从高到低排列，第一张通常是最值得保留的
+#[derive(Clone, Serialize)]
+pub struct WallpaperDuplicateCluster {
+    /// 组内相连两张图片之间的最低相似度
+    pub similarity: f64,
+    pub wallpapers: Vec<LocalWallpaper>,
+}
+
+/**
+ * 壁纸去重服务
+ * @description 导入时计算 dHash 感知哈希，缩放、重新压缩或另存为其他格式的同一张图片哈希相近；
+ * 早期导入、尚未计算哈希的壁纸在查找重复或导入前补算
+ */
+pub struct WallpaperDedupService {
+    db: Arc<Database>,
+}
+
+impl WallpaperDedupService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    pub fn settings(&self) -> Result<WallpaperDedupSettings> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT value FROM app_settings WHERE key = ?1",
+                params![SETTINGS_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        match value {
+            Some(value) => match serde_json::from_str(&value) {
+                Ok(settings) => Ok(settings),
+                Err(e) => {
+                    log::warn!("Ignoring invalid wallpaper dedup settings: {}", e);
+                    Ok(WallpaperDedupSettings::default())
+                }
+            },
+            None => Ok(WallpaperDedupSettings::default()),
+        }
+    }
+
+    pub fn save_settings(&self, settings: &WallpaperDedupSettings) -> Result<WallpaperDedupSettings> {
+        validate_similarity(settings.similarity)?;
+        let value = serde_json::to_string(settings)?;
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO app_settings (key, value, updated_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)",
+            params![SETTINGS_KEY, value],
+        )?;
+        Ok(settings.clone())
+    }
+
+    /// 为尚未计算哈希的壁纸补算，文件读取与解码在数据库锁外完成，返回成功计算的数量；
+    /// 无法解码的壁纸记为 hash_failed，之后不再重复尝试，直到文件内容变化后重新读取
+    pub fn fill_missing_hashes(&self) -> Result<usize> {
+        let pending: Vec<(String, String)> = {
+            let conn = self.db.get_connection();
+            let conn = conn.lock().unwrap();
+            let mut stmt = conn.prepare(
+                "SELECT id, file_path FROM wallpapers WHERE perceptual_hash IS NULL AND hash_failed = 0 AND missing = 0",
+            )?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            rows
+        };
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let hashes: Vec<(String, Option<u64>)> = pending
+            .into_iter()
+            .map(|(id, path)| (id, perceptual_hash(Path::new(&path))))
+            .collect();
+
+        let conn = self.db.get_connection();
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for (id, hash) in &hashes {
+            tx.execute(
+                "UPDATE wallpapers SET perceptual_hash = ?1, hash_failed = ?1 IS NULL WHERE id = ?2",
+                params![hash.map(|h| h as i64), id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(hashes.iter().filter(|(_, hash)| hash.is_some()).count())
+    }
+
+    /**
+     * 查找壁纸库中的近似重复壁纸
+     * @param similarity 相似度阈值，为空时使用设置中的值
+     * @description 两两比较哈希，相似的图片归入同一组（传递合并）；文件已丢失的壁纸不参与比较
+     */
+    pub fn find_clusters(&self, similarity: Option<f64>) -> Result<Vec<WallpaperDuplicateCluster>> {
+        let similarity = match similarity {
+            Some(similarity) => {
+                validate_similarity(similarity)?;
+                similarity
+            }
+            None => self.settings()?.similarity,
+        };
+        let max_distance = max_distance(similarity);
+        self.fill_missing_hashes()?;
+
+        let wallpapers: Vec<(LocalWallpaper, u64)> = {
+            let conn = self.db.get_connection();
+            let conn = conn.lock().unwrap();
+            load_hashed_wallpapers(&conn)?
+        }
+        .into_iter()
+        .filter(|(_, hash)| is_distinctive(*hash, max_distance))
+        .collect();
+        let mut parents: Vec<usize> = (0..wallpapers.len()).collect();
+        let mut min_similarity: HashMap<usize, f64> = HashMap::new();
+        let mut links = vec![];
+        for i in 0..wallpapers.len() {
+            for j in (i + 1)..wallpapers.len() {
+                let distance = hamming_distance(wallpapers[i].1, wallpapers[j].1);
+                if distance <= max_distance {
+                    union(&mut parents, i, j);
+                    links.push((i, distance));
+                }
+            }
+        }
+        for (i, distance) in links {
+            let root = find(&mut parents, i);
+            let similarity = similarity_of(distance);
+            let entry = min_similarity.entry(root).or_insert(similarity);
+            *entry = entry.min(similarity);
+        }
+
+        let mut groups: HashMap<usize, Vec<LocalWallpaper>> = HashMap::new();
+        for (i, (wallpaper, _)) in wallpapers.into_iter().enumerate() {
+            let root = find(&mut parents, i);
+            if min_similarity.contains_key(&root) {
+                groups.entry(root).or_default().push(wallpaper);
+            }
+        }
+
+        let mut clusters: Vec<WallpaperDuplicateCluster> = groups
+            .into_iter()
+            .map(|(root, mut wallpapers)| {
+                wallpapers.sort_by(|a, b| {
+                    pixels(b)
+                        .cmp(&pixels(a))
+                        .then(b.file_size.cmp(&a.file_size))
+                        .then(a.created_at.cmp(&b.created_at))
+                });
+                WallpaperDuplicateCluster {
+                    similarity: min_similarity[&root],
+                    wallpapers,
+                }
+            })
+            .collect();
+        clusters.sort_by(|a, b| {
+            b.wallpapers
+                .len()
+                .cmp(&a.wallpapers.len())
+                .then(a.wallpapers[0].created_at.cmp(&b.wallpapers[0].created_at))
+        });
+        Ok(clusters)
+    }
+}
+
+/// 读取已计算哈希且文件未丢失的壁纸，按导入时间排列
+pub(crate) fn load_hashed_wallpapers(conn: &Connection) -> Result<Vec<(LocalWallpaper, u64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, file_path, file_name, file_type, file_size, width, height, modified_at, created_at,
+                folder_id, missing, favorite, rating, perceptual_hash
+         FROM wallpapers WHERE perceptual_hash IS NOT NULL AND missing = 0
+         ORDER BY created_at, id",
+    )?;
+    let wallpapers = stmt
+        .query_map([], |row| {
+            let hash = row.get::<_, i64>(14)? as u64;
+            Ok((
+                LocalWallpaper {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    file_path: row.get(2)?,
+                    file_name: row.get(3)?,
+                    file_type: row.get(4)?,
+                    file_size: row.get(5)?,
+                    width: row.get(6)?,
+                    height: row.get(7)?,
+                    modified_at: row.get(8)?,
+                    created_at: row.get(9)?,
+                    folder_id: row.get(10)?,
+                    missing: row.get(11)?,
+                    favorite: row.get(12)?,
+                    rating: row.get(13)?,
+                    tags: vec![],
+                    perceptual_hash: Some(hash),
+                },
+                hash,
+            ))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(wallpapers)
+}
+
+/**
+ * 计算图片的 dHash 感知哈希
+ * @description 缩放为 9 × 8 灰度图后比较每行相邻像素的亮度，与图片尺寸、格式和压缩质量无关；无法解码时返回 None
+ */
+pub fn perceptual_hash(path: &Path) -> Option<u64> {
+    let image = match load_image(path, SVG_RENDER_SIZE, SVG_RENDER_SIZE) {
+        Ok(image) => image,
+        Err(e) => {
+            log::warn!("Failed to hash {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    // 先快速缩小再精确缩放到 9 × 8，避免对大图直接做插值
+    let small = image.thumbnail(64, 64).resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    Some(hash)
+}
+
+/// 纯色或单向渐变的图片哈希接近全 0 或全 1，不同颜色的纯色图片无法区分，不参与近似重复判断
+pub fn is_distinctive(hash: u64, max_distance: u32) -> bool {
+    let ones = hash.count_ones();
+    ones > max_distance && ones < HASH_BITS - max_distance
+}
+
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// 汉明距离对应的相似度
+pub fn similarity_of(distance: u32) -> f64 {
+    1.0 - distance as f64 / HASH_BITS as f64
+}
+
+fn max_distance(similarity: f64) -> u32 {
+    ((1.0 - similarity) * HASH_BITS as f64 + 1e-9).floor() as u32
+}
+
+fn validate_similarity(similarity: f64) -> Result<()> {
+    if !(MIN_SIMILARITY..=1.0).contains(&similarity) {
+        return Err(AppError::InvalidInput(format!(
+            "相似度需在 {} 到 1 之间",
+            MIN_SIMILARITY
+        )));
+    }
+    Ok(())
+}
+
+/// 像素数，宽高未知时为 0
+pub(crate) fn pixels(wallpaper: &LocalWallpaper) -> i64 {
+    wallpaper.width.unwrap_or(0) as i64 * wallpaper.height.unwrap_or(0) as i64
+}
+
+fn find(parents: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    let mut node = i;
+    while parents[node] != root {
+        let next = parents[node];
+        parents[node] = root;
+        node = next;
+    }
+    root
+}
+
+fn union(parents: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (find(parents, a), find(parents, b));
+    if a != b {
+        parents[b.max(a)] = a.min(b);
+    }
+}
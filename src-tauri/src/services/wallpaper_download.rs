@@ -0,0 +1,397 @@
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::services::thumbnail::{load_image, THUMBNAIL_EXTENSIONS};
+use crate::services::wallpaper::LocalWallpaper;
+use crate::services::wallpaper_source::WallpaperSourceRegistry;
+use crate::services::WallpaperService;
+use reqwest::header::{CONTENT_RANGE, CONTENT_TYPE, RANGE};
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio::io::AsyncWriteExt;
+
+/// 下载进度事件名
+pub const WALLPAPER_DOWNLOAD_EVENT: &str = "wallpaper://download";
+
+/// 单张壁纸的大小上限
+const MAX_DOWNLOAD_BYTES: u64 = 100 * 1024 * 1024;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+/// 超过该时间未收到数据视为连接中断，已下载的部分留待续传
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// 两次进度事件的最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+/// 未完成的下载存放在壁纸库下的该目录中，以原图地址的哈希命名，再次下载同一地址时断点续传
+const PARTIAL_DIR: &str = ".partial";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadState {
+    Downloading,
+    /// 下载完成，正在校验图片并导入
+    Verifying,
+    Completed,
+    Failed,
+}
+
+/// 下载进度，作为 wallpaper://download 事件的负载
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WallpaperDownloadProgress {
+    /// 壁纸站中的壁纸 ID
+    pub wallpaper_id: String,
+    pub state: DownloadState,
+    pub downloaded_bytes: u64,
+    /// 服务器未返回大小时为 None
+    pub total_bytes: Option<u64>,
+    /// 是否从上次中断处继续下载
+    pub resumed: bool,
+    /// 导入后的本地壁纸，仅在 completed 时有值
+    pub wallpaper: Option<LocalWallpaper>,
+    pub error: Option<String>,
+}
+
+/// 壁纸下载管理器
+/// @description 从当前壁纸源下载原图到壁纸库目录，校验后通过导入流程写入 wallpapers 表；
+/// 同一张壁纸同时只会有一个下载任务
+pub struct WallpaperDownloadManager {
+    client: reqwest::Client,
+    active: Mutex<HashSet<String>>,
+}
+
+/// 下载结束时从进行中的任务中移除
+struct ActiveDownload<'a> {
+    manager: &'a WallpaperDownloadManager,
+    wallpaper_id: String,
+}
+
+impl Drop for ActiveDownload<'_> {
+    fn drop(&mut self) {
+        self.manager.active.lock().unwrap().remove(&self.wallpaper_id);
+    }
+}
+
+/// 下载中的文件
+struct Transfer {
+    path: PathBuf,
+    content_type: Option<String>,
+}
+
+impl WallpaperDownloadManager {
+    pub fn new() -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .user_agent(concat!("local-resource-manager/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        Ok(WallpaperDownloadManager {
+            client,
+            active: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// 下载壁纸站中的壁纸并导入本地壁纸库，失败时同样会推送 failed 事件
+    pub async fn download<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        db: Arc<Database>,
+        sources: Arc<WallpaperSourceRegistry>,
+        wallpaper_id: String,
+    ) -> Result<LocalWallpaper> {
+        if !self.active.lock().unwrap().insert(wallpaper_id.clone()) {
+            return Err(AppError::InvalidInput(format!("壁纸正在下载中: {}", wallpaper_id)));
+        }
+        let _active = ActiveDownload {
+            manager: self,
+            wallpaper_id: wallpaper_id.clone(),
+        };
+
+        let mut progress = WallpaperDownloadProgress {
+            wallpaper_id: wallpaper_id.clone(),
+            state: DownloadState::Downloading,
+            downloaded_bytes: 0,
+            total_bytes: None,
+            resumed: false,
+            wallpaper: None,
+            error: None,
+        };
+        let result = self.run(app, db, sources, &mut progress).await;
+        match &result {
+            Ok(wallpaper) => {
+                progress.state = DownloadState::Completed;
+                progress.wallpaper = Some(wallpaper.clone());
+            }
+            Err(e) => {
+                log::warn!("Failed to download wallpaper {}: {}", wallpaper_id, e);
+                progress.state = DownloadState::Failed;
+                progress.error = Some(e.to_string());
+            }
+        }
+        let _ = app.emit(WALLPAPER_DOWNLOAD_EVENT, progress);
+        result
+    }
+
+    async fn run<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        db: Arc<Database>,
+        sources: Arc<WallpaperSourceRegistry>,
+        progress: &mut WallpaperDownloadProgress,
+    ) -> Result<LocalWallpaper> {
+        // 解析下载地址可能需要请求订阅，放到阻塞线程执行
+        let wallpaper_id = progress.wallpaper_id.clone();
+        let url = tauri::async_runtime::spawn_blocking(move || {
+            let source = sources
+                .active()
+                .ok_or_else(|| AppError::InvalidInput("尚未配置在线壁纸源".to_string()))?;
+            source.resolve_download_url(&wallpaper_id)
+        })
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))??;
+        let url = Url::parse(&url).map_err(|e| AppError::InvalidInput(format!("无效的下载地址 {}: {}", url, e)))?;
+
+        let library = library_dir(app)?;
+        let partial_dir = library.join(PARTIAL_DIR);
+        tokio::fs::create_dir_all(&partial_dir).await?;
+        let url_hash = blake3::hash(url.as_str().as_bytes()).to_hex()[..32].to_string();
+        let part_path = partial_dir.join(format!("{}.part", url_hash));
+
+        let transfer = self.fetch(app, &url, &part_path, progress).await?;
+
+        progress.state = DownloadState::Verifying;
+        let _ = app.emit(WALLPAPER_DOWNLOAD_EVENT, progress.clone());
+
+        let file_name = file_name_for(&url);
+        tauri::async_runtime::spawn_blocking(move || {
+            let extension = image_extension(transfer.content_type.as_deref(), &file_name, &transfer.path)?;
+            // 以正确的扩展名校验，SVG 需要按扩展名识别
+            let verify_path = partial_dir.join(format!("{}.{}", url_hash, extension));
+            std::fs::rename(&transfer.path, &verify_path)?;
+            if let Err(e) = load_image(&verify_path, 64, 64) {
+                let _ = std::fs::remove_file(&verify_path);
+                return Err(AppError::InvalidInput(format!("下载的文件不是有效的图片: {}", e)));
+            }
+
+            let target = unique_path(&library, &file_name, extension);
+            std::fs::rename(&verify_path, &target)?;
+            let target_str = target.to_string_lossy().to_string();
+            let service = WallpaperService::new(db);
+            let result = service.import_wallpapers(vec![target_str.clone()])?;
+            if let Some(wallpaper) = result.imported.into_iter().next() {
+                return Ok(wallpaper);
+            }
+            // 与已有壁纸近似重复时返回已有壁纸；下载的文件未被采用时删除
+            let duplicate = result
+                .duplicates
+                .into_iter()
+                .next()
+                .ok_or_else(|| AppError::Internal(format!("导入下载的壁纸失败: {}", target.display())))?;
+            if duplicate.existing.file_path != target_str {
+                let _ = std::fs::remove_file(&target);
+            }
+            Ok(duplicate.existing)
+        })
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+    }
+
+    /// 下载到临时文件，已有部分内容时通过 Range 请求续传；服务器不支持续传时重新下载
+    async fn fetch<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        url: &Url,
+        part_path: &Path,
+        progress: &mut WallpaperDownloadProgress,
+    ) -> Result<Transfer> {
+        let network_error = |e: reqwest::Error| AppError::Network(format!("下载 {} 失败: {}", url, e));
+
+        let mut existing = tokio::fs::metadata(part_path).await.map(|m| m.len()).unwrap_or(0);
+        let mut response = self.request(url, existing).await.map_err(network_error)?;
+        if response.status() == StatusCode::RANGE_NOT_SATISFIABLE && existing > 0 {
+            // 临时文件与服务器上的文件不一致，丢弃后重新下载
+            existing = 0;
+            response = self.request(url, 0).await.map_err(network_error)?;
+        }
+        let response = response.error_for_status().map_err(network_error)?;
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.split(';').next().unwrap_or("").trim().to_ascii_lowercase());
+        if let Some(content_type) = &content_type {
+            let is_binary = matches!(content_type.as_str(), "application/octet-stream" | "binary/octet-stream");
+            if !content_type.starts_with("image/") && !is_binary {
+                return Err(AppError::InvalidInput(format!("下载的内容不是图片: {}", content_type)));
+            }
+        }
+
+        // 只有返回的区间正好接在已下载内容之后时才续传
+        let (downloaded, total) = match response.status() {
+            StatusCode::PARTIAL_CONTENT => {
+                let range = response
+                    .headers()
+                    .get(CONTENT_RANGE)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(parse_content_range);
+                match range {
+                    Some((start, total)) if start == existing => (existing, total),
+                    _ => {
+                        let _ = tokio::fs::remove_file(part_path).await;
+                        return Err(AppError::Network(format!("服务器返回的续传区间无效: {}", url)));
+                    }
+                }
+            }
+            _ => (0, response.content_length()),
+        };
+        if let Some(total) = total {
+            if total > MAX_DOWNLOAD_BYTES {
+                return Err(AppError::InvalidInput(format!(
+                    "壁纸过大: {} 字节，上限为 {} 字节",
+                    total, MAX_DOWNLOAD_BYTES
+                )));
+            }
+        }
+
+        let mut file = if downloaded > 0 {
+            tokio::fs::OpenOptions::new().append(true).open(part_path).await?
+        } else {
+            tokio::fs::File::create(part_path).await?
+        };
+        progress.resumed = downloaded > 0;
+        progress.downloaded_bytes = downloaded;
+        progress.total_bytes = total;
+        let _ = app.emit(WALLPAPER_DOWNLOAD_EVENT, progress.clone());
+
+        let mut response = response;
+        let mut last_emit = Instant::now();
+        loop {
+            let chunk = tokio::time::timeout(READ_TIMEOUT, response.chunk())
+                .await
+                .map_err(|_| AppError::Network(format!("下载 {} 超时", url)))?
+                .map_err(network_error)?;
+            let Some(chunk) = chunk else {
+                break;
+            };
+            progress.downloaded_bytes += chunk.len() as u64;
+            if progress.downloaded_bytes > MAX_DOWNLOAD_BYTES {
+                drop(file);
+                let _ = tokio::fs::remove_file(part_path).await;
+                return Err(AppError::InvalidInput(format!("壁纸过大，超过 {} 字节", MAX_DOWNLOAD_BYTES)));
+            }
+            file.write_all(&chunk).await?;
+            if last_emit.elapsed() >= PROGRESS_INTERVAL {
+                last_emit = Instant::now();
+                let _ = app.emit(WALLPAPER_DOWNLOAD_EVENT, progress.clone());
+            }
+        }
+        file.flush().await?;
+        drop(file);
+
+        // 连接中断时保留临时文件，下次下载同一地址时续传
+        if let Some(total) = total {
+            if progress.downloaded_bytes != total {
+                return Err(AppError::Network(format!(
+                    "下载不完整: 已下载 {} / {} 字节",
+                    progress.downloaded_bytes, total
+                )));
+            }
+        }
+        if progress.downloaded_bytes == 0 {
+            let _ = tokio::fs::remove_file(part_path).await;
+            return Err(AppError::Network(format!("下载的文件为空: {}", url)));
+        }
+
+        Ok(Transfer {
+            path: part_path.to_path_buf(),
+            content_type,
+        })
+    }
+
+    async fn request(&self, url: &Url, offset: u64) -> reqwest::Result<reqwest::Response> {
+        let mut request = self.client.get(url.clone());
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", offset));
+        }
+        request.send().await
+    }
+}
+
+/// 下载的壁纸存放目录
+fn library_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf> {
+    let app_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Internal(format!("获取应用数据目录失败: {}", e)))?;
+    Ok(app_dir.join("wallpapers").join("library"))
+}
+
+/// 解析 Content-Range: bytes start-end/total，返回起始位置与总大小
+fn parse_content_range(value: &str) -> Option<(u64, Option<u64>)> {
+    let range = value.trim().strip_prefix("bytes ")?;
+    let (span, total) = range.split_once('/')?;
+    let (start, _) = span.split_once('-')?;
+    Some((start.trim().parse().ok()?, total.trim().parse().ok()))
+}
+
+/// 按 Content-Type、地址中的扩展名、文件内容的顺序确定图片格式
+fn image_extension(content_type: Option<&str>, file_name: &str, path: &Path) -> Result<&'static str> {
+    let from_mime = |mime: &str| match mime {
+        "image/jpeg" | "image/jpg" | "image/pjpeg" => Some("jpg"),
+        "image/png" => Some("png"),
+        "image/gif" => Some("gif"),
+        "image/bmp" | "image/x-ms-bmp" => Some("bmp"),
+        "image/webp" => Some("webp"),
+        "image/svg+xml" => Some("svg"),
+        _ => None,
+    };
+    if let Some(extension) = content_type.and_then(from_mime) {
+        return Ok(extension);
+    }
+    if let Some(extension) = Path::new(file_name).extension().and_then(|e| e.to_str()) {
+        let extension = extension.to_ascii_lowercase();
+        if let Some(known) = THUMBNAIL_EXTENSIONS.iter().find(|e| **e == extension) {
+            return Ok(if *known == "jpeg" { "jpg" } else { known });
+        }
+    }
+    infer::get_from_path(path)?
+        .and_then(|kind| from_mime(kind.mime_type()))
+        .ok_or_else(|| AppError::InvalidInput("无法识别下载文件的图片格式".to_string()))
+}
+
+/// 取地址最后一段作为文件名，去掉文件系统不允许的字符
+fn file_name_for(url: &Url) -> String {
+    let name = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .unwrap_or("")
+        .to_string();
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_control() || r#"<>:"/\|?*"#.contains(c) { '_' } else { c })
+        .collect();
+    let name = name.trim_matches(|c: char| c == '.' || c.is_whitespace());
+    if name.is_empty() {
+        "wallpaper".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// 在壁纸库中生成不重名的路径，重名时追加序号
+fn unique_path(dir: &Path, file_name: &str, extension: &str) -> PathBuf {
+    let stem = Path::new(file_name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .filter(|s| !s.is_empty())
+        .unwrap_or("wallpaper");
+    let mut path = dir.join(format!("{}.{}", stem, extension));
+    let mut index = 1;
+    while path.exists() {
+        path = dir.join(format!("{} ({}).{}", stem, index, extension));
+        index += 1;
+    }
+    path
+}
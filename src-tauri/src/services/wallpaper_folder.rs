@@ -0,0 +1,696 @@
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::services::thumbnail::{ThumbnailCache, THUMBNAIL_EXTENSIONS};
+use crate::services::wallpaper::read_wallpaper_file;
+use ignore::WalkBuilder;
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use once_cell::sync::Lazy;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// 文件夹同步后壁纸库发生变化时推送给前端的事件名
+pub const WALLPAPERS_CHANGED_EVENT: &str = "wallpapers://changed";
+
+/// 合并短时间内连续文件事件的时间窗口
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// 每个文件夹的同步锁，监听器、启动时的后台同步与手动同步可能同时触发同一文件夹的同步
+static SYNC_LOCKS: Lazy<Mutex<HashMap<i64, Arc<Mutex<()>>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+const FOLDER_COLUMNS: &str =
+    "id, path, recursive, min_width, min_height, watch_enabled, missing_policy, last_synced_at, created_at";
+
+/// 文件被移动或删除后对壁纸记录的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingPolicy {
+    /// 保留记录并标记为 missing，文件恢复后自动取消标记
+    #[default]
+    Flag,
+    /// 直接删除记录
+    Remove,
+}
+
+impl MissingPolicy {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MissingPolicy::Flag => "flag",
+            MissingPolicy::Remove => "remove",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "remove" => MissingPolicy::Remove,
+            _ => MissingPolicy::Flag,
+        }
+    }
+}
+
+/// 壁纸来源文件夹
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WallpaperFolder {
+    pub id: i64,
+    pub path: String,
+    /// 是否包含子文件夹
+    pub recursive: bool,
+    /// 宽或高小于该值的图片不会导入，宽高未知的图片同样不会导入
+    pub min_width: Option<i32>,
+    pub min_height: Option<i32>,
+    /// 是否实时监听文件夹变化
+    pub watch_enabled: bool,
+    pub missing_policy: MissingPolicy,
+    pub last_synced_at: Option<String>,
+    pub created_at: String,
+}
+
+/// 添加或修改文件夹时的参数
+#[derive(Debug, Clone, Deserialize)]
+pub struct WallpaperFolderInput {
+    pub path: String,
+    #[serde(default)]
+    pub recursive: bool,
+    #[serde(default)]
+    pub min_width: Option<i32>,
+    #[serde(default)]
+    pub min_height: Option<i32>,
+    #[serde(default)]
+    pub watch_enabled: bool,
+    #[serde(default)]
+    pub missing_policy: MissingPolicy,
+}
+
+/// 单个文件夹的同步结果，同时也是 wallpapers://changed 事件的负载
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WallpaperFolderSyncResult {
+    pub folder_id: i64,
+    pub added: i32,
+    /// 文件内容变化后重新读取了宽高的记录数
+    pub updated: i32,
+    /// 新标记为 missing 的记录数
+    pub missing: i32,
+    /// 文件重新出现后取消 missing 标记的记录数
+    pub restored: i32,
+    /// 按 remove 策略删除的记录数
+    pub removed: i32,
+    /// 不再满足递归或分辨率条件而解除与文件夹关联的记录数，记录连同收藏、评分与标签保留在壁纸库中
+    pub detached: i32,
+    /// 分辨率不足而未导入的图片数
+    pub skipped: i32,
+}
+
+impl WallpaperFolderSyncResult {
+    fn changed(&self) -> bool {
+        self.added + self.updated + self.missing + self.restored + self.removed + self.detached > 0
+    }
+}
+
+/// 文件夹中已导入的壁纸记录
+struct ExistingWallpaper {
+    id: String,
+    file_size: Option<i64>,
+    width: Option<i32>,
+    height: Option<i32>,
+    modified_at: String,
+    missing: bool,
+}
+
+/**
+ * 壁纸文件夹服务
+ * @description 管理壁纸来源文件夹，同步时导入新图片、更新已变化的图片，并按设置标记或删除已丢失的图片
+ */
+pub struct WallpaperFolderService {
+    db: Arc<Database>,
+}
+
+impl WallpaperFolderService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    pub fn list_folders(&self) -> Result<Vec<WallpaperFolder>> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM wallpaper_folders ORDER BY created_at, id",
+            FOLDER_COLUMNS
+        ))?;
+        let folders = stmt
+            .query_map([], map_folder)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(folders)
+    }
+
+    pub fn get_folder(&self, folder_id: i64) -> Result<WallpaperFolder> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+        conn.query_row(
+            &format!("SELECT {} FROM wallpaper_folders WHERE id = ?1", FOLDER_COLUMNS),
+            params![folder_id],
+            map_folder,
+        )
+        .optional()?
+        .ok_or_else(|| AppError::InvalidInput(format!("未找到壁纸文件夹: {}", folder_id)))
+    }
+
+    /// 添加文件夹，不会立即同步；同一文件只能属于一个文件夹，因此不能与已有文件夹相互包含
+    pub fn add_folder(&self, input: &WallpaperFolderInput) -> Result<WallpaperFolder> {
+        let path = normalize_folder_path(&input.path)?;
+        validate_resolution(input)?;
+
+        let folder_id = {
+            let conn = self.db.get_connection();
+            let conn = conn.lock().unwrap();
+            let existing: Vec<String> = conn
+                .prepare("SELECT path FROM wallpaper_folders")?
+                .query_map([], |row| row.get(0))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            for other in &existing {
+                if *other == path {
+                    return Err(AppError::InvalidInput(format!("壁纸文件夹已存在: {}", path)));
+                }
+                if Path::new(&path).starts_with(other) || Path::new(other).starts_with(&path) {
+                    return Err(AppError::InvalidInput(format!(
+                        "壁纸文件夹与已添加的文件夹 {} 相互包含: {}",
+                        other, path
+                    )));
+                }
+            }
+            conn.execute(
+                "INSERT INTO wallpaper_folders (path, recursive, min_width, min_height, watch_enabled, missing_policy)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    path,
+                    input.recursive,
+                    input.min_width,
+                    input.min_height,
+                    input.watch_enabled,
+                    input.missing_policy.as_str()
+                ],
+            )?;
+            conn.last_insert_rowid()
+        };
+        self.get_folder(folder_id)
+    }
+
+    /// 修改文件夹设置，路径不可修改；新的设置在下次同步时生效
+    pub fn update_folder(&self, folder_id: i64, input: &WallpaperFolderInput) -> Result<WallpaperFolder> {
+        validate_resolution(input)?;
+        {
+            let conn = self.db.get_connection();
+            let conn = conn.lock().unwrap();
+            let updated = conn.execute(
+                "UPDATE wallpaper_folders
+                 SET recursive = ?1, min_width = ?2, min_height = ?3, watch_enabled = ?4, missing_policy = ?5
+                 WHERE id = ?6",
+                params![
+                    input.recursive,
+                    input.min_width,
+                    input.min_height,
+                    input.watch_enabled,
+                    input.missing_policy.as_str(),
+                    folder_id
+                ],
+            )?;
+            if updated == 0 {
+                return Err(AppError::InvalidInput(format!("未找到壁纸文件夹: {}", folder_id)));
+            }
+        }
+        self.get_folder(folder_id)
+    }
+
+    /// 删除文件夹；remove_wallpapers 为 false 时保留已导入的壁纸，仅解除与文件夹的关联
+    pub fn remove_folder(&self, thumbnails: &ThumbnailCache, folder_id: i64, remove_wallpapers: bool) -> Result<()> {
+        let mut removed_paths = vec![];
+        {
+            let conn = self.db.get_connection();
+            let mut conn = conn.lock().unwrap();
+            let tx = conn.transaction()?;
+            if remove_wallpapers {
+                removed_paths = {
+                    let mut stmt = tx.prepare("SELECT file_path FROM wallpapers WHERE folder_id = ?1")?;
+                    let rows = stmt.query_map(params![folder_id], |row| row.get(0))?;
+                    rows.collect::<std::result::Result<Vec<String>, _>>()?
+                };
+                tx.execute("DELETE FROM wallpapers WHERE folder_id = ?1", params![folder_id])?;
+            } else {
+                tx.execute("UPDATE wallpapers SET folder_id = NULL WHERE folder_id = ?1", params![folder_id])?;
+            }
+            let deleted = tx.execute("DELETE FROM wallpaper_folders WHERE id = ?1", params![folder_id])?;
+            if deleted == 0 {
+                return Err(AppError::InvalidInput(format!("未找到壁纸文件夹: {}", folder_id)));
+            }
+            tx.commit()?;
+        }
+        SYNC_LOCKS.lock().unwrap().remove(&folder_id);
+        thumbnails.remove_sources(&removed_paths);
+        Ok(())
+    }
+
+    /// 同步所有文件夹，并检查不属于任何文件夹的壁纸文件是否还在；单个文件夹失败不影响其他文件夹
+    pub fn sync_all(&self, thumbnails: &ThumbnailCache) -> Result<Vec<WallpaperFolderSyncResult>> {
+        let mut results = vec![];
+        for folder in self.list_folders()? {
+            match self.sync_folder(thumbnails, folder.id) {
+                Ok(result) => results.push(result),
+                Err(e) => log::warn!("Failed to sync wallpaper folder {}: {}", folder.path, e),
+            }
+        }
+        self.flag_missing_unmanaged()?;
+        Ok(results)
+    }
+
+    /**
+     * 同步单个文件夹
+     * @description 文件系统遍历与图片解析在数据库锁外完成，只有内容变化（大小或修改时间不同）的图片会重新解析；
+     * 文件夹本身不存在时（如移动硬盘未连接）直接报错，不会把其中的壁纸全部标记为丢失；
+     * 同一文件夹的同步按顺序执行，避免基于过期的已有记录重复导入；按 remove 策略删除的记录会一并清理缩略图
+     */
+    pub fn sync_folder(&self, thumbnails: &ThumbnailCache, folder_id: i64) -> Result<WallpaperFolderSyncResult> {
+        let sync_lock = Arc::clone(SYNC_LOCKS.lock().unwrap().entry(folder_id).or_default());
+        let _guard = sync_lock.lock().unwrap();
+
+        let folder = self.get_folder(folder_id)?;
+        let root = Path::new(&folder.path);
+        if !root.is_dir() {
+            return Err(AppError::PathNotFound(folder.path.clone()));
+        }
+
+        let files = collect_images(root, folder.recursive);
+        let existing = self.load_existing(&folder)?;
+
+        let mut result = WallpaperFolderSyncResult {
+            folder_id,
+            ..Default::default()
+        };
+        let mut inserts = vec![];
+        let mut updates = vec![];
+        let mut restores = vec![];
+        let mut removals = vec![];
+        let mut detaches = vec![];
+        let mut seen = HashSet::new();
+
+        for path in &files {
+            let path_str = path.to_string_lossy().to_string();
+            seen.insert(path_str.clone());
+            let metadata = match path.metadata() {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
+            let modified_at = metadata
+                .modified()
+                .ok()
+                .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339());
+            let current = existing.get(&path_str);
+            let unchanged = current.is_some_and(|w| {
+                w.file_size == Some(metadata.len() as i64) && Some(&w.modified_at) == modified_at.as_ref()
+            });
+            if let (Some(current), true) = (current, unchanged) {
+                // 文件未变化时按记录中的宽高检查，修改后的分辨率要求同样生效
+                if !meets_resolution(&folder, current.width, current.height) {
+                    detaches.push(current.id.clone());
+                    continue;
+                }
+                if current.missing {
+                    restores.push(current.id.clone());
+                }
+                continue;
+            }
+
+            let file_name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("未知")
+                .to_string();
+            let mut wallpaper = read_wallpaper_file(path, &file_name, &path_str);
+            if !meets_resolution(&folder, wallpaper.width, wallpaper.height) {
+                match current {
+                    Some(current) => detaches.push(current.id.clone()),
+                    None => result.skipped += 1,
+                }
+                continue;
+            }
+            match current {
+                Some(current) => {
+                    wallpaper.id = current.id.clone();
+                    updates.push(wallpaper);
+                }
+                None => {
+                    wallpaper.folder_id = Some(folder_id);
+                    inserts.push(wallpaper);
+                }
+            }
+        }
+
+        let mut missing = vec![];
+        for (path, wallpaper) in &existing {
+            if seen.contains(path) {
+                continue;
+            }
+            if Path::new(path).is_file() {
+                // 文件还在，但因关闭了递归或被移入隐藏目录等原因不再属于该文件夹
+                detaches.push(wallpaper.id.clone());
+            } else if folder.missing_policy == MissingPolicy::Remove {
+                removals.push((wallpaper.id.clone(), path.clone()));
+            } else if !wallpaper.missing {
+                missing.push(wallpaper.id.clone());
+            }
+        }
+
+        let conn = self.db.get_connection();
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for wallpaper in &inserts {
+            // 一次性导入过的同一文件直接归入该文件夹，不重复插入
+            let adopted = tx.execute(
+                "UPDATE wallpapers SET folder_id = ?1, missing = 0, file_size = ?2, width = ?3, height = ?4, modified_at = ?5,
+                 perceptual_hash = ?6, hash_failed = ?6 IS NULL
+                 WHERE file_path = ?7 AND folder_id IS NULL",
+                params![
+                    folder_id,
+                    wallpaper.file_size,
+                    wallpaper.width,
+                    wallpaper.height,
+                    wallpaper.modified_at,
+                    wallpaper.perceptual_hash.map(|h| h as i64),
+                    wallpaper.file_path
+                ],
+            )?;
+            if adopted > 0 {
+                result.updated += 1;
+                continue;
+            }
+            // 路径已属于其他文件夹时保持原有归属
+            let inserted = tx.execute(
+                "INSERT INTO wallpapers (id, name, file_path, file_name, file_type, file_size, width, height, modified_at, created_at, folder_id, perceptual_hash, hash_failed)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?12 IS NULL)
+                 ON CONFLICT(file_path) DO NOTHING",
+                params![
+                    wallpaper.id,
+                    wallpaper.name,
+                    wallpaper.file_path,
+                    wallpaper.file_name,
+                    wallpaper.file_type,
+                    wallpaper.file_size,
+                    wallpaper.width,
+                    wallpaper.height,
+                    wallpaper.modified_at,
+                    wallpaper.created_at,
+                    folder_id,
+                    wallpaper.perceptual_hash.map(|h| h as i64)
+                ],
+            )?;
+            if inserted > 0 {
+                result.added += 1;
+            }
+        }
+        for wallpaper in &updates {
+            tx.execute(
+                "UPDATE wallpapers SET file_size = ?1, width = ?2, height = ?3, modified_at = ?4, perceptual_hash = ?5, hash_failed = ?5 IS NULL,
+                 missing = 0 WHERE id = ?6",
+                params![
+                    wallpaper.file_size,
+                    wallpaper.width,
+                    wallpaper.height,
+                    wallpaper.modified_at,
+                    wallpaper.perceptual_hash.map(|h| h as i64),
+                    wallpaper.id
+                ],
+            )?;
+            result.updated += 1;
+        }
+        for id in &restores {
+            tx.execute("UPDATE wallpapers SET missing = 0 WHERE id = ?1", params![id])?;
+            result.restored += 1;
+        }
+        for id in &missing {
+            tx.execute("UPDATE wallpapers SET missing = 1 WHERE id = ?1", params![id])?;
+            result.missing += 1;
+        }
+        for (id, _) in &removals {
+            tx.execute("DELETE FROM wallpapers WHERE id = ?1", params![id])?;
+            result.removed += 1;
+        }
+        // 解除关联后按手动导入的壁纸处理，重新满足条件时在下次同步中归入文件夹
+        for id in &detaches {
+            tx.execute("UPDATE wallpapers SET folder_id = NULL WHERE id = ?1", params![id])?;
+            result.detached += 1;
+        }
+        tx.execute(
+            "UPDATE wallpaper_folders SET last_synced_at = ?1 WHERE id = ?2",
+            params![chrono::Utc::now().to_rfc3339(), folder_id],
+        )?;
+        tx.commit()?;
+        drop(conn);
+
+        let removed_paths: Vec<String> = removals.into_iter().map(|(_, path)| path).collect();
+        thumbnails.remove_sources(&removed_paths);
+
+        log::info!(
+            "Synced wallpaper folder {}: {} added, {} updated, {} missing, {} restored, {} removed, {} detached, {} skipped",
+            folder.path,
+            result.added,
+            result.updated,
+            result.missing,
+            result.restored,
+            result.removed,
+            result.detached,
+            result.skipped
+        );
+        Ok(result)
+    }
+
+    fn load_existing(&self, folder: &WallpaperFolder) -> Result<HashMap<String, ExistingWallpaper>> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+        let mut stmt =
+            conn.prepare("SELECT id, file_path, file_size, width, height, modified_at, missing FROM wallpapers WHERE folder_id = ?1")?;
+        let rows = stmt.query_map(params![folder.id], |row| {
+            Ok((
+                row.get::<_, String>(1)?,
+                ExistingWallpaper {
+                    id: row.get(0)?,
+                    file_size: row.get(2)?,
+                    width: row.get(3)?,
+                    height: row.get(4)?,
+                    modified_at: row.get(5)?,
+                    missing: row.get(6)?,
+                },
+            ))
+        })?;
+        let existing = rows.collect::<std::result::Result<HashMap<_, _>, _>>()?;
+        Ok(existing)
+    }
+
+    /// 手动导入的壁纸不属于任何文件夹，只根据文件是否存在更新 missing 标记，不会删除
+    fn flag_missing_unmanaged(&self) -> Result<()> {
+        let rows: Vec<(String, String, bool)> = {
+            let conn = self.db.get_connection();
+            let conn = conn.lock().unwrap();
+            let mut stmt = conn.prepare("SELECT id, file_path, missing FROM wallpapers WHERE folder_id IS NULL")?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            rows
+        };
+        let changes: Vec<(String, bool)> = rows
+            .into_iter()
+            .filter_map(|(id, path, missing)| {
+                let now_missing = !Path::new(&path).is_file();
+                (now_missing != missing).then_some((id, now_missing))
+            })
+            .collect();
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        let conn = self.db.get_connection();
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for (id, missing) in &changes {
+            tx.execute("UPDATE wallpapers SET missing = ?1 WHERE id = ?2", params![missing, id])?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+/**
+ * 壁纸文件夹管理器
+ * @description 启动时在后台同步所有文件夹，并为开启了监听的文件夹启动去抖后的文件系统监听器，
+ * 文件变化后重新同步对应文件夹并通过 wallpapers://changed 通知前端
+ */
+pub struct WallpaperFolderManager {
+    watchers: Mutex<HashMap<i64, Debouncer<RecommendedWatcher>>>,
+}
+
+impl Default for WallpaperFolderManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WallpaperFolderManager {
+    pub fn new() -> Self {
+        WallpaperFolderManager {
+            watchers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 启动监听并在后台线程中同步所有文件夹，避免拖慢应用启动
+    pub fn restore<R: Runtime>(&self, app: &AppHandle<R>, db: Arc<Database>, thumbnails: Arc<ThumbnailCache>) -> Result<()> {
+        let service = WallpaperFolderService::new(db.clone());
+        for folder in service.list_folders()?.into_iter().filter(|f| f.watch_enabled) {
+            if let Err(e) = self.watch(app.clone(), db.clone(), thumbnails.clone(), &folder) {
+                log::warn!("Failed to watch wallpaper folder {}: {}", folder.path, e);
+            }
+        }
+
+        let app = app.clone();
+        std::thread::Builder::new()
+            .name("wallpaper-folder-sync".to_string())
+            .spawn(move || match service.sync_all(&thumbnails) {
+                Ok(results) => emit_changes(&app, &results),
+                Err(e) => log::warn!("Failed to sync wallpaper folders: {}", e),
+            })?;
+        Ok(())
+    }
+
+    /// 按文件夹当前设置开始或停止监听
+    pub fn apply<R: Runtime>(
+        &self,
+        app: AppHandle<R>,
+        db: Arc<Database>,
+        thumbnails: Arc<ThumbnailCache>,
+        folder: &WallpaperFolder,
+    ) -> Result<()> {
+        if folder.watch_enabled {
+            self.watch(app, db, thumbnails, folder)
+        } else {
+            self.unwatch(folder.id);
+            Ok(())
+        }
+    }
+
+    /// 开始监听文件夹，已在监听时会先停止旧的监听器
+    pub fn watch<R: Runtime>(
+        &self,
+        app: AppHandle<R>,
+        db: Arc<Database>,
+        thumbnails: Arc<ThumbnailCache>,
+        folder: &WallpaperFolder,
+    ) -> Result<()> {
+        let folder_id = folder.id;
+        let mut debouncer = new_debouncer(DEBOUNCE_TIMEOUT, move |result: DebounceEventResult| {
+            if let Err(e) = result {
+                log::warn!("Watcher error for wallpaper folder {}: {}", folder_id, e);
+                return;
+            }
+            match WallpaperFolderService::new(db.clone()).sync_folder(&thumbnails, folder_id) {
+                Ok(result) => emit_changes(&app, &[result]),
+                Err(e) => log::error!("Failed to sync wallpaper folder {}: {}", folder_id, e),
+            }
+        })
+        .map_err(|e| AppError::Internal(format!("Failed to create watcher: {}", e)))?;
+
+        let mode = if folder.recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        debouncer
+            .watcher()
+            .watch(Path::new(&folder.path), mode)
+            .map_err(|e| AppError::Internal(format!("Failed to watch directory: {}", e)))?;
+
+        log::info!("Watching wallpaper folder {}", folder.path);
+        self.watchers.lock().unwrap().insert(folder_id, debouncer);
+        Ok(())
+    }
+
+    pub fn unwatch(&self, folder_id: i64) {
+        if self.watchers.lock().unwrap().remove(&folder_id).is_some() {
+            log::info!("Stopped watching wallpaper folder {}", folder_id);
+        }
+    }
+}
+
+/// 只推送有变化的同步结果
+pub fn emit_changes<R: Runtime>(app: &AppHandle<R>, results: &[WallpaperFolderSyncResult]) {
+    for result in results.iter().filter(|r| r.changed()) {
+        let _ = app.emit(WALLPAPERS_CHANGED_EVENT, result.clone());
+    }
+}
+
+fn map_folder(row: &Row) -> rusqlite::Result<WallpaperFolder> {
+    Ok(WallpaperFolder {
+        id: row.get(0)?,
+        path: row.get(1)?,
+        recursive: row.get(2)?,
+        min_width: row.get(3)?,
+        min_height: row.get(4)?,
+        watch_enabled: row.get(5)?,
+        missing_policy: MissingPolicy::parse(&row.get::<_, String>(6)?),
+        last_synced_at: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+/// 统一为绝对路径，避免同一文件夹以不同写法重复添加
+fn normalize_folder_path(path: &str) -> Result<String> {
+    let path = Path::new(path.trim());
+    if !path.is_dir() {
+        return Err(AppError::PathNotFound(path.display().to_string()));
+    }
+    let canonical = dunce_canonicalize(path)?;
+    Ok(canonical.to_string_lossy().to_string())
+}
+
+/// Windows 上 canonicalize 会返回 \\?\ 前缀的路径，与导入时记录的普通路径不一致，需去掉前缀
+fn dunce_canonicalize(path: &Path) -> Result<PathBuf> {
+    let canonical = path.canonicalize()?;
+    let text = canonical.to_string_lossy();
+    Ok(match text.strip_prefix(r"\\?\") {
+        Some(stripped) if !stripped.starts_with("UNC\\") => PathBuf::from(stripped),
+        _ => canonical,
+    })
+}
+
+fn validate_resolution(input: &WallpaperFolderInput) -> Result<()> {
+    if input.min_width.is_some_and(|w| w < 0) || input.min_height.is_some_and(|h| h < 0) {
+        return Err(AppError::InvalidInput("最小分辨率不能为负数".to_string()));
+    }
+    Ok(())
+}
+
+fn meets_resolution(folder: &WallpaperFolder, width: Option<i32>, height: Option<i32>) -> bool {
+    let meets = |minimum: Option<i32>, value: Option<i32>| match minimum.filter(|m| *m > 0) {
+        Some(minimum) => value.is_some_and(|v| v >= minimum),
+        None => true,
+    };
+    meets(folder.min_width, width) && meets(folder.min_height, height)
+}
+
+/// 收集文件夹中的图片，跳过隐藏文件与隐藏目录（如下载中的临时文件目录）
+fn collect_images(root: &Path, recursive: bool) -> Vec<PathBuf> {
+    WalkBuilder::new(root)
+        .standard_filters(false)
+        .hidden(true)
+        .max_depth(if recursive { None } else { Some(1) })
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|ext| THUMBNAIL_EXTENSIONS.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+        })
+        .collect()
+}
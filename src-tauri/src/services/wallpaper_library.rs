@@ -0,0 +1,405 @@
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use rusqlite::{params, OptionalExtension, Row, Transaction};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// 标签名与合集名的最大长度（字符数）
+const MAX_NAME_CHARS: usize = 50;
+/// 评分上限，0 表示未评分
+const MAX_RATING: i32 = 5;
+
+const COLLECTION_COLUMNS: &str = "c.id, c.name, c.description, c.created_at, c.updated_at,
+     (SELECT COUNT(*) FROM wallpaper_collection_items i WHERE i.collection_id = c.id),
+     (SELECT i.wallpaper_id FROM wallpaper_collection_items i WHERE i.collection_id = c.id
+      ORDER BY i.added_at, i.wallpaper_id LIMIT 1)";
+
+/// 本地壁纸标签
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WallpaperTag {
+    pub id: i64,
+    pub name: String,
+    pub wallpaper_count: i64,
+}
+
+/// 本地壁纸合集
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WallpaperCollection {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub wallpaper_count: i64,
+    /// 最早加入合集的壁纸，用作封面
+    pub cover_wallpaper_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// 创建或修改合集时的参数
+#[derive(Debug, Clone, Deserialize)]
+pub struct WallpaperCollectionInput {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/**
+ * 壁纸库整理服务
+ * @description 管理本地壁纸的标签、收藏、评分与合集；删除壁纸时关联记录由外键一并删除
+ */
+pub struct WallpaperLibraryService {
+    db: Arc<Database>,
+}
+
+impl WallpaperLibraryService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// 获取所有标签及其壁纸数量，按名称排序
+    pub fn list_tags(&self) -> Result<Vec<WallpaperTag>> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.name, COUNT(l.wallpaper_id) FROM wallpaper_tags t
+             LEFT JOIN wallpaper_tag_links l ON l.tag_id = t.id
+             GROUP BY t.id
+             ORDER BY t.name COLLATE NOCASE",
+        )?;
+        let tags = stmt
+            .query_map([], |row| {
+                Ok(WallpaperTag {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    wallpaper_count: row.get(2)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(tags)
+    }
+
+    /// 用给定的标签替换壁纸的全部标签，不存在的标签自动创建，不再被使用的标签自动删除
+    pub fn set_wallpaper_tags(&self, wallpaper_id: &str, names: &[String]) -> Result<()> {
+        let names = normalize_names(names)?;
+        let conn = self.db.get_connection();
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        ensure_wallpapers_exist(&tx, &[wallpaper_id.to_string()])?;
+        tx.execute("DELETE FROM wallpaper_tag_links WHERE wallpaper_id = ?1", params![wallpaper_id])?;
+        for name in &names {
+            let tag_id = upsert_tag(&tx, name)?;
+            tx.execute(
+                "INSERT OR IGNORE INTO wallpaper_tag_links (wallpaper_id, tag_id) VALUES (?1, ?2)",
+                params![wallpaper_id, tag_id],
+            )?;
+        }
+        prune_tags(&tx)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 为多张壁纸添加标签，保留已有标签
+    pub fn add_wallpaper_tags(&self, wallpaper_ids: &[String], names: &[String]) -> Result<()> {
+        let names = normalize_names(names)?;
+        let conn = self.db.get_connection();
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        ensure_wallpapers_exist(&tx, wallpaper_ids)?;
+        for name in &names {
+            let tag_id = upsert_tag(&tx, name)?;
+            for wallpaper_id in wallpaper_ids {
+                tx.execute(
+                    "INSERT OR IGNORE INTO wallpaper_tag_links (wallpaper_id, tag_id) VALUES (?1, ?2)",
+                    params![wallpaper_id, tag_id],
+                )?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 从多张壁纸上移除标签，不再被使用的标签自动删除
+    pub fn remove_wallpaper_tags(&self, wallpaper_ids: &[String], tag_ids: &[i64]) -> Result<()> {
+        let conn = self.db.get_connection();
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for wallpaper_id in wallpaper_ids {
+            for tag_id in tag_ids {
+                tx.execute(
+                    "DELETE FROM wallpaper_tag_links WHERE wallpaper_id = ?1 AND tag_id = ?2",
+                    params![wallpaper_id, tag_id],
+                )?;
+            }
+        }
+        prune_tags(&tx)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 重命名标签；新名称与其他标签重复时合并到该标签
+    pub fn rename_tag(&self, tag_id: i64, name: &str) -> Result<()> {
+        let name = normalize_name(name)?;
+        let conn = self.db.get_connection();
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let existing: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM wallpaper_tags WHERE name = ?1 AND id != ?2",
+                params![name, tag_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        match existing {
+            Some(target_id) => {
+                tx.execute(
+                    "INSERT OR IGNORE INTO wallpaper_tag_links (wallpaper_id, tag_id)
+                     SELECT wallpaper_id, ?1 FROM wallpaper_tag_links WHERE tag_id = ?2",
+                    params![target_id, tag_id],
+                )?;
+                tx.execute("DELETE FROM wallpaper_tags WHERE id = ?1", params![tag_id])?;
+            }
+            None => {
+                let updated = tx.execute("UPDATE wallpaper_tags SET name = ?1 WHERE id = ?2", params![name, tag_id])?;
+                if updated == 0 {
+                    return Err(AppError::InvalidInput(format!("未找到标签: {}", tag_id)));
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 删除标签并从所有壁纸上移除
+    pub fn delete_tag(&self, tag_id: i64) -> Result<()> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+        conn.execute("DELETE FROM wallpaper_tags WHERE id = ?1", params![tag_id])?;
+        Ok(())
+    }
+
+    pub fn set_favorite(&self, wallpaper_ids: &[String], favorite: bool) -> Result<()> {
+        let conn = self.db.get_connection();
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for wallpaper_id in wallpaper_ids {
+            tx.execute("UPDATE wallpapers SET favorite = ?1 WHERE id = ?2", params![favorite, wallpaper_id])?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 设置评分，0 表示清除评分
+    pub fn set_rating(&self, wallpaper_ids: &[String], rating: i32) -> Result<()> {
+        if !(0..=MAX_RATING).contains(&rating) {
+            return Err(AppError::InvalidInput(format!("评分需在 0-{} 之间", MAX_RATING)));
+        }
+        let conn = self.db.get_connection();
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for wallpaper_id in wallpaper_ids {
+            tx.execute("UPDATE wallpapers SET rating = ?1 WHERE id = ?2", params![rating, wallpaper_id])?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 获取所有合集，按名称排序
+    pub fn list_collections(&self) -> Result<Vec<WallpaperCollection>> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM wallpaper_collections c ORDER BY c.name COLLATE NOCASE",
+            COLLECTION_COLUMNS
+        ))?;
+        let collections = stmt
+            .query_map([], map_collection)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(collections)
+    }
+
+    pub fn get_collection(&self, collection_id: i64) -> Result<WallpaperCollection> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+        conn.query_row(
+            &format!("SELECT {} FROM wallpaper_collections c WHERE c.id = ?1", COLLECTION_COLUMNS),
+            params![collection_id],
+            map_collection,
+        )
+        .optional()?
+        .ok_or_else(|| AppError::InvalidInput(format!("未找到合集: {}", collection_id)))
+    }
+
+    pub fn create_collection(&self, input: &WallpaperCollectionInput) -> Result<WallpaperCollection> {
+        let name = normalize_name(&input.name)?;
+        let collection_id = {
+            let conn = self.db.get_connection();
+            let conn = conn.lock().unwrap();
+            ensure_collection_name_available(&conn, &name, None)?;
+            conn.execute(
+                "INSERT INTO wallpaper_collections (name, description) VALUES (?1, ?2)",
+                params![name, normalize_description(&input.description)],
+            )?;
+            conn.last_insert_rowid()
+        };
+        self.get_collection(collection_id)
+    }
+
+    pub fn update_collection(&self, collection_id: i64, input: &WallpaperCollectionInput) -> Result<WallpaperCollection> {
+        let name = normalize_name(&input.name)?;
+        {
+            let conn = self.db.get_connection();
+            let conn = conn.lock().unwrap();
+            ensure_collection_name_available(&conn, &name, Some(collection_id))?;
+            let updated = conn.execute(
+                "UPDATE wallpaper_collections SET name = ?1, description = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?3",
+                params![name, normalize_description(&input.description), collection_id],
+            )?;
+            if updated == 0 {
+                return Err(AppError::InvalidInput(format!("未找到合集: {}", collection_id)));
+            }
+        }
+        self.get_collection(collection_id)
+    }
+
+    /// 删除合集，不会删除其中的壁纸
+    pub fn delete_collection(&self, collection_id: i64) -> Result<()> {
+        let conn = self.db.get_connection();
+        let conn = conn.lock().unwrap();
+        conn.execute("DELETE FROM wallpaper_collections WHERE id = ?1", params![collection_id])?;
+        Ok(())
+    }
+
+    /// 将壁纸加入合集，已在合集中的壁纸保持原来的加入时间
+    pub fn add_to_collection(&self, collection_id: i64, wallpaper_ids: &[String]) -> Result<WallpaperCollection> {
+        {
+            let conn = self.db.get_connection();
+            let mut conn = conn.lock().unwrap();
+            let tx = conn.transaction()?;
+            ensure_collection_exists(&tx, collection_id)?;
+            ensure_wallpapers_exist(&tx, wallpaper_ids)?;
+            for wallpaper_id in wallpaper_ids {
+                tx.execute(
+                    "INSERT OR IGNORE INTO wallpaper_collection_items (collection_id, wallpaper_id) VALUES (?1, ?2)",
+                    params![collection_id, wallpaper_id],
+                )?;
+            }
+            touch_collection(&tx, collection_id)?;
+            tx.commit()?;
+        }
+        self.get_collection(collection_id)
+    }
+
+    pub fn remove_from_collection(&self, collection_id: i64, wallpaper_ids: &[String]) -> Result<WallpaperCollection> {
+        {
+            let conn = self.db.get_connection();
+            let mut conn = conn.lock().unwrap();
+            let tx = conn.transaction()?;
+            ensure_collection_exists(&tx, collection_id)?;
+            for wallpaper_id in wallpaper_ids {
+                tx.execute(
+                    "DELETE FROM wallpaper_collection_items WHERE collection_id = ?1 AND wallpaper_id = ?2",
+                    params![collection_id, wallpaper_id],
+                )?;
+            }
+            touch_collection(&tx, collection_id)?;
+            tx.commit()?;
+        }
+        self.get_collection(collection_id)
+    }
+}
+
+fn map_collection(row: &Row) -> rusqlite::Result<WallpaperCollection> {
+    Ok(WallpaperCollection {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+        wallpaper_count: row.get(5)?,
+        cover_wallpaper_id: row.get(6)?,
+    })
+}
+
+fn normalize_name(name: &str) -> Result<String> {
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        return Err(AppError::InvalidInput("名称不能为空".to_string()));
+    }
+    if name.chars().count() > MAX_NAME_CHARS {
+        return Err(AppError::InvalidInput(format!("名称不能超过 {} 个字符", MAX_NAME_CHARS)));
+    }
+    Ok(name)
+}
+
+/// 去掉空白的标签名，并按不区分大小写去重
+fn normalize_names(names: &[String]) -> Result<Vec<String>> {
+    let mut normalized: Vec<String> = vec![];
+    for name in names {
+        if name.trim().is_empty() {
+            continue;
+        }
+        let name = normalize_name(name)?;
+        if !normalized.iter().any(|n| n.to_lowercase() == name.to_lowercase()) {
+            normalized.push(name);
+        }
+    }
+    Ok(normalized)
+}
+
+fn normalize_description(description: &Option<String>) -> Option<String> {
+    description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(str::to_string)
+}
+
+/// 返回同名标签的ID（名称不区分大小写），不存在时创建
+fn upsert_tag(tx: &Transaction, name: &str) -> Result<i64> {
+    tx.execute("INSERT OR IGNORE INTO wallpaper_tags (name) VALUES (?1)", params![name])?;
+    let tag_id = tx.query_row("SELECT id FROM wallpaper_tags WHERE name = ?1", params![name], |row| row.get(0))?;
+    Ok(tag_id)
+}
+
+fn prune_tags(tx: &Transaction) -> Result<()> {
+    tx.execute(
+        "DELETE FROM wallpaper_tags WHERE id NOT IN (SELECT tag_id FROM wallpaper_tag_links)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn ensure_wallpapers_exist(tx: &Transaction, wallpaper_ids: &[String]) -> Result<()> {
+    let mut stmt = tx.prepare("SELECT 1 FROM wallpapers WHERE id = ?1")?;
+    for wallpaper_id in wallpaper_ids {
+        if !stmt.exists(params![wallpaper_id])? {
+            return Err(AppError::InvalidInput(format!("未找到壁纸: {}", wallpaper_id)));
+        }
+    }
+    Ok(())
+}
+
+fn ensure_collection_exists(tx: &Transaction, collection_id: i64) -> Result<()> {
+    if !tx.prepare("SELECT 1 FROM wallpaper_collections WHERE id = ?1")?.exists(params![collection_id])? {
+        return Err(AppError::InvalidInput(format!("未找到合集: {}", collection_id)));
+    }
+    Ok(())
+}
+
+fn ensure_collection_name_available(conn: &rusqlite::Connection, name: &str, except_id: Option<i64>) -> Result<()> {
+    let exists = conn
+        .prepare("SELECT 1 FROM wallpaper_collections WHERE name = ?1 AND id IS NOT ?2")?
+        .exists(params![name, except_id])?;
+    if exists {
+        return Err(AppError::InvalidInput(format!("合集已存在: {}", name)));
+    }
+    Ok(())
+}
+
+fn touch_collection(tx: &Transaction, collection_id: i64) -> Result<()> {
+    tx.execute(
+        "UPDATE wallpaper_collections SET updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+        params![collection_id],
+    )?;
+    Ok(())
+}
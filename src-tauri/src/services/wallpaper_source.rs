@@ -0,0 +1,606 @@
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::services::wallpaper::{RemoteWallpaper, WallpaperCategory, WallpaperStationResponse};
+use reqwest::Url;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// 壁纸源配置存放在 app_settings 中的键
+const SETTINGS_KEY: &str = "wallpaper_sources";
+
+/// 订阅内容的缓存时间，浏览、翻页和搜索都基于同一份缓存
+const FEED_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const MAX_PAGE_SIZE: u32 = 100;
+/// 表示不按分类筛选的分类 ID
+const ALL_CATEGORY_ID: &str = "all";
+const MEDIA_RSS_NS: &str = "http://search.yahoo.com/mrss/";
+
+/// 在线壁纸源
+/// @description 每个实现对应一种壁纸站或订阅格式；方法会发起网络请求，调用方需在阻塞线程中调用
+pub trait WallpaperSource: Send + Sync {
+    /// 分类列表，第一项为"全部"
+    fn list_categories(&self) -> Result<Vec<WallpaperCategory>>;
+
+    /// 分页浏览，category_id 为空或为 "all" 时不按分类筛选；页码从 1 开始
+    fn browse(&self, category_id: Option<&str>, page: u32, page_size: u32) -> Result<WallpaperStationResponse>;
+
+    /// 按关键词搜索，匹配名称、作者、分类和标签
+    fn search(&self, keyword: &str, page: u32, page_size: u32) -> Result<WallpaperStationResponse>;
+
+    /// 返回壁纸原图的下载地址
+    fn resolve_download_url(&self, wallpaper_id: &str) -> Result<String>;
+}
+
+/// 壁纸源类型，新增的实现需要在此登记并在 create_source 中创建
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WallpaperSourceKind {
+    /// JSON 订阅，格式见 parse_json_feed
+    JsonFeed,
+    /// RSS 2.0 或 Atom 订阅，图片取自 media:content、enclosure 等
+    RssFeed,
+}
+
+/// 壁纸源配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WallpaperSourceConfig {
+    /// 为空时添加时自动生成
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub kind: WallpaperSourceKind,
+    pub url: String,
+}
+
+/// 已配置的壁纸源及当前选中的壁纸源
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WallpaperSourceSettings {
+    pub active_id: Option<String>,
+    pub sources: Vec<WallpaperSourceConfig>,
+}
+
+fn create_source(config: &WallpaperSourceConfig) -> Result<Arc<dyn WallpaperSource>> {
+    match config.kind {
+        WallpaperSourceKind::JsonFeed | WallpaperSourceKind::RssFeed => Ok(Arc::new(FeedSource::new(config)?)),
+    }
+}
+
+/// 壁纸源注册表
+/// @description 按设置创建各壁纸源实例，壁纸站相关命令都通过当前选中的壁纸源获取数据
+pub struct WallpaperSourceRegistry {
+    inner: RwLock<RegistryState>,
+}
+
+struct RegistryState {
+    settings: WallpaperSourceSettings,
+    sources: Vec<(String, Arc<dyn WallpaperSource>)>,
+}
+
+/// 未配置任何壁纸源的注册表，设置无法加载时使用
+impl Default for WallpaperSourceRegistry {
+    fn default() -> Self {
+        WallpaperSourceRegistry {
+            inner: RwLock::new(RegistryState {
+                settings: WallpaperSourceSettings::default(),
+                sources: vec![],
+            }),
+        }
+    }
+}
+
+impl WallpaperSourceRegistry {
+    /// 从数据库加载设置，无法创建的壁纸源会被跳过并记录日志
+    pub fn load(db: &Database) -> Result<Self> {
+        let settings = load_settings(db)?;
+        let sources = settings
+            .sources
+            .iter()
+            .filter_map(|config| match create_source(config) {
+                Ok(source) => Some((config.id.clone(), source)),
+                Err(e) => {
+                    log::warn!("Skipping wallpaper source {}: {}", config.id, e);
+                    None
+                }
+            })
+            .collect();
+        Ok(WallpaperSourceRegistry {
+            inner: RwLock::new(RegistryState { settings, sources }),
+        })
+    }
+
+    pub fn settings(&self) -> WallpaperSourceSettings {
+        self.inner.read().unwrap().settings.clone()
+    }
+
+    /// 当前选中的壁纸源，未配置时返回 None
+    pub fn active(&self) -> Option<Arc<dyn WallpaperSource>> {
+        let inner = self.inner.read().unwrap();
+        let active_id = inner.settings.active_id.as_deref()?;
+        inner
+            .sources
+            .iter()
+            .find(|(id, _)| id == active_id)
+            .map(|(_, source)| Arc::clone(source))
+    }
+
+    /// 添加壁纸源，ID 已存在时替换原配置；尚未选中壁纸源时自动选中新添加的
+    pub fn save_source(&self, db: &Database, mut config: WallpaperSourceConfig) -> Result<WallpaperSourceSettings> {
+        config.name = config.name.trim().to_string();
+        if config.name.is_empty() {
+            return Err(AppError::InvalidInput("壁纸源名称不能为空".to_string()));
+        }
+        if config.id.trim().is_empty() {
+            config.id = Uuid::new_v4().to_string();
+        }
+        let source = create_source(&config)?;
+
+        let mut inner = self.inner.write().unwrap();
+        let mut settings = inner.settings.clone();
+        match settings.sources.iter_mut().find(|s| s.id == config.id) {
+            Some(existing) => *existing = config.clone(),
+            None => settings.sources.push(config.clone()),
+        }
+        if settings.active_id.is_none() {
+            settings.active_id = Some(config.id.clone());
+        }
+        save_settings(db, &settings)?;
+
+        inner.sources.retain(|(id, _)| *id != config.id);
+        inner.sources.push((config.id, source));
+        inner.settings = settings.clone();
+        Ok(settings)
+    }
+
+    /// 删除壁纸源，删除的是当前选中的壁纸源时改为选中剩下的第一个
+    pub fn remove_source(&self, db: &Database, source_id: &str) -> Result<WallpaperSourceSettings> {
+        let mut inner = self.inner.write().unwrap();
+        let mut settings = inner.settings.clone();
+        settings.sources.retain(|s| s.id != source_id);
+        if settings.active_id.as_deref() == Some(source_id) {
+            settings.active_id = settings.sources.first().map(|s| s.id.clone());
+        }
+        save_settings(db, &settings)?;
+
+        inner.sources.retain(|(id, _)| id != source_id);
+        inner.settings = settings.clone();
+        Ok(settings)
+    }
+
+    pub fn set_active(&self, db: &Database, source_id: &str) -> Result<WallpaperSourceSettings> {
+        let mut inner = self.inner.write().unwrap();
+        if !inner.settings.sources.iter().any(|s| s.id == source_id) {
+            return Err(AppError::InvalidInput(format!("未找到壁纸源: {}", source_id)));
+        }
+        let mut settings = inner.settings.clone();
+        settings.active_id = Some(source_id.to_string());
+        save_settings(db, &settings)?;
+        inner.settings = settings.clone();
+        Ok(settings)
+    }
+}
+
+/// 解析后的订阅内容
+struct Feed {
+    categories: Vec<WallpaperCategory>,
+    wallpapers: Vec<RemoteWallpaper>,
+}
+
+/// 基于 JSON 或 RSS 订阅的通用壁纸源
+/// @description 订阅地址返回完整的壁纸列表，分类、分页与搜索都在本地完成
+pub struct FeedSource {
+    kind: WallpaperSourceKind,
+    url: Url,
+    client: OnceLock<reqwest::blocking::Client>,
+    cache: Mutex<Option<(Instant, Arc<Feed>)>>,
+}
+
+impl FeedSource {
+    pub fn new(config: &WallpaperSourceConfig) -> Result<Self> {
+        let url = Url::parse(config.url.trim())
+            .map_err(|e| AppError::InvalidInput(format!("无效的订阅地址 {}: {}", config.url, e)))?;
+        if url.scheme() != "http" && url.scheme() != "https" {
+            return Err(AppError::InvalidInput(format!("订阅地址只支持 http 或 https: {}", config.url)));
+        }
+        Ok(FeedSource {
+            kind: config.kind,
+            url,
+            client: OnceLock::new(),
+            cache: Mutex::new(None),
+        })
+    }
+
+    /// 阻塞客户端不能在异步上下文中创建，因此推迟到第一次请求时（已位于阻塞线程中）再创建
+    fn client(&self) -> Result<&reqwest::blocking::Client> {
+        if let Some(client) = self.client.get() {
+            return Ok(client);
+        }
+        let client = reqwest::blocking::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("local-resource-manager/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        Ok(self.client.get_or_init(|| client))
+    }
+
+    /// 获取订阅内容，缓存过期前直接返回缓存
+    fn feed(&self) -> Result<Arc<Feed>> {
+        let mut cache = self.cache.lock().unwrap();
+        if let Some((fetched_at, feed)) = cache.as_ref() {
+            if fetched_at.elapsed() < FEED_CACHE_TTL {
+                return Ok(Arc::clone(feed));
+            }
+        }
+
+        let body = self
+            .client()?
+            .get(self.url.clone())
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.text())
+            .map_err(|e| AppError::Network(format!("获取订阅 {} 失败: {}", self.url, e)))?;
+        let feed = match self.kind {
+            WallpaperSourceKind::JsonFeed => parse_json_feed(&body, &self.url)?,
+            WallpaperSourceKind::RssFeed => parse_rss_feed(&body, &self.url)?,
+        };
+        let feed = Arc::new(feed);
+        *cache = Some((Instant::now(), Arc::clone(&feed)));
+        Ok(feed)
+    }
+}
+
+impl WallpaperSource for FeedSource {
+    fn list_categories(&self) -> Result<Vec<WallpaperCategory>> {
+        let feed = self.feed()?;
+        let mut categories = vec![WallpaperCategory {
+            id: ALL_CATEGORY_ID.to_string(),
+            name: "全部".to_string(),
+            icon: None,
+            color: None,
+            count: Some(feed.wallpapers.len() as i32),
+        }];
+        categories.extend(feed.categories.iter().cloned());
+        Ok(categories)
+    }
+
+    fn browse(&self, category_id: Option<&str>, page: u32, page_size: u32) -> Result<WallpaperStationResponse> {
+        let feed = self.feed()?;
+        let category_id = category_id.filter(|id| *id != ALL_CATEGORY_ID);
+        let matches = feed
+            .wallpapers
+            .iter()
+            .filter(|w| category_id.is_none() || w.category_id.as_deref() == category_id);
+        Ok(paginate(matches, page, page_size))
+    }
+
+    fn search(&self, keyword: &str, page: u32, page_size: u32) -> Result<WallpaperStationResponse> {
+        let feed = self.feed()?;
+        let keyword = keyword.trim().to_lowercase();
+        let matches = feed.wallpapers.iter().filter(|w| {
+            let contains = |value: &str| value.to_lowercase().contains(&keyword);
+            contains(&w.name)
+                || w.author.as_deref().is_some_and(contains)
+                || w.category_name.as_deref().is_some_and(contains)
+                || w.tags.iter().any(|tag| contains(tag))
+        });
+        Ok(paginate(matches, page, page_size))
+    }
+
+    fn resolve_download_url(&self, wallpaper_id: &str) -> Result<String> {
+        self.feed()?
+            .wallpapers
+            .iter()
+            .find(|w| w.id == wallpaper_id)
+            .map(|w| w.original_url.clone())
+            .ok_or_else(|| AppError::InvalidInput(format!("壁纸源中未找到壁纸: {}", wallpaper_id)))
+    }
+}
+
+fn paginate<'a>(matches: impl Iterator<Item = &'a RemoteWallpaper>, page: u32, page_size: u32) -> WallpaperStationResponse {
+    let page = page.max(1);
+    let page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+    let matches: Vec<&RemoteWallpaper> = matches.collect();
+    let start = ((page - 1) as usize).saturating_mul(page_size as usize);
+    let wallpapers: Vec<RemoteWallpaper> = matches.iter().skip(start).take(page_size as usize).map(|w| (*w).clone()).collect();
+    WallpaperStationResponse {
+        has_more: start + wallpapers.len() < matches.len(),
+        total: matches.len() as i32,
+        wallpapers,
+        page,
+        page_size,
+    }
+}
+
+/// 订阅中的一张壁纸，缺少的字段在 into_remote 中补全
+#[derive(Default)]
+struct FeedItem {
+    id: Option<String>,
+    name: Option<String>,
+    url: String,
+    thumbnail_url: Option<String>,
+    width: Option<i32>,
+    height: Option<i32>,
+    file_size: Option<i64>,
+    category_id: Option<String>,
+    author: Option<String>,
+    tags: Vec<String>,
+}
+
+impl FeedItem {
+    /// 相对地址按订阅地址解析；没有 ID 时用原图地址的哈希代替，保证刷新订阅后 ID 不变
+    fn into_remote(self, base: &Url) -> Option<RemoteWallpaper> {
+        let original_url = base.join(self.url.trim()).ok()?;
+        let id = self
+            .id
+            .filter(|id| !id.trim().is_empty())
+            .unwrap_or_else(|| blake3::hash(original_url.as_str().as_bytes()).to_hex()[..16].to_string());
+        let name = self
+            .name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .or_else(|| {
+                original_url
+                    .path_segments()
+                    .and_then(|mut segments| segments.next_back())
+                    .filter(|segment| !segment.is_empty())
+                    .map(str::to_string)
+            })
+            .unwrap_or_else(|| id.clone());
+        Some(RemoteWallpaper {
+            id,
+            name,
+            file_path: String::new(),
+            thumbnail_url: self.thumbnail_url.and_then(|url| base.join(url.trim()).ok()).map(String::from),
+            original_url: original_url.into(),
+            width: self.width.unwrap_or(0),
+            height: self.height.unwrap_or(0),
+            file_size: self.file_size.unwrap_or(0),
+            category_id: self.category_id.filter(|id| !id.trim().is_empty()),
+            category_name: None,
+            author: self.author.filter(|author| !author.trim().is_empty()),
+            tags: self.tags,
+        })
+    }
+}
+
+/// 组装订阅内容：补全分类名称与数量，订阅中未声明的分类按壁纸上的分类 ID 生成
+fn build_feed(declared: Vec<WallpaperCategory>, items: Vec<FeedItem>, base: &Url) -> Feed {
+    let mut wallpapers: Vec<RemoteWallpaper> = items.into_iter().filter_map(|item| item.into_remote(base)).collect();
+
+    let mut counts: BTreeMap<String, i32> = BTreeMap::new();
+    for wallpaper in &wallpapers {
+        if let Some(category_id) = &wallpaper.category_id {
+            *counts.entry(category_id.clone()).or_default() += 1;
+        }
+    }
+    let mut categories = declared;
+    for (category_id, _) in counts.iter() {
+        if !categories.iter().any(|c| &c.id == category_id) {
+            categories.push(WallpaperCategory {
+                id: category_id.clone(),
+                name: category_id.clone(),
+                icon: None,
+                color: None,
+                count: None,
+            });
+        }
+    }
+    for category in &mut categories {
+        category.count = Some(counts.get(&category.id).copied().unwrap_or(0));
+    }
+    for wallpaper in &mut wallpapers {
+        wallpaper.category_name = wallpaper
+            .category_id
+            .as_ref()
+            .and_then(|id| categories.iter().find(|c| &c.id == id))
+            .map(|c| c.name.clone());
+    }
+
+    Feed { categories, wallpapers }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonFeed {
+    Object {
+        #[serde(default)]
+        categories: Vec<JsonFeedCategory>,
+        wallpapers: Vec<JsonFeedItem>,
+    },
+    List(Vec<JsonFeedItem>),
+}
+
+#[derive(Deserialize)]
+struct JsonFeedCategory {
+    id: String,
+    name: String,
+    #[serde(default)]
+    icon: Option<String>,
+    #[serde(default)]
+    color: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JsonFeedItem {
+    #[serde(default)]
+    id: Option<serde_json::Value>,
+    #[serde(default, alias = "title")]
+    name: Option<String>,
+    #[serde(alias = "original_url", alias = "download_url")]
+    url: String,
+    #[serde(default, alias = "thumbnail", alias = "thumb")]
+    thumbnail_url: Option<String>,
+    #[serde(default)]
+    width: Option<i32>,
+    #[serde(default)]
+    height: Option<i32>,
+    #[serde(default)]
+    file_size: Option<i64>,
+    #[serde(default, alias = "category")]
+    category_id: Option<String>,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// 解析 JSON 订阅，支持两种格式：
+/// - 对象：{ "categories": [{ "id", "name", "icon"?, "color"? }], "wallpapers": [...] }，categories 可省略
+/// - 数组：直接为壁纸列表
+///
+/// 壁纸只有 url（或 original_url、download_url）是必填的，其余字段为 id、name（或 title）、
+/// thumbnail_url（或 thumbnail、thumb）、width、height、file_size、category_id（或 category）、author、tags
+fn parse_json_feed(body: &str, base: &Url) -> Result<Feed> {
+    let feed: JsonFeed =
+        serde_json::from_str(body).map_err(|e| AppError::InvalidInput(format!("无法解析 JSON 订阅: {}", e)))?;
+    let (categories, items) = match feed {
+        JsonFeed::Object { categories, wallpapers } => (categories, wallpapers),
+        JsonFeed::List(wallpapers) => (vec![], wallpapers),
+    };
+    let categories = categories
+        .into_iter()
+        .map(|c| WallpaperCategory {
+            id: c.id,
+            name: c.name,
+            icon: c.icon,
+            color: c.color,
+            count: None,
+        })
+        .collect();
+    let items = items
+        .into_iter()
+        .map(|item| FeedItem {
+            // 兼容数字 ID
+            id: item.id.map(|id| match id {
+                serde_json::Value::String(id) => id,
+                other => other.to_string(),
+            }),
+            name: item.name,
+            url: item.url,
+            thumbnail_url: item.thumbnail_url,
+            width: item.width,
+            height: item.height,
+            file_size: item.file_size,
+            category_id: item.category_id,
+            author: item.author,
+            tags: item.tags,
+        })
+        .collect();
+    Ok(build_feed(categories, items, base))
+}
+
+/// 解析 RSS 2.0 或 Atom 订阅
+/// 图片依次取 media:content、enclosure（RSS）或 rel="enclosure" 的 link（Atom），没有图片的条目会被忽略；
+/// 第一个 category 作为分类，其余 category 与 media:keywords 作为标签
+fn parse_rss_feed(body: &str, base: &Url) -> Result<Feed> {
+    let document =
+        roxmltree::Document::parse(body).map_err(|e| AppError::InvalidInput(format!("无法解析 RSS 订阅: {}", e)))?;
+
+    let mut items = vec![];
+    for node in document
+        .descendants()
+        .filter(|n| n.is_element() && matches!(n.tag_name().name(), "item" | "entry"))
+    {
+        let children: Vec<roxmltree::Node> = node.children().filter(|c| c.is_element()).collect();
+        let child = |name: &str| children.iter().find(|c| c.tag_name().name() == name && !is_media(c));
+        let media = |name: &str| children.iter().find(|c| c.tag_name().name() == name && is_media(c));
+        let text = |node: &roxmltree::Node| node.text().map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+
+        let media_content = media("content").filter(|c| {
+            c.attribute("medium").is_none_or(|m| m == "image")
+                && c.attribute("type").is_none_or(|t| t.starts_with("image/"))
+        });
+        let enclosure = child("enclosure").filter(|c| c.attribute("type").is_none_or(|t| t.starts_with("image/")));
+        let atom_enclosure = children.iter().find(|c| {
+            c.tag_name().name() == "link"
+                && c.attribute("rel") == Some("enclosure")
+                && c.attribute("type").is_none_or(|t| t.starts_with("image/"))
+        });
+        let url = media_content
+            .and_then(|c| c.attribute("url"))
+            .or_else(|| enclosure.and_then(|c| c.attribute("url")))
+            .or_else(|| atom_enclosure.and_then(|c| c.attribute("href")));
+        let Some(url) = url else {
+            continue;
+        };
+
+        let mut categories = children
+            .iter()
+            .filter(|c| c.tag_name().name() == "category")
+            .filter_map(|c| c.attribute("term").map(str::to_string).or_else(|| text(c)));
+        let category_id = categories.next();
+        let mut tags: Vec<String> = categories.collect();
+        if let Some(keywords) = media("keywords").and_then(text) {
+            tags.extend(keywords.split(',').map(|k| k.trim().to_string()).filter(|k| !k.is_empty()));
+        }
+
+        let author = child("creator")
+            .or_else(|| child("author"))
+            .and_then(|author| {
+                // Atom 的 author 下还有 name 子元素
+                author
+                    .children()
+                    .find(|c| c.tag_name().name() == "name")
+                    .and_then(|name| text(&name))
+                    .or_else(|| text(author))
+            });
+        items.push(FeedItem {
+            id: child("guid").or_else(|| child("id")).and_then(text),
+            name: child("title").and_then(text),
+            url: url.to_string(),
+            thumbnail_url: media("thumbnail").and_then(|c| c.attribute("url")).map(str::to_string),
+            width: parse_attribute(media_content, "width"),
+            height: parse_attribute(media_content, "height"),
+            file_size: parse_attribute(media_content, "fileSize").or_else(|| parse_attribute(enclosure, "length")),
+            category_id,
+            author,
+            tags,
+        });
+    }
+    Ok(build_feed(vec![], items, base))
+}
+
+fn is_media(node: &roxmltree::Node) -> bool {
+    node.tag_name().namespace() == Some(MEDIA_RSS_NS)
+}
+
+fn parse_attribute<T: std::str::FromStr>(node: Option<&roxmltree::Node>, name: &str) -> Option<T> {
+    node.and_then(|n| n.attribute(name)).and_then(|v| v.trim().parse().ok())
+}
+
+fn load_settings(db: &Database) -> Result<WallpaperSourceSettings> {
+    let conn = db.get_connection();
+    let conn = conn.lock().unwrap();
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM app_settings WHERE key = ?1",
+            params![SETTINGS_KEY],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match value {
+        Some(value) => match serde_json::from_str(&value) {
+            Ok(settings) => Ok(settings),
+            Err(e) => {
+                log::warn!("Ignoring invalid wallpaper source settings: {}", e);
+                Ok(WallpaperSourceSettings::default())
+            }
+        },
+        None => Ok(WallpaperSourceSettings::default()),
+    }
+}
+
+fn save_settings(db: &Database, settings: &WallpaperSourceSettings) -> Result<()> {
+    let value = serde_json::to_string(settings)?;
+    let conn = db.get_connection();
+    let conn = conn.lock().unwrap();
+    conn.execute(
+        "INSERT OR REPLACE INTO app_settings (key, value, updated_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)",
+        params![SETTINGS_KEY, value],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use crate::db::models::{DirectoryBinding, ScanSummary};
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::services::DirectoryService;
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// 监听到资源变化后推送给前端的事件名
+pub const RESOURCES_CHANGED_EVENT: &str = "resources://changed";
+
+/// 合并短时间内连续文件事件的时间窗口
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// resources://changed 事件负载
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourcesChangedEvent {
+    pub category_id: i64,
+    pub binding_id: i64,
+    pub summary: ScanSummary,
+}
+
+/// 目录实时监听管理器
+/// @description 每个开启了监听的目录绑定对应一个去抖后的文件系统监听器，
+/// 文件变化会增量写入 resources 表并通过 resources://changed 通知前端
+pub struct WatcherManager {
+    watchers: Mutex<HashMap<i64, Debouncer<RecommendedWatcher>>>,
+}
+
+impl Default for WatcherManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WatcherManager {
+    pub fn new() -> Self {
+        WatcherManager {
+            watchers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 为所有开启了监听的绑定启动监听器，单个失败不影响其他绑定
+    pub fn restore<R: Runtime>(&self, app: &AppHandle<R>, db: Arc<Database>) -> Result<()> {
+        let bindings = DirectoryService::new(db.clone()).get_watched_bindings()?;
+        for binding in bindings {
+            if let Err(e) = self.watch(app.clone(), db.clone(), &binding) {
+                log::warn!("Failed to watch {}: {}", binding.directory_path, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// 开始监听绑定目录，已在监听时会先停止旧的监听器
+    pub fn watch<R: Runtime>(
+        &self,
+        app: AppHandle<R>,
+        db: Arc<Database>,
+        binding: &DirectoryBinding,
+    ) -> Result<()> {
+        let binding_id = binding.id;
+        let category_id = binding.category_id;
+
+        let mut debouncer = new_debouncer(DEBOUNCE_TIMEOUT, move |result: DebounceEventResult| {
+            let events = match result {
+                Ok(events) => events,
+                Err(e) => {
+                    log::warn!("Watcher error for binding {}: {}", binding_id, e);
+                    return;
+                }
+            };
+
+            let paths: Vec<PathBuf> = events
+                .into_iter()
+                .map(|event| event.path)
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+
+            let service = DirectoryService::new(db.clone());
+            match service.apply_changes(binding_id, &paths) {
+                Ok(summary) => {
+                    if summary.added + summary.changed + summary.removed == 0 {
+                        return;
+                    }
+                    let _ = app.emit(
+                        RESOURCES_CHANGED_EVENT,
+                        ResourcesChangedEvent {
+                            category_id,
+                            binding_id,
+                            summary,
+                        },
+                    );
+                }
+                Err(e) => {
+                    log::error!("Failed to apply changes for binding {}: {}", binding_id, e);
+                }
+            }
+        })
+        .map_err(|e| AppError::Internal(format!("Failed to create watcher: {}", e)))?;
+
+        debouncer
+            .watcher()
+            .watch(Path::new(&binding.directory_path), RecursiveMode::Recursive)
+            .map_err(|e| AppError::Internal(format!("Failed to watch directory: {}", e)))?;
+
+        log::info!("Watching {} for binding {}", binding.directory_path, binding_id);
+        self.watchers.lock().unwrap().insert(binding_id, debouncer);
+
+        Ok(())
+    }
+
+    /// 停止监听绑定目录
+    pub fn unwatch(&self, binding_id: i64) {
+        if self.watchers.lock().unwrap().remove(&binding_id).is_some() {
+            log::info!("Stopped watching binding {}", binding_id);
+        }
+    }
+}
@@ -10,6 +10,7 @@ import { invoke } from '@tauri-apps/api/core';
 import type {
   Category,
   CategoryNode,
+  CategoryStats,
   CreateCategoryRequest,
   UpdateCategoryRequest,
   DeleteStrategy,
@@ -24,15 +25,36 @@ export const categoryApi = {
     return invoke('create_category', { req });
   },
 
-  async updateCategory(id: number, req: UpdateCategoryRequest): Promise<Category> {
-    return invoke('update_category', { id, req });
+  // 受密码保护的分类需要携带已解锁该分类的令牌
+  async updateCategory(id: number, req: UpdateCategoryRequest, token?: string): Promise<Category> {
+    return invoke('update_category', { id, req, token });
   },
 
-  async deleteCategory(id: number, strategy: DeleteStrategy): Promise<void> {
-    return invoke('delete_category', { id, strategy });
+  // 按 delete_all 删除时，受保护的子分类同样需要已解锁
+  async deleteCategory(id: number, strategy: DeleteStrategy, token?: string): Promise<void> {
+    return invoke('delete_category', { id, strategy, token });
   },
 
-  async reorderCategories(orders: Array<[number, number]>): Promise<void> {
-    return invoke('reorder_categories', { orders });
+  async reorderCategories(orders: Array<[number, number]>, token?: string): Promise<void> {
+    return invoke('reorder_categories', { orders, token });
+  },
+
+  // 不传 categoryId 时统计全部分类
+  async getCategoryStats(categoryId?: number, topN?: number, token?: string): Promise<CategoryStats> {
+    return invoke('get_category_stats', { categoryId, topN, token });
+  },
+
+  // 已设置密码时需要提供当前密码
+  async setCategoryPassword(id: number, password: string, currentPassword?: string): Promise<void> {
+    return invoke('set_category_password', { id, password, currentPassword });
+  },
+
+  // 校验成功返回解锁令牌（传入令牌仍有效时复用），失败返回 null
+  async verifyCategoryPassword(id: number, password: string, token?: string): Promise<string | null> {
+    return invoke('verify_category_password', { id, password, token });
+  },
+
+  async clearCategoryPassword(id: number, password: string): Promise<void> {
+    return invoke('clear_category_password', { id, password });
   },
 };
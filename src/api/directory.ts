@@ -1,42 +1,97 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { DirectoryBinding, ResourceItem } from '../types';
+import type { DirectoryBinding, ResourceItem, UpdateBindingRequest } from '../types';
 
 export interface BatchRenameResult {
   success: string[];
   failed: string[];
 }
 
+export interface ScanSummary {
+  added: number;
+  changed: number;
+  removed: number;
+  unchanged: number;
+}
+
 export interface ScanProgress {
   total_files: number;
   scanned_files: number;
   current_batch: ResourceItem[];
   is_complete: boolean;
+  summary: ScanSummary | null;
+}
+
+export type ScanJobState = 'running' | 'completed' | 'cancelled' | 'failed';
+
+// 后台扫描任务状态，同时也是 scan://progress 事件的负载
+export interface ScanJobStatus {
+  job_id: string;
+  category_id: number;
+  state: ScanJobState;
+  files_scanned: number;
+  current_path: string | null;
+  summary: ScanSummary | null;
+  error: string | null;
+  started_at: string;
+  finished_at: string | null;
 }
 
+export const SCAN_PROGRESS_EVENT = 'scan://progress';
+
+// 目录实时监听检测到变化后推送
+export interface ResourcesChangedEvent {
+  category_id: number;
+  binding_id: number;
+  summary: ScanSummary;
+}
+
+export const RESOURCES_CHANGED_EVENT = 'resources://changed';
+
 export const directoryApi = {
-  async bindDirectory(categoryId: number, path: string): Promise<DirectoryBinding> {
-    return invoke('bind_directory', { categoryId, path });
+  // 受密码保护的分类需要携带已解锁该分类的令牌
+  async bindDirectory(categoryId: number, path: string, token?: string): Promise<DirectoryBinding> {
+    return invoke('bind_directory', { categoryId, path, token });
+  },
+
+  async unbindDirectory(bindingId: number, token?: string): Promise<void> {
+    return invoke('unbind_directory', { bindingId, token });
+  },
+
+  // 受密码保护的分类需要携带已解锁该分类的令牌
+  async getBindings(categoryId: number, token?: string): Promise<DirectoryBinding[]> {
+    return invoke('get_bindings', { categoryId, token });
+  },
+
+  async setBindingWatch(bindingId: number, enabled: boolean, token?: string): Promise<DirectoryBinding> {
+    return invoke('set_binding_watch', { bindingId, enabled, token });
+  },
+
+  async updateBinding(bindingId: number, req: UpdateBindingRequest, token?: string): Promise<DirectoryBinding> {
+    return invoke('update_binding', { bindingId, req, token });
+  },
+
+  async scanDirectory(categoryId: number, token?: string): Promise<ResourceItem[]> {
+    return invoke('scan_directory', { categoryId, token });
+  },
+
+  async rescanDirectory(categoryId: number, token?: string): Promise<ScanSummary> {
+    return invoke('rescan_directory', { categoryId, token });
   },
 
-  async unbindDirectory(bindingId: number): Promise<void> {
-    return invoke('unbind_directory', { bindingId });
+  async scanDirectoryBatch(categoryId: number, batchSize?: number, token?: string): Promise<ScanProgress> {
+    return invoke('scan_directory_batch', { categoryId, batchSize, token });
   },
 
-  async getBindings(categoryId: number): Promise<DirectoryBinding[]> {
-    return invoke('get_bindings', { categoryId });
+  async startScanJob(categoryId: number, token?: string): Promise<string> {
+    return invoke('start_scan_job', { categoryId, token });
   },
 
-  async scanDirectory(categoryId: number, showHidden: boolean, ignoreDirectories?: string): Promise<ResourceItem[]> {
-    return invoke('scan_directory', { categoryId, showHidden, ignoreDirectories });
+  async getScanJob(jobId: string, token?: string): Promise<ScanJobStatus> {
+    return invoke('get_scan_job', { jobId, token });
   },
 
-  async scanDirectoryBatch(
-    categoryId: number,
-    showHidden: boolean,
-    ignoreDirectories?: string,
-    batchSize?: number
-  ): Promise<ScanProgress> {
-    return invoke('scan_directory_batch', { categoryId, showHidden, ignoreDirectories, batchSize });
+  async cancelScanJob(jobId: string, token?: string): Promise<void> {
+    return invoke('cancel_scan_job', { jobId, token });
   },
 
   async selectDirectory(): Promise<string | null> {
@@ -0,0 +1,190 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { FileKind, ResourceItem } from '../types';
+import type { ThumbnailOutput } from './thumbnail';
+
+export type ResourceSortField = 'name' | 'size' | 'type' | 'modified';
+export type SortDirection = 'asc' | 'desc';
+
+export interface ResourceSort {
+  field: ResourceSortField;
+  direction: SortDirection;
+}
+
+// next_page 为下一页游标，为 null 时表示已到最后一页
+export interface ResourcePage {
+  items: ResourceItem[];
+  total: number;
+  next_page: string | null;
+}
+
+export type SearchMode = 'prefix' | 'substring';
+
+export interface SearchResourcesRequest {
+  query: string;
+  mode?: SearchMode;
+  // 限定在该分类及其子分类下
+  category_id?: number;
+  file_types?: string[];
+  kinds?: FileKind[];
+  min_size?: number;
+  max_size?: number;
+  // RFC 3339 格式
+  modified_after?: string;
+  modified_before?: string;
+  // 以下条件基于媒体元数据，尚未提取元数据的资源不会匹配
+  min_width?: number;
+  max_width?: number;
+  min_height?: number;
+  max_height?: number;
+  // 本地时间 YYYY-MM-DD 或 YYYY-MM-DDTHH:MM:SS，含边界
+  captured_after?: string;
+  captured_before?: string;
+  min_duration_ms?: number;
+  max_duration_ms?: number;
+  // 匹配相机厂商、型号以及标题、艺术家、专辑、流派
+  metadata_query?: string;
+  page?: number;
+  page_size?: number;
+}
+
+export interface SearchResourcesResult {
+  items: ResourceItem[];
+  total: number;
+  page: number;
+  page_size: number;
+  has_more: boolean;
+}
+
+export interface DuplicateGroup {
+  content_hash: string;
+  file_size: number;
+  // 除保留一份外其余副本占用的字节数
+  wasted_bytes: number;
+  resources: ResourceItem[];
+}
+
+export type DuplicatePhase = 'grouping' | 'partial_hash' | 'full_hash';
+
+// 重复文件检测任务状态，同时也是 duplicates://progress 事件的负载
+export interface DuplicateJobStatus {
+  job_id: string;
+  state: 'running' | 'completed' | 'cancelled' | 'failed';
+  phase: DuplicatePhase;
+  processed: number;
+  total: number;
+  groups: DuplicateGroup[] | null;
+  wasted_bytes: number;
+  error: string | null;
+  started_at: string;
+  finished_at: string | null;
+}
+
+export const DUPLICATE_PROGRESS_EVENT = 'duplicates://progress';
+
+export type DuplicateAction = 'delete' | 'trash' | 'hardlink';
+
+export interface DuplicateResolution {
+  keep_id: number;
+  duplicate_ids: number[];
+}
+
+export interface ResolveDuplicatesResult {
+  success: number[];
+  failed: string[];
+  reclaimed_bytes: number;
+}
+
+export interface ResourceMetadata {
+  resource_id: number;
+  width: number | null;
+  height: number | null;
+  duration_ms: number | null;
+  // 本地时间 YYYY-MM-DDTHH:MM:SS
+  captured_at: string | null;
+  camera_make: string | null;
+  camera_model: string | null;
+  title: string | null;
+  artist: string | null;
+  album: string | null;
+  genre: string | null;
+  extracted_at: string;
+}
+
+// 元数据提取任务状态，同时也是 metadata://progress 事件的负载
+export interface MetadataJobStatus {
+  job_id: string;
+  category_id: number | null;
+  state: 'running' | 'completed' | 'cancelled' | 'failed';
+  processed: number;
+  total: number;
+  error: string | null;
+  started_at: string;
+  finished_at: string | null;
+}
+
+export const METADATA_PROGRESS_EVENT = 'metadata://progress';
+
+export const resourceApi = {
+  async listResources(
+    categoryId: number,
+    includeDescendants: boolean,
+    sort?: ResourceSort,
+    page?: string | null,
+    pageSize?: number,
+    token?: string
+  ): Promise<ResourcePage> {
+    return invoke('list_resources', { categoryId, includeDescendants, sort, page, pageSize, token });
+  },
+
+  async searchResources(request: SearchResourcesRequest, token?: string): Promise<SearchResourcesResult> {
+    return invoke('search_resources', { request, token });
+  },
+
+  async startDuplicateJob(token?: string): Promise<string> {
+    return invoke('start_duplicate_job', { token });
+  },
+
+  async getDuplicateJob(jobId: string, token?: string): Promise<DuplicateJobStatus> {
+    return invoke('get_duplicate_job', { jobId, token });
+  },
+
+  async cancelDuplicateJob(jobId: string, token?: string): Promise<void> {
+    return invoke('cancel_duplicate_job', { jobId, token });
+  },
+
+  async resolveDuplicates(
+    action: DuplicateAction,
+    groups: DuplicateResolution[],
+    token?: string
+  ): Promise<ResolveDuplicatesResult> {
+    return invoke('resolve_duplicates', { request: { action, groups }, token });
+  },
+
+  // 不指定分类时处理所有已解锁的分类
+  async startMetadataJob(categoryId?: number, token?: string): Promise<string> {
+    return invoke('start_metadata_job', { categoryId, token });
+  },
+
+  async getMetadataJob(jobId: string, token?: string): Promise<MetadataJobStatus> {
+    return invoke('get_metadata_job', { jobId, token });
+  },
+
+  async cancelMetadataJob(jobId: string, token?: string): Promise<void> {
+    return invoke('cancel_metadata_job', { jobId, token });
+  },
+
+  async getResourceMetadata(resourceId: number, token?: string): Promise<ResourceMetadata | null> {
+    return invoke('get_resource_metadata', { resourceId, token });
+  },
+
+  // 仅支持图片资源，output 为 path 时需通过 convertFileSrc 加载
+  async getResourceThumbnail(
+    resourceId: number,
+    width: number,
+    height: number,
+    output: ThumbnailOutput = 'data_url',
+    token?: string
+  ): Promise<string> {
+    return invoke('get_resource_thumbnail', { resourceId, width, height, output, token });
+  },
+};
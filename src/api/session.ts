@@ -0,0 +1,17 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export const sessionApi = {
+  // 不传令牌时锁定所有会话
+  async lockSession(token?: string): Promise<void> {
+    return invoke('lock_session', { token });
+  },
+
+  // 空闲自动锁定时间（分钟），0 表示不自动锁定
+  async getAutoLockTimeout(): Promise<number> {
+    return invoke('get_auto_lock_timeout');
+  },
+
+  async setAutoLockTimeout(minutes: number): Promise<void> {
+    return invoke('set_auto_lock_timeout', { minutes });
+  },
+};
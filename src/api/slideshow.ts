@@ -0,0 +1,75 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { WallpaperFitMode } from '../types';
+
+export type PlaylistSource =
+  | { type: 'all' }
+  | { type: 'wallpapers'; ids: string[] }
+  | { type: 'favorites' }
+  | { type: 'collection'; collection_id: number }
+  | { type: 'tag'; tag_id: number };
+
+export type SlideshowOrder = 'sequential' | 'shuffle';
+
+// 本地时间 HH:MM，start 晚于 end 时跨越午夜，两者相同时表示全天
+export interface TimeRule {
+  start: string;
+  end: string;
+  source: PlaylistSource;
+}
+
+export interface SlideshowConfig {
+  source: PlaylistSource;
+  interval_minutes: number;
+  order?: SlideshowOrder;
+  fit_mode?: WallpaperFitMode;
+  // 不指定时设置到所有显示器
+  monitor_id?: string | null;
+  // 第一条命中的规则生效，都不命中时使用 source
+  rules?: TimeRule[];
+}
+
+// 同时也是 slideshow://changed 事件的负载
+export interface SlideshowStatus {
+  enabled: boolean;
+  paused: boolean;
+  config: SlideshowConfig | null;
+  playlist: string[];
+  position: number | null;
+  current_wallpaper_id: string | null;
+  active_rule: number | null;
+  last_changed_at: string | null;
+  next_change_at: string | null;
+  last_error: string | null;
+}
+
+export const SLIDESHOW_CHANGED_EVENT = 'slideshow://changed';
+
+export const slideshowApi = {
+  async getStatus(): Promise<SlideshowStatus> {
+    return invoke('get_slideshow_status');
+  },
+
+  async start(config: SlideshowConfig): Promise<SlideshowStatus> {
+    return invoke('start_slideshow', { config });
+  },
+
+  async stop(): Promise<SlideshowStatus> {
+    return invoke('stop_slideshow');
+  },
+
+  async pause(): Promise<SlideshowStatus> {
+    return invoke('pause_slideshow');
+  },
+
+  async resume(): Promise<SlideshowStatus> {
+    return invoke('resume_slideshow');
+  },
+
+  async next(): Promise<SlideshowStatus> {
+    return invoke('slideshow_next');
+  },
+
+  async previous(): Promise<SlideshowStatus> {
+    return invoke('slideshow_previous');
+  },
+};
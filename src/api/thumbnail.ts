@@ -0,0 +1,21 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// data_url 可直接用作 img 的 src，path 为缓存文件路径
+export type ThumbnailOutput = 'data_url' | 'path';
+
+export interface ThumbnailCacheStats {
+  file_count: number;
+  total_bytes: number;
+  max_bytes: number;
+}
+
+export const thumbnailApi = {
+  async getCacheStats(): Promise<ThumbnailCacheStats> {
+    return invoke('get_thumbnail_cache_stats');
+  },
+
+  // 返回释放的字节数
+  async clearCache(): Promise<number> {
+    return invoke('clear_thumbnail_cache');
+  },
+};
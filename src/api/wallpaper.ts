@@ -1,12 +1,33 @@
 import { invoke } from '@tauri-apps/api/core';
+import type { ThumbnailOutput } from './thumbnail';
 import type {
   LocalWallpaper,
   WallpaperCategory,
   WallpaperStationResponse,
   SetWallpaperResult,
   WallpaperFitMode,
+  WallpaperFilter,
+  WallpaperBackfillResult,
+  MonitorInfo,
+  CurrentWallpaperInfo,
+  WallpaperSourceConfig,
+  WallpaperSourceSettings,
+  WallpaperFolder,
+  WallpaperFolderInput,
+  WallpaperFolderSyncResult,
+  LocalWallpaperPage,
+  LocalWallpaperQuery,
+  WallpaperTag,
+  WallpaperCollection,
+  WallpaperCollectionInput,
+  WallpaperImportResult,
+  WallpaperDedupSettings,
+  WallpaperDuplicateCluster,
 } from '../types';
 
+export const WALLPAPER_DOWNLOAD_EVENT = 'wallpaper://download';
+export const WALLPAPERS_CHANGED_EVENT = 'wallpapers://changed';
+
 /**
  * 壁纸API服务类
  * @description 处理壁纸相关的所有API调用，包括本地壁纸管理和壁纸站服务
@@ -17,22 +38,51 @@ export const wallpaperApi = {
   // ============================================
 
   /**
-   * 获取所有本地壁纸列表
-   * @returns Promise<LocalWallpaper[]> 本地壁纸数组
+   * 分页获取本地壁纸列表
+   * @param query 筛选、排序与分页参数，不传则按导入时间倒序获取第一页
+   * @returns Promise<LocalWallpaperPage> 分页响应
+   */
+  async getLocalWallpapers(query: LocalWallpaperQuery = {}): Promise<LocalWallpaperPage> {
+    return invoke('get_local_wallpapers', {
+      filter: query.filter,
+      sort: query.sort,
+      page: query.page,
+      pageSize: query.pageSize,
+    });
+  },
+
+  /**
+   * 获取适配指定显示器分辨率的本地壁纸
+   * @param monitor 显示器信息
+   * @param page 页码，从1开始
+   * @returns Promise<LocalWallpaperPage> 分页响应
+   */
+  async getWallpapersFittingMonitor(
+    monitor: { width: number; height: number },
+    page: number = 1
+  ): Promise<LocalWallpaperPage> {
+    const filter: WallpaperFilter = { fits_width: monitor.width, fits_height: monitor.height };
+    return wallpaperApi.getLocalWallpapers({ filter, page });
+  },
+
+  /**
+   * 补全已导入壁纸的宽高、文件大小与修改时间
+   * @returns Promise<WallpaperBackfillResult> 补全结果
    */
-  async getLocalWallpapers(): Promise<LocalWallpaper[]> {
-    return invoke('get_local_wallpapers');
+  async backfillWallpaperDimensions(): Promise<WallpaperBackfillResult> {
+    const result: string = await invoke('backfill_wallpaper_dimensions');
+    return JSON.parse(result);
   },
 
   /**
    * 导入本地壁纸图片
    * @param filePaths 图片文件路径数组
-   * @returns Promise<LocalWallpaper[]> 导入的壁纸数组
+   * @returns Promise<WallpaperImportResult> 导入的壁纸与被拒绝或合并的近似重复图片
    */
-  async importWallpapers(filePaths: string[]): Promise<LocalWallpaper[]> {
+  async importWallpapers(filePaths: string[]): Promise<WallpaperImportResult> {
     console.log('[wallpaperApi] 开始导入，文件数量:', filePaths.length);
     console.log('[wallpaperApi] 文件路径:', filePaths);
-    const result = await invoke('import_wallpapers', { filePaths: JSON.stringify(filePaths) }) as LocalWallpaper[];
+    const result: WallpaperImportResult = await invoke('import_wallpapers', { filePaths: JSON.stringify(filePaths) });
     console.log('[wallpaperApi] 导入结果:', result);
     return result;
   },
@@ -56,18 +106,237 @@ export const wallpaperApi = {
   },
 
   /**
-   * 获取本地壁纸缩略图
+   * 获取本地壁纸缩略图，缩放到 width × height 以内并保持宽高比
    * @param wallpaperId 壁纸ID
-   * @param width 缩略图宽度
-   * @param height 缩略图高度
-   * @returns Promise<string> 缩略图数据URL
+   * @param width 缩略图最大宽度
+   * @param height 缩略图最大高度
+   * @param output 返回形式，path 时需通过 convertFileSrc 加载
+   * @returns Promise<string> 缩略图数据URL或缓存文件路径
    */
   async getWallpaperThumbnail(
     wallpaperId: string,
     width: number = 300,
-    height: number = 200
+    height: number = 200,
+    output: ThumbnailOutput = 'data_url'
   ): Promise<string> {
-    return invoke('get_wallpaper_thumbnail', { wallpaperId, width, height });
+    return invoke('get_wallpaper_thumbnail', { wallpaperId, width, height, output });
+  },
+
+  // ============================================
+  // 标签、收藏、评分与合集
+  // ============================================
+
+  /**
+   * 获取本地壁纸标签列表
+   * @returns Promise<WallpaperTag[]> 标签数组
+   */
+  async getWallpaperTags(): Promise<WallpaperTag[]> {
+    return invoke('get_wallpaper_tags');
+  },
+
+  /**
+   * 替换壁纸的全部标签，不存在的标签会自动创建
+   * @param wallpaperId 壁纸ID
+   * @param tags 标签名数组
+   * @returns Promise<void>
+   */
+  async setWallpaperTags(wallpaperId: string, tags: string[]): Promise<void> {
+    return invoke('set_wallpaper_tags', { wallpaperId, tags });
+  },
+
+  /**
+   * 为多张壁纸添加标签
+   * @param wallpaperIds 壁纸ID数组
+   * @param tags 标签名数组
+   * @returns Promise<void>
+   */
+  async addWallpaperTags(wallpaperIds: string[], tags: string[]): Promise<void> {
+    return invoke('add_wallpaper_tags', { wallpaperIds, tags });
+  },
+
+  /**
+   * 从多张壁纸上移除标签
+   * @param wallpaperIds 壁纸ID数组
+   * @param tagIds 标签ID数组
+   * @returns Promise<void>
+   */
+  async removeWallpaperTags(wallpaperIds: string[], tagIds: number[]): Promise<void> {
+    return invoke('remove_wallpaper_tags', { wallpaperIds, tagIds });
+  },
+
+  /**
+   * 重命名标签，与已有标签重名时合并
+   * @param tagId 标签ID
+   * @param name 新名称
+   * @returns Promise<void>
+   */
+  async renameWallpaperTag(tagId: number, name: string): Promise<void> {
+    return invoke('rename_wallpaper_tag', { tagId, name });
+  },
+
+  /**
+   * 删除标签
+   * @param tagId 标签ID
+   * @returns Promise<void>
+   */
+  async deleteWallpaperTag(tagId: number): Promise<void> {
+    return invoke('delete_wallpaper_tag', { tagId });
+  },
+
+  /**
+   * 收藏或取消收藏壁纸
+   * @param wallpaperIds 壁纸ID数组
+   * @param favorite 是否收藏
+   * @returns Promise<void>
+   */
+  async setWallpaperFavorite(wallpaperIds: string[], favorite: boolean): Promise<void> {
+    return invoke('set_wallpaper_favorite', { wallpaperIds, favorite });
+  },
+
+  /**
+   * 设置壁纸评分
+   * @param wallpaperIds 壁纸ID数组
+   * @param rating 评分 1-5，0 表示清除评分
+   * @returns Promise<void>
+   */
+  async setWallpaperRating(wallpaperIds: string[], rating: number): Promise<void> {
+    return invoke('set_wallpaper_rating', { wallpaperIds, rating });
+  },
+
+  /**
+   * 获取壁纸合集列表
+   * @returns Promise<WallpaperCollection[]> 合集数组
+   */
+  async getWallpaperCollections(): Promise<WallpaperCollection[]> {
+    return invoke('get_wallpaper_collections');
+  },
+
+  /**
+   * 创建壁纸合集
+   * @param input 合集名称与描述
+   * @returns Promise<WallpaperCollection> 创建的合集
+   */
+  async createWallpaperCollection(input: WallpaperCollectionInput): Promise<WallpaperCollection> {
+    return invoke('create_wallpaper_collection', { input });
+  },
+
+  /**
+   * 修改壁纸合集
+   * @param collectionId 合集ID
+   * @param input 合集名称与描述
+   * @returns Promise<WallpaperCollection> 修改后的合集
+   */
+  async updateWallpaperCollection(collectionId: number, input: WallpaperCollectionInput): Promise<WallpaperCollection> {
+    return invoke('update_wallpaper_collection', { collectionId, input });
+  },
+
+  /**
+   * 删除壁纸合集，不会删除其中的壁纸
+   * @param collectionId 合集ID
+   * @returns Promise<void>
+   */
+  async deleteWallpaperCollection(collectionId: number): Promise<void> {
+    return invoke('delete_wallpaper_collection', { collectionId });
+  },
+
+  /**
+   * 将壁纸加入合集
+   * @param collectionId 合集ID
+   * @param wallpaperIds 壁纸ID数组
+   * @returns Promise<WallpaperCollection> 更新后的合集
+   */
+  async addWallpapersToCollection(collectionId: number, wallpaperIds: string[]): Promise<WallpaperCollection> {
+    return invoke('add_wallpapers_to_collection', { collectionId, wallpaperIds });
+  },
+
+  /**
+   * 将壁纸移出合集
+   * @param collectionId 合集ID
+   * @param wallpaperIds 壁纸ID数组
+   * @returns Promise<WallpaperCollection> 更新后的合集
+   */
+  async removeWallpapersFromCollection(collectionId: number, wallpaperIds: string[]): Promise<WallpaperCollection> {
+    return invoke('remove_wallpapers_from_collection', { collectionId, wallpaperIds });
+  },
+
+  // ============================================
+  // 壁纸文件夹
+  // ============================================
+
+  /**
+   * 获取壁纸来源文件夹列表
+   * @returns Promise<WallpaperFolder[]> 文件夹数组
+   */
+  async getWallpaperFolders(): Promise<WallpaperFolder[]> {
+    return invoke('get_wallpaper_folders');
+  },
+
+  /**
+   * 添加壁纸来源文件夹并立即同步
+   * @param input 文件夹路径与同步设置
+   * @returns Promise<WallpaperFolder> 添加后的文件夹
+   */
+  async addWallpaperFolder(input: WallpaperFolderInput): Promise<WallpaperFolder> {
+    return invoke('add_wallpaper_folder', { input });
+  },
+
+  /**
+   * 修改壁纸来源文件夹设置并重新同步
+   * @param folderId 文件夹ID
+   * @param input 同步设置
+   * @returns Promise<WallpaperFolder> 修改后的文件夹
+   */
+  async updateWallpaperFolder(folderId: number, input: WallpaperFolderInput): Promise<WallpaperFolder> {
+    return invoke('update_wallpaper_folder', { folderId, input });
+  },
+
+  /**
+   * 删除壁纸来源文件夹
+   * @param folderId 文件夹ID
+   * @param removeWallpapers 是否同时从壁纸库移除该文件夹导入的壁纸，不会删除文件
+   * @returns Promise<void>
+   */
+  async removeWallpaperFolder(folderId: number, removeWallpapers: boolean = false): Promise<void> {
+    return invoke('remove_wallpaper_folder', { folderId, removeWallpapers });
+  },
+
+  /**
+   * 手动同步壁纸来源文件夹，有变化时同样会推送 WALLPAPERS_CHANGED_EVENT 事件
+   * @param folderId 文件夹ID，不传则同步全部
+   * @returns Promise<WallpaperFolderSyncResult[]> 各文件夹的同步结果
+   */
+  async syncWallpaperFolders(folderId?: number): Promise<WallpaperFolderSyncResult[]> {
+    return invoke('sync_wallpaper_folders', { folderId });
+  },
+
+  // ============================================
+  // 近似重复壁纸
+  // ============================================
+
+  /**
+   * 获取壁纸去重设置
+   * @returns Promise<WallpaperDedupSettings> 相似度阈值与导入时的处理方式
+   */
+  async getWallpaperDedupSettings(): Promise<WallpaperDedupSettings> {
+    return invoke('get_wallpaper_dedup_settings');
+  },
+
+  /**
+   * 保存壁纸去重设置
+   * @param settings 相似度阈值（0.75-1）与导入时的处理方式
+   * @returns Promise<WallpaperDedupSettings> 保存后的设置
+   */
+  async saveWallpaperDedupSettings(settings: WallpaperDedupSettings): Promise<WallpaperDedupSettings> {
+    return invoke('save_wallpaper_dedup_settings', { settings });
+  },
+
+  /**
+   * 查找壁纸库中的近似重复壁纸
+   * @param similarity 相似度阈值，不传则使用去重设置中的值
+   * @returns Promise<WallpaperDuplicateCluster[]> 近似重复的壁纸分组
+   */
+  async findWallpaperDuplicates(similarity?: number): Promise<WallpaperDuplicateCluster[]> {
+    return invoke('find_wallpaper_duplicates', { similarity });
   },
 
   // ============================================
@@ -79,7 +348,8 @@ export const wallpaperApi = {
    * @returns Promise<WallpaperCategory[]> 分类数组
    */
   async getWallpaperCategories(): Promise<WallpaperCategory[]> {
-    return invoke('get_wallpaper_categories');
+    const result = await invoke<string>('get_wallpaper_categories');
+    return JSON.parse(result);
   },
 
   /**
@@ -94,16 +364,18 @@ export const wallpaperApi = {
     page: number = 1,
     pageSize: number = 20
   ): Promise<WallpaperStationResponse> {
-    return invoke('get_wallpaper_station_wallpapers', { categoryId, page, pageSize });
+    const result = await invoke<string>('get_wallpaper_station_wallpapers', { categoryId, page, pageSize });
+    return JSON.parse(result);
   },
 
   /**
-   * 下载壁纸站壁纸到本地
+   * 下载壁纸站壁纸到本地壁纸库并导入，进度通过 WALLPAPER_DOWNLOAD_EVENT 事件推送
    * @param wallpaperId 壁纸ID
    * @returns Promise<LocalWallpaper> 下载后的本地壁纸
    */
   async downloadWallpaper(wallpaperId: string): Promise<LocalWallpaper> {
-    return invoke('download_wallpaper', { wallpaperId });
+    const result = await invoke<string>('download_wallpaper', { wallpaperId });
+    return JSON.parse(result);
   },
 
   /**
@@ -118,7 +390,43 @@ export const wallpaperApi = {
     page: number = 1,
     pageSize: number = 20
   ): Promise<WallpaperStationResponse> {
-    return invoke('search_wallpapers', { keyword, page, pageSize });
+    const result = await invoke<string>('search_wallpapers', { keyword, page, pageSize });
+    return JSON.parse(result);
+  },
+
+  /**
+   * 获取已配置的壁纸源
+   * @returns Promise<WallpaperSourceSettings> 壁纸源列表及当前选中的壁纸源
+   */
+  async getWallpaperSources(): Promise<WallpaperSourceSettings> {
+    return invoke('get_wallpaper_sources');
+  },
+
+  /**
+   * 添加或更新壁纸源，第一个添加的壁纸源会自动选中
+   * @param config 壁纸源配置，不传 id 时新增
+   * @returns Promise<WallpaperSourceSettings> 更新后的设置
+   */
+  async saveWallpaperSource(config: WallpaperSourceConfig): Promise<WallpaperSourceSettings> {
+    return invoke('save_wallpaper_source', { config });
+  },
+
+  /**
+   * 删除壁纸源
+   * @param sourceId 壁纸源ID
+   * @returns Promise<WallpaperSourceSettings> 更新后的设置
+   */
+  async removeWallpaperSource(sourceId: string): Promise<WallpaperSourceSettings> {
+    return invoke('remove_wallpaper_source', { sourceId });
+  },
+
+  /**
+   * 切换壁纸站使用的壁纸源
+   * @param sourceId 壁纸源ID
+   * @returns Promise<WallpaperSourceSettings> 更新后的设置
+   */
+  async setActiveWallpaperSource(sourceId: string): Promise<WallpaperSourceSettings> {
+    return invoke('set_active_wallpaper_source', { sourceId });
   },
 
   // ============================================
@@ -129,7 +437,7 @@ export const wallpaperApi = {
    * 设置系统壁纸
    * @param wallpaperId 壁纸ID（本地或远程）
    * @param fitMode 适配模式
-   * @param monitorId 显示器ID（多显示器时使用）
+   * @param monitorId 显示器ID，不传则设置到所有显示器
    * @returns Promise<SetWallpaperResult> 设置结果
    */
   async setWallpaper(
@@ -144,7 +452,7 @@ export const wallpaperApi = {
    * 设置本地文件为壁纸
    * @param filePath 本地文件路径
    * @param fitMode 适配模式
-   * @param monitorId 显示器ID
+   * @param monitorId 显示器ID，不传则设置到所有显示器
    * @returns Promise<SetWallpaperResult> 设置结果
    */
   async setLocalWallpaper(
@@ -157,20 +465,20 @@ export const wallpaperApi = {
 
   /**
    * 获取系统当前壁纸信息
-   * @returns Promise<{ filePath: string; fitMode: WallpaperFitMode }> 当前壁纸信息
+   * @returns Promise<CurrentWallpaperInfo> 当前壁纸信息，壁纸在应用外被修改时适配模式为 fill
    */
-  async getCurrentWallpaper(): Promise<{ filePath: string; fitMode: WallpaperFitMode }> {
-    return invoke('get_current_wallpaper');
+  async getCurrentWallpaper(): Promise<CurrentWallpaperInfo> {
+    const result: string = await invoke('get_current_wallpaper');
+    return JSON.parse(result);
   },
 
   /**
-   * 获取可用显示器列表
-   * @returns Promise<Array<{ id: string; name: string; width: number; height: number }>> 显示器数组
+   * 获取可用显示器列表，主显示器排在最前
+   * @returns Promise<MonitorInfo[]> 显示器数组
    */
-  async getMonitors(): Promise<
-    Array<{ id: string; name: string; width: number; height: number }>
-  > {
-    return invoke('get_monitors');
+  async getMonitors(): Promise<MonitorInfo[]> {
+    const result: string = await invoke('get_monitors');
+    return JSON.parse(result);
   },
 
   /**
@@ -178,6 +486,6 @@ export const wallpaperApi = {
    * @returns WallpaperFitMode[] 适配模式数组
    */
   getFitModes(): WallpaperFitMode[] {
-    return ['fill', 'fit', 'stretch', 'tile', 'center'];
+    return ['fill', 'fit', 'stretch', 'tile', 'center', 'span'];
   },
 };
@@ -10,6 +10,7 @@ import { open } from '@tauri-apps/plugin-dialog';
 import { convertFileSrc, invoke } from '@tauri-apps/api/core';
 import { useWallpaperStore } from './store';
 import { Wallpaper } from './types';
+import type { WallpaperImportResult } from '../../types';
 import styles from './styles.module.scss';
 
 import { LocalImage } from './LocalImage';
@@ -69,10 +70,9 @@ export const LocalGallery: React.FC = () => {
       });
 
       if (selected && typeof selected === 'string') {
-        const result = await invoke<string>('import_wallpapers_from_directory', {
+        const { imported, duplicates } = await invoke<WallpaperImportResult>('import_wallpapers_from_directory', {
           directoryPath: selected,
         });
-        const imported = JSON.parse(result);
 
         const newWallpapers: Wallpaper[] = imported.map((w: any) => ({
           id: w.id,
@@ -86,7 +86,11 @@ export const LocalGallery: React.FC = () => {
         }));
 
         addLocalWallpapers(newWallpapers);
-        message.success(`已从目录添加 ${newWallpapers.length} 张壁纸`);
+        message.success(
+          duplicates.length > 0
+            ? `已从目录添加 ${newWallpapers.length} 张壁纸，${duplicates.length} 张与已有壁纸近似重复`
+            : `已从目录添加 ${newWallpapers.length} 张壁纸`
+        );
       }
     } catch (e) {
       console.error(e);
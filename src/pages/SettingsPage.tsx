@@ -163,7 +163,7 @@ export function SettingsPage() {
           <div className="setting-item">
             <div className="setting-label">
               <span className="label-text">扫描忽略目录</span>
-              <span className="label-desc">扫描时跳过的目录名，多个目录用逗号分隔</span>
+              <span className="label-desc">新绑定目录默认跳过的目录名，多个目录用逗号分隔</span>
             </div>
             <Input
               placeholder="例如: node_modules, .git, dist"
@@ -2,32 +2,35 @@ import { invoke } from '@tauri-apps/api/core';
 import { QuickAccessItem, CreateQuickAccessDto, UpdateQuickAccessDto, WebsiteMetadata } from '../types/shortcuts';
 
 export const shortcutsApi = {
-  getShortcuts: async (includeHidden: boolean): Promise<QuickAccessItem[]> => {
-    return invoke('get_shortcuts', { includeHidden });
+  getShortcuts: async (includeHidden: boolean, token?: string | null): Promise<QuickAccessItem[]> => {
+    return invoke('get_shortcuts', { includeHidden, token });
   },
 
-  createShortcut: async (dto: CreateQuickAccessDto): Promise<QuickAccessItem> => {
-    return invoke('create_shortcut', { dto });
+  // 加密项需要携带解锁令牌
+  createShortcut: async (dto: CreateQuickAccessDto, token?: string | null): Promise<QuickAccessItem> => {
+    return invoke('create_shortcut', { dto, token });
   },
 
-  updateShortcut: async (id: string, dto: UpdateQuickAccessDto): Promise<QuickAccessItem> => {
-    return invoke('update_shortcut', { id, dto });
+  updateShortcut: async (id: string, dto: UpdateQuickAccessDto, token?: string | null): Promise<QuickAccessItem> => {
+    return invoke('update_shortcut', { id, dto, token });
   },
 
-  deleteShortcut: async (id: string): Promise<void> => {
-    return invoke('delete_shortcut', { id });
+  // 隐藏项或加密项需要携带解锁令牌
+  deleteShortcut: async (id: string, token?: string | null): Promise<void> => {
+    return invoke('delete_shortcut', { id, token });
   },
 
   openShortcut: async (target: string, kind: string): Promise<void> => {
     return invoke('open_shortcut', { target, kind });
   },
 
-  setGlobalPassword: async (password: string): Promise<void> => {
-    return invoke('set_global_password', { password });
+  setGlobalPassword: async (password: string, currentPassword?: string): Promise<void> => {
+    return invoke('set_global_password', { password, currentPassword });
   },
 
-  verifyPassword: async (password: string): Promise<boolean> => {
-    return invoke('verify_password', { password });
+  // 校验成功返回解锁令牌，失败返回 null
+  verifyPassword: async (password: string, token?: string | null): Promise<string | null> => {
+    return invoke('verify_password', { password, token });
   },
 
   hasPassword: async (): Promise<boolean> => {
@@ -1,12 +1,15 @@
 import { create } from 'zustand';
 import type { Category, CategoryNode, CreateCategoryRequest, UpdateCategoryRequest, DeleteStrategy } from '../types';
 import { categoryApi } from '../api/category';
+import { sessionApi } from '../api/session';
 
 interface CategoryStore {
   categories: CategoryNode[];
   selectedCategory: Category | null;
   loading: boolean;
   error: string | null;
+  // 受密码保护分类的解锁令牌，多个分类共用同一会话
+  unlockToken: string | null;
 
   // Actions
   loadCategories: () => Promise<void>;
@@ -15,6 +18,8 @@ interface CategoryStore {
   deleteCategory: (id: number, strategy: DeleteStrategy) => Promise<void>;
   selectCategory: (category: Category | null) => void;
   reorderCategories: (orders: Array<[number, number]>) => Promise<void>;
+  unlockCategory: (id: number, password: string) => Promise<boolean>;
+  lockCategories: () => Promise<void>;
 }
 
 export const useCategoryStore = create<CategoryStore>((set, get) => ({
@@ -22,6 +27,7 @@ export const useCategoryStore = create<CategoryStore>((set, get) => ({
   selectedCategory: null,
   loading: false,
   error: null,
+  unlockToken: null,
 
   loadCategories: async () => {
     console.log('Loading categories...');
@@ -51,7 +57,7 @@ export const useCategoryStore = create<CategoryStore>((set, get) => ({
   updateCategory: async (id: number, req: UpdateCategoryRequest) => {
     set({ loading: true, error: null });
     try {
-      await categoryApi.updateCategory(id, req);
+      await categoryApi.updateCategory(id, req, get().unlockToken ?? undefined);
       await get().loadCategories();
     } catch (error) {
       set({ error: String(error), loading: false });
@@ -62,7 +68,7 @@ export const useCategoryStore = create<CategoryStore>((set, get) => ({
   deleteCategory: async (id: number, strategy: DeleteStrategy) => {
     set({ loading: true, error: null });
     try {
-      await categoryApi.deleteCategory(id, strategy);
+      await categoryApi.deleteCategory(id, strategy, get().unlockToken ?? undefined);
       await get().loadCategories();
       // 如果删除的是当前选中的分类，清除选中状态
       if (get().selectedCategory?.id === id) {
@@ -81,11 +87,28 @@ export const useCategoryStore = create<CategoryStore>((set, get) => ({
   reorderCategories: async (orders: Array<[number, number]>) => {
     set({ loading: true, error: null });
     try {
-      await categoryApi.reorderCategories(orders);
+      await categoryApi.reorderCategories(orders, get().unlockToken ?? undefined);
       await get().loadCategories();
     } catch (error) {
       set({ error: String(error), loading: false });
       throw error;
     }
   },
+
+  unlockCategory: async (id: number, password: string) => {
+    const token = await categoryApi.verifyCategoryPassword(id, password, get().unlockToken ?? undefined);
+    if (!token) {
+      return false;
+    }
+    set({ unlockToken: token });
+    return true;
+  },
+
+  lockCategories: async () => {
+    const token = get().unlockToken;
+    set({ unlockToken: null });
+    if (token) {
+      await sessionApi.lockSession(token);
+    }
+  },
 }));
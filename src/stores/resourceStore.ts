@@ -1,7 +1,9 @@
 import { create } from 'zustand';
-import type { DirectoryBinding, ResourceItem } from '../types';
+import type { DirectoryBinding, FileKind, ResourceItem } from '../types';
 import { directoryApi } from '../api/directory';
+import { resourceApi } from '../api/resource';
 import { useSettingsStore } from './settingsStore';
+import { useCategoryStore } from './categoryStore';
 
 export interface FileTypeStats {
   fileType: string;
@@ -22,7 +24,6 @@ interface ResourceStore {
   resources: ResourceItem[];
   loading: boolean;
   error: string | null;
-  showHidden: boolean;
   scanProgress: {
     totalFiles: number;
     scannedFiles: number;
@@ -34,19 +35,43 @@ interface ResourceStore {
   removeBinding: (bindingId: number, categoryId?: number) => Promise<void>;
   scanDirectory: (categoryId: number) => Promise<void>;
   scanDirectoryBatch: (categoryId: number) => Promise<void>;
-  setShowHidden: (show: boolean) => void;
   clearResources: () => void;
   getFileTypeStats: () => FileTypeStats[];
   getFileCategoryStats: () => FileCategoryStats[];
   getTotalFileCount: () => number;
 }
 
+const KIND_LABELS: Record<FileKind, { category: string; icon: string }> = {
+  image: { category: '图片', icon: '🖼️' },
+  document: { category: '文档', icon: '📝' },
+  video: { category: '视频', icon: '🎬' },
+  audio: { category: '音频', icon: '🎵' },
+  archive: { category: '压缩包', icon: '📦' },
+  code: { category: '代码', icon: '💻' },
+  executable: { category: '程序', icon: '⚙️' },
+  other: { category: '其他', icon: '📄' },
+};
+
+// 受密码保护的分类需要携带解锁令牌
+function unlockToken(): string | undefined {
+  return useCategoryStore.getState().unlockToken ?? undefined;
+}
+
+// 将逗号分隔的目录名转换为 gitignore 规则，每个目录名一行
+function toIgnoreRules(directories: string): string {
+  return directories
+    .split(',')
+    .map((d) => d.trim())
+    .filter((d) => d.length > 0)
+    .map((d) => `${d}/`)
+    .join('\n');
+}
+
 export const useResourceStore = create<ResourceStore>((set, get) => ({
   bindings: [],
   resources: [],
   loading: false,
   error: null,
-  showHidden: false,
   scanProgress: {
     totalFiles: 0,
     scannedFiles: 0,
@@ -55,7 +80,7 @@ export const useResourceStore = create<ResourceStore>((set, get) => ({
   loadBindings: async (categoryId: number) => {
     set({ loading: true, error: null });
     try {
-      const bindings = await directoryApi.getBindings(categoryId);
+      const bindings = await directoryApi.getBindings(categoryId, unlockToken());
       set({ bindings, loading: false });
     } catch (error) {
       set({ error: String(error), loading: false });
@@ -65,7 +90,12 @@ export const useResourceStore = create<ResourceStore>((set, get) => ({
   addBinding: async (categoryId: number, path: string) => {
     set({ loading: true, error: null });
     try {
-      await directoryApi.bindDirectory(categoryId, path);
+      const binding = await directoryApi.bindDirectory(categoryId, path, unlockToken());
+      // 新绑定的目录以设置中的忽略目录作为初始规则，之后可按绑定单独修改
+      const { scanIgnoreDirectories } = useSettingsStore.getState();
+      await directoryApi.updateBinding(binding.id, {
+        ignore_rules: toIgnoreRules(scanIgnoreDirectories),
+      }, unlockToken());
       await get().loadBindings(categoryId);
       await get().scanDirectory(categoryId);
     } catch (error) {
@@ -77,7 +107,7 @@ export const useResourceStore = create<ResourceStore>((set, get) => ({
   removeBinding: async (bindingId: number, categoryId?: number) => {
     set({ loading: true, error: null });
     try {
-      await directoryApi.unbindDirectory(bindingId);
+      await directoryApi.unbindDirectory(bindingId, unlockToken());
       const newBindings = get().bindings.filter((b) => b.id !== bindingId);
       set({ bindings: newBindings, loading: false });
       if (newBindings.length === 0) {
@@ -94,9 +124,10 @@ export const useResourceStore = create<ResourceStore>((set, get) => ({
   scanDirectory: async (categoryId: number) => {
     set({ loading: true, error: null });
     try {
-      const { scanIgnoreDirectories } = useSettingsStore.getState();
-      const resources = await directoryApi.scanDirectory(categoryId, get().showHidden, scanIgnoreDirectories || undefined);
+      const resources = await directoryApi.scanDirectory(categoryId, unlockToken());
       set({ resources, loading: false });
+      // 媒体元数据在后台提取，不阻塞扫描结果的展示
+      resourceApi.startMetadataJob(categoryId, unlockToken()).catch((error) => console.error('Failed to start metadata job:', error));
     } catch (error) {
       set({ error: String(error), loading: false });
       throw error;
@@ -106,17 +137,11 @@ export const useResourceStore = create<ResourceStore>((set, get) => ({
   scanDirectoryBatch: async (categoryId: number) => {
     set({ loading: true, error: null, resources: [] });
     try {
-      const { scanIgnoreDirectories } = useSettingsStore.getState();
       const batchSize = 1000;
       let isComplete = false;
 
       while (!isComplete) {
-        const progress = await directoryApi.scanDirectoryBatch(
-          categoryId,
-          get().showHidden,
-          scanIgnoreDirectories || undefined,
-          batchSize
-        );
+        const progress = await directoryApi.scanDirectoryBatch(categoryId, batchSize, unlockToken());
 
         set((state) => ({
           resources: [...state.resources, ...progress.current_batch],
@@ -134,16 +159,13 @@ export const useResourceStore = create<ResourceStore>((set, get) => ({
       }
 
       set({ loading: false });
+      resourceApi.startMetadataJob(categoryId, unlockToken()).catch((error) => console.error('Failed to start metadata job:', error));
     } catch (error) {
       set({ error: String(error), loading: false });
       throw error;
     }
   },
 
-  setShowHidden: (show: boolean) => {
-    set({ showHidden: show });
-  },
-
   clearResources: () => {
     set({ resources: [] });
   },
@@ -152,33 +174,9 @@ export const useResourceStore = create<ResourceStore>((set, get) => ({
     const { resources } = get();
     const categoryMap = new Map<string, { count: number; totalSize: number; icon: string }>();
     
-    const imageTypes = ['jpg', 'jpeg', 'png', 'gif', 'bmp', 'webp', 'svg', 'ico', 'tiff'];
-    const documentTypes = ['doc', 'docx', 'pdf', 'txt', 'xls', 'xlsx', 'ppt', 'pptx', 'rtf', 'odt', 'ods'];
-    const videoTypes = ['mp4', 'avi', 'mov', 'mkv', 'flv', 'wmv', 'webm', 'm4v', '3gp'];
-    const audioTypes = ['mp3', 'wav', 'flac', 'aac', 'ogg', 'wma', 'm4a', 'opus'];
-    const archiveTypes = ['zip', 'rar', '7z', 'tar', 'gz', 'bz2'];
     
     resources.forEach((resource) => {
-      const fileType = resource.file_type.toLowerCase();
-      let category = '其他';
-      let icon = '📄';
-      
-      if (imageTypes.includes(fileType)) {
-        category = '图片';
-        icon = '🖼️';
-      } else if (documentTypes.includes(fileType)) {
-        category = '文档';
-        icon = '📝';
-      } else if (videoTypes.includes(fileType)) {
-        category = '视频';
-        icon = '🎬';
-      } else if (audioTypes.includes(fileType)) {
-        category = '音频';
-        icon = '🎵';
-      } else if (archiveTypes.includes(fileType)) {
-        category = '压缩包';
-        icon = '📦';
-      }
+      const { category, icon } = KIND_LABELS[resource.kind] ?? KIND_LABELS.other;
       
       const existing = categoryMap.get(category) || { count: 0, totalSize: 0, icon };
       categoryMap.set(category, {
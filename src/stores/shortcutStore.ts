@@ -7,6 +7,7 @@ interface ShortcutState {
   items: QuickAccessItem[];
   loading: boolean;
   hasPasswordSet: boolean;
+  unlockToken: string | null;
   activeTab: 'website' | 'directory';
   
   // Actions
@@ -26,6 +27,7 @@ export const useShortcutStore = create<ShortcutState>((set, get) => ({
   items: [],
   loading: false,
   hasPasswordSet: false,
+  unlockToken: null,
   activeTab: 'website',
 
   setActiveTab: (tab) => set({ activeTab: tab }),
@@ -33,8 +35,16 @@ export const useShortcutStore = create<ShortcutState>((set, get) => ({
   fetchShortcuts: async () => {
     set({ loading: true });
     try {
-      // 在管理页面显示所有项目（包括隐藏的）
-      const items = await shortcutsApi.getShortcuts(true);
+      // 解锁后在管理页面显示所有项目（包括隐藏的）
+      const token = get().unlockToken;
+      let items: QuickAccessItem[];
+      try {
+        items = await shortcutsApi.getShortcuts(!!token, token);
+      } catch {
+        // 会话已过期，回退为仅显示非隐藏项目
+        set({ unlockToken: null });
+        items = await shortcutsApi.getShortcuts(false);
+      }
       set({ items });
     } catch (error) {
       console.error('Failed to fetch shortcuts:', error);
@@ -55,7 +65,7 @@ export const useShortcutStore = create<ShortcutState>((set, get) => ({
 
   createShortcut: async (data) => {
     try {
-      await shortcutsApi.createShortcut(data);
+      await shortcutsApi.createShortcut(data, get().unlockToken);
       message.success('添加成功');
       await get().fetchShortcuts();
       return true;
@@ -68,7 +78,7 @@ export const useShortcutStore = create<ShortcutState>((set, get) => ({
 
   updateShortcut: async (id, data) => {
     try {
-      await shortcutsApi.updateShortcut(id, data);
+      await shortcutsApi.updateShortcut(id, data, get().unlockToken);
       message.success('更新成功');
       await get().fetchShortcuts();
       return true;
@@ -81,7 +91,7 @@ export const useShortcutStore = create<ShortcutState>((set, get) => ({
 
   deleteShortcut: async (id) => {
     try {
-      await shortcutsApi.deleteShortcut(id);
+      await shortcutsApi.deleteShortcut(id, get().unlockToken);
       message.success('删除成功');
       await get().fetchShortcuts();
       return true;
@@ -114,7 +124,9 @@ export const useShortcutStore = create<ShortcutState>((set, get) => ({
   setGlobalPassword: async (password) => {
     try {
       await shortcutsApi.setGlobalPassword(password);
-      set({ hasPasswordSet: true });
+      // 修改密码后所有会话都会被锁定，需要用新密码重新解锁
+      set({ hasPasswordSet: true, unlockToken: null });
+      await get().fetchShortcuts();
       message.success('密码设置成功');
       return true;
     } catch (error) {
@@ -126,7 +138,13 @@ export const useShortcutStore = create<ShortcutState>((set, get) => ({
 
   verifyPassword: async (password) => {
     try {
-      return await shortcutsApi.verifyPassword(password);
+      const token = await shortcutsApi.verifyPassword(password, get().unlockToken);
+      if (!token) {
+        return false;
+      }
+      set({ unlockToken: token });
+      await get().fetchShortcuts();
+      return true;
     } catch (error) {
       console.error('Failed to verify password:', error);
       return false;
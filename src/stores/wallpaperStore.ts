@@ -2,11 +2,13 @@ import { create } from 'zustand';
 import { persist, createJSONStorage } from 'zustand/middleware';
 import type {
   LocalWallpaper,
+  LocalWallpaperQuery,
   RemoteWallpaper,
   Wallpaper,
   WallpaperCategory,
   WallpaperDisplayMode,
   WallpaperFitMode,
+  WallpaperImportResult,
   WallpaperPreviewState,
 } from '../types';
 import { wallpaperApi } from '../api/wallpaper';
@@ -20,6 +22,10 @@ interface WallpaperState {
   localWallpapers: LocalWallpaper[];
   localWallpapersLoading: boolean;
   localWallpapersError: string | null;
+  localWallpaperQuery: LocalWallpaperQuery;
+  localWallpapersPage: number;
+  localWallpapersTotal: number;
+  localWallpapersHasMore: boolean;
 
   // 壁纸站
   stationCategories: WallpaperCategory[];
@@ -41,8 +47,8 @@ interface WallpaperState {
   settingWallpaperError: string | null;
 
   // 操作方法
-  loadLocalWallpapers: () => Promise<void>;
-  importWallpapers: (filePaths: string[]) => Promise<LocalWallpaper[]>;
+  loadLocalWallpapers: (query?: LocalWallpaperQuery, append?: boolean) => Promise<void>;
+  importWallpapers: (filePaths: string[]) => Promise<WallpaperImportResult>;
   deleteLocalWallpaper: (id: string) => Promise<void>;
   deleteLocalWallpapers: (ids: string[]) => Promise<void>;
 
@@ -79,12 +85,31 @@ export const useWallpaperStore = create<WallpaperState>()(
       localWallpapers: [],
       localWallpapersLoading: false,
       localWallpapersError: null,
+      localWallpaperQuery: {},
+      localWallpapersPage: 1,
+      localWallpapersTotal: 0,
+      localWallpapersHasMore: false,
 
-      loadLocalWallpapers: async () => {
+      loadLocalWallpapers: async (query, append = false) => {
+        const state = get();
         set({ localWallpapersLoading: true, localWallpapersError: null });
+
+        // 不传查询条件时沿用上次的筛选与排序
+        const nextQuery = query ?? state.localWallpaperQuery;
+        const page = append ? state.localWallpapersPage + 1 : 1;
+
         try {
-          const wallpapers = await wallpaperApi.getLocalWallpapers();
-          set({ localWallpapers: wallpapers, localWallpapersLoading: false });
+          const response = await wallpaperApi.getLocalWallpapers({ ...nextQuery, page });
+          set((prevState) => ({
+            localWallpapers: append
+              ? [...prevState.localWallpapers, ...response.wallpapers]
+              : response.wallpapers,
+            localWallpaperQuery: nextQuery,
+            localWallpapersPage: page,
+            localWallpapersTotal: response.total,
+            localWallpapersHasMore: response.has_more,
+            localWallpapersLoading: false,
+          }));
         } catch (error) {
           set({ localWallpapersError: String(error), localWallpapersLoading: false });
         }
@@ -94,13 +119,22 @@ export const useWallpaperStore = create<WallpaperState>()(
         set({ localWallpapersLoading: true, localWallpapersError: null });
         try {
           console.log('[wallpaperStore] 开始导入，文件数量:', filePaths.length);
-          const imported = await wallpaperApi.importWallpapers(filePaths);
-          console.log('[wallpaperStore] 导入完成，返回数量:', imported.length);
+          const result = await wallpaperApi.importWallpapers(filePaths);
+          const { imported, duplicates } = result;
+          console.log('[wallpaperStore] 导入完成，返回数量:', imported.length, '近似重复:', duplicates.length);
+          // 合并的近似重复图片会更新已有壁纸的文件与分辨率
+          const merged = new Map(
+            duplicates.filter((d) => d.action === 'merge').map((d) => [d.existing.id, d.existing])
+          );
           set((state) => ({
-            localWallpapers: [...imported, ...state.localWallpapers],
+            localWallpapers: [
+              ...imported,
+              ...state.localWallpapers.map((w) => merged.get(w.id) ?? w),
+            ],
+            localWallpapersTotal: state.localWallpapersTotal + imported.length,
             localWallpapersLoading: false,
           }));
-          return imported;
+          return result;
         } catch (error) {
           set({ localWallpapersError: String(error), localWallpapersLoading: false });
           throw error;
@@ -112,6 +146,7 @@ export const useWallpaperStore = create<WallpaperState>()(
           await wallpaperApi.deleteLocalWallpaper(id);
           set((state) => ({
             localWallpapers: state.localWallpapers.filter((w) => w.id !== id),
+            localWallpapersTotal: Math.max(0, state.localWallpapersTotal - 1),
             selectedWallpaperIds: (() => {
               const newSet = new Set(state.selectedWallpaperIds);
               newSet.delete(id);
@@ -129,6 +164,7 @@ export const useWallpaperStore = create<WallpaperState>()(
           await wallpaperApi.deleteLocalWallpapers(ids);
           set((state) => ({
             localWallpapers: state.localWallpapers.filter((w) => !ids.includes(w.id)),
+            localWallpapersTotal: Math.max(0, state.localWallpapersTotal - ids.length),
             selectedWallpaperIds: (() => {
               const newSet = new Set(state.selectedWallpaperIds);
               ids.forEach((id) => newSet.delete(id));
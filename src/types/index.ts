@@ -19,6 +19,36 @@ export interface CategoryNode extends Category {
   resource_count: number;
 }
 
+// 容量树节点，分类节点的子节点为子分类和绑定目录
+// 文件夹节点的 id 为所属绑定目录的 ID
+export interface UsageNode {
+  id: number;
+  name: string;
+  kind: 'category' | 'binding' | 'folder';
+  // 绑定目录和文件夹的完整路径，分类节点为 null
+  path: string | null;
+  total_bytes: number;
+  file_count: number;
+  children: UsageNode[];
+}
+
+export interface CategoryStats {
+  total_bytes: number;
+  file_count: number;
+  tree: UsageNode[];
+  bindings: Array<{
+    binding_id: number;
+    category_id: number;
+    directory_path: string;
+    total_bytes: number;
+    file_count: number;
+  }>;
+  file_types: Array<{ file_type: string; total_bytes: number; file_count: number }>;
+  kinds: Array<{ kind: FileKind; total_bytes: number; file_count: number }>;
+  largest_files: ResourceItem[];
+  largest_folders: Array<{ path: string; category_id: number; total_bytes: number; file_count: number }>;
+}
+
 export interface CreateCategoryRequest {
   name: string;
   description?: string;
@@ -42,6 +72,21 @@ export interface DirectoryBinding {
   category_id: number;
   directory_path: string;
   created_at: string;
+  watch_enabled: boolean;
+  // gitignore 语法的忽略规则，每行一条
+  ignore_rules: string;
+  show_hidden: boolean;
+  follow_symlinks: boolean;
+  // 最大扫描深度，null 表示不限制
+  max_depth: number | null;
+}
+
+// 未提供的字段保持不变，max_depth 为 0 表示不限制深度
+export interface UpdateBindingRequest {
+  show_hidden?: boolean;
+  ignore_rules?: string;
+  follow_symlinks?: boolean;
+  max_depth?: number;
 }
 
 export interface ResourceItem {
@@ -49,10 +94,15 @@ export interface ResourceItem {
   file_name: string;
   file_path: string;
   file_size: number;
+  // 能通过文件头识别时以实际内容为准
   file_type: string;
+  mime_type: string;
+  kind: FileKind;
   modified_at: string;
 }
 
+export type FileKind = 'image' | 'video' | 'audio' | 'archive' | 'document' | 'code' | 'executable' | 'other';
+
 export interface SearchResult {
   resource: ResourceItem;
   category_name: string;
@@ -68,9 +118,9 @@ export interface SearchResult {
 export type WallpaperSource = 'local' | 'remote';
 
 /**
- * 壁纸适配模式
+ * 壁纸适配模式，span 表示一张图片横跨所有显示器
  */
-export type WallpaperFitMode = 'fill' | 'fit' | 'stretch' | 'tile' | 'center';
+export type WallpaperFitMode = 'fill' | 'fit' | 'stretch' | 'tile' | 'center' | 'span';
 
 /**
  * 壁纸显示模式
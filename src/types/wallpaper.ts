@@ -1,3 +1,5 @@
+import type { WallpaperFitMode } from './index';
+
 export interface LocalWallpaper {
   id: string;
   name: string;
@@ -9,6 +11,14 @@ export interface LocalWallpaper {
   height?: number;
   modified_at: string;
   created_at: string;
+  // 由壁纸文件夹同步导入时为所属文件夹ID
+  folder_id?: number | null;
+  // 文件已被移动或删除
+  missing?: boolean;
+  favorite?: boolean;
+  // 评分 1-5，0 表示未评分
+  rating?: number;
+  tags?: string[];
   // Compatibility fields for UI if needed, but try to use file_path
   url?: string; 
   title?: string;
@@ -37,6 +47,222 @@ export interface WallpaperStationResponse {
   has_more: boolean;
 }
 
+// 位置与尺寸均为物理像素，位置相对于主显示器左上角
+export interface MonitorInfo {
+  // 系统提供的显示器名称，重启后保持不变，可作为 set_wallpaper 的 monitorId
+  id: string;
+  name: string;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  scale_factor: number;
+  is_primary: boolean;
+}
+
+// 宽高未知的壁纸不会匹配任何尺寸条件
+export interface WallpaperFilter {
+  min_width?: number;
+  min_height?: number;
+  // 宽高比（宽/高），如 16:9 约为 1.778
+  aspect_ratio?: number;
+  // 宽高比允许的相对误差，默认 0.02
+  aspect_tolerance?: number;
+  // 只返回宽高均不小于该分辨率且宽高比一致的壁纸，如 3440x1440 的显示器
+  fits_width?: number;
+  fits_height?: number;
+  // 只返回该来源文件夹中的壁纸
+  folder_id?: number;
+  // 按文件是否已丢失筛选，不传时不限
+  missing?: boolean;
+  // 名称或文件名中包含的关键字
+  keyword?: string;
+  // 同时带有这些标签的壁纸
+  tag_ids?: number[];
+  collection_id?: number;
+  favorite?: boolean;
+  // 评分不低于该值的壁纸
+  min_rating?: number;
+}
+
+export type WallpaperSortField = 'created_at' | 'name' | 'file_size' | 'resolution' | 'rating' | 'modified_at';
+
+// 未指定方向时按名称升序，其余字段降序
+export interface WallpaperSort {
+  field?: WallpaperSortField;
+  direction?: 'asc' | 'desc';
+}
+
+export interface LocalWallpaperPage {
+  wallpapers: LocalWallpaper[];
+  // 符合筛选条件的总数
+  total: number;
+  page: number;
+  page_size: number;
+  has_more: boolean;
+}
+
+// 导入时遇到近似重复图片的处理方式：照常导入 / 不导入 / 合并到已有壁纸
+export type NearDuplicateAction = 'allow' | 'refuse' | 'merge';
+
+export interface WallpaperDedupSettings {
+  // 感知哈希相似度（0.75-1），不低于该值视为近似重复
+  similarity: number;
+  action: NearDuplicateAction;
+}
+
+export interface WallpaperImportDuplicate {
+  file_path: string;
+  // 与之近似重复的已有壁纸（合并后为更新后的记录）
+  existing: LocalWallpaper;
+  similarity: number;
+  action: NearDuplicateAction;
+}
+
+export interface WallpaperImportResult {
+  imported: LocalWallpaper[];
+  duplicates: WallpaperImportDuplicate[];
+}
+
+export interface WallpaperDuplicateCluster {
+  // 组内相连两张图片之间的最低相似度
+  similarity: number;
+  // 按分辨率从高到低排列
+  wallpapers: LocalWallpaper[];
+}
+
+export interface LocalWallpaperQuery {
+  filter?: WallpaperFilter;
+  sort?: WallpaperSort;
+  // 从1开始
+  page?: number;
+  pageSize?: number;
+}
+
+export interface WallpaperTag {
+  id: number;
+  name: string;
+  wallpaper_count: number;
+}
+
+export interface WallpaperCollection {
+  id: number;
+  name: string;
+  description: string | null;
+  wallpaper_count: number;
+  // 最早加入合集的壁纸，用作封面
+  cover_wallpaper_id: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface WallpaperCollectionInput {
+  name: string;
+  description?: string | null;
+}
+
+export interface WallpaperBackfillResult {
+  updated: number;
+  // 文件已不存在的记录数
+  missing: number;
+  // 无法解析宽高的记录数
+  failed: number;
+}
+
+export interface MonitorWallpaper {
+  monitor_id: string;
+  file_path: string;
+  fit_mode: WallpaperFitMode;
+}
+
+export interface CurrentWallpaperInfo {
+  // 设置到所有显示器的壁纸；按显示器分别设置时为拼接后的图片
+  file_path: string;
+  fit_mode: WallpaperFitMode;
+  // 按显示器单独设置的壁纸
+  monitors: MonitorWallpaper[];
+}
+
+// flag 为保留记录并标记丢失，remove 为直接从壁纸库移除
+export type WallpaperFolderMissingPolicy = 'flag' | 'remove';
+
+export interface WallpaperFolder {
+  id: number;
+  path: string;
+  // 是否包含子文件夹
+  recursive: boolean;
+  // 宽或高小于该值的图片不会导入
+  min_width: number | null;
+  min_height: number | null;
+  // 是否实时监听文件夹变化
+  watch_enabled: boolean;
+  missing_policy: WallpaperFolderMissingPolicy;
+  last_synced_at: string | null;
+  created_at: string;
+}
+
+export interface WallpaperFolderInput {
+  // 修改时不会变更路径
+  path: string;
+  recursive?: boolean;
+  min_width?: number | null;
+  min_height?: number | null;
+  watch_enabled?: boolean;
+  missing_policy?: WallpaperFolderMissingPolicy;
+}
+
+// 同步结果，也是 wallpapers://changed 事件的负载
+export interface WallpaperFolderSyncResult {
+  folder_id: number;
+  added: number;
+  // 文件内容变化后重新读取了宽高的记录数
+  updated: number;
+  // 新标记为丢失的记录数
+  missing: number;
+  // 文件重新出现后取消丢失标记的记录数
+  restored: number;
+  // 按 remove 策略删除的记录数
+  removed: number;
+  // 不再满足递归或分辨率条件而解除与文件夹关联的记录数，记录仍保留在壁纸库中
+  detached: number;
+  // 分辨率不足而未导入的图片数
+  skipped: number;
+}
+
+// json_feed 为 JSON 订阅，rss_feed 为 RSS 2.0 或 Atom 订阅
+export type WallpaperSourceKind = 'json_feed' | 'rss_feed';
+
+export interface WallpaperSourceConfig {
+  // 新增时留空，由后端生成
+  id?: string;
+  name: string;
+  kind: WallpaperSourceKind;
+  url: string;
+}
+
+export interface WallpaperSourceSettings {
+  // 壁纸站当前使用的壁纸源，未配置时为 null
+  active_id: string | null;
+  sources: Required<WallpaperSourceConfig>[];
+}
+
+export type WallpaperDownloadState = 'downloading' | 'verifying' | 'completed' | 'failed';
+
+// wallpaper://download 事件的负载
+export interface WallpaperDownloadProgress {
+  // 壁纸站中的壁纸ID
+  wallpaper_id: string;
+  state: WallpaperDownloadState;
+  downloaded_bytes: number;
+  // 服务器未返回大小时为 null
+  total_bytes: number | null;
+  // 是否从上次中断处继续下载
+  resumed: boolean;
+  // 导入后的本地壁纸，仅在 completed 时有值
+  wallpaper: LocalWallpaper | null;
+  error: string | null;
+}
+
 export interface SetWallpaperResult {
   success: boolean;
   message?: string;